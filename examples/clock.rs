@@ -0,0 +1,9 @@
+//! Chapter 4: draws the hour marks of an analog clock to
+//! `analog_clock.ppm`
+
+use anyhow::Result;
+use raytracer::{export::write_canvas, scenes};
+
+fn main() -> Result<()> {
+    write_canvas("./analog_clock.ppm", &scenes::analog_clock(500)?)
+}
@@ -0,0 +1,10 @@
+//! Chapter 6: renders a sphere shaded with the Phong reflection model
+//! to `cast_rays3d.ppm`
+
+use anyhow::Result;
+use raytracer::{export::write_canvas, scenes};
+
+fn main() -> Result<()> {
+    println!("It's time to render your first 3D sphere!");
+    write_canvas("./cast_rays3d.ppm", &scenes::phong_sphere(100)?)
+}
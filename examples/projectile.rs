@@ -0,0 +1,8 @@
+//! Chapter 2: plots the trajectory of a projectile to `projectile.ppm`
+
+use anyhow::Result;
+use raytracer::{export::write_canvas, scenes};
+
+fn main() -> Result<()> {
+    write_canvas("./projectile.ppm", &scenes::projectile(900, 550)?)
+}
@@ -0,0 +1,9 @@
+//! Chapter 5: casts rays at a sphere and draws its silhouette to
+//! `cast_rays.ppm`
+
+use anyhow::Result;
+use raytracer::{export::write_canvas, scenes};
+
+fn main() -> Result<()> {
+    write_canvas("./cast_rays.ppm", &scenes::sphere_silhouette(100)?)
+}
@@ -0,0 +1,10 @@
+//! Chapter 7: renders the spheres in a room through a camera to
+//! `world.ppm`
+
+use anyhow::Result;
+use raytracer::{export::write_canvas, scenes};
+
+fn main() -> Result<()> {
+    let (world, camera) = scenes::chapter7()?;
+    write_canvas("./world.ppm", &camera.render(&world)?)
+}
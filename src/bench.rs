@@ -0,0 +1,103 @@
+use crate::{
+    camera::{BenchmarkReport, Camera},
+    scenes::{self, SceneFn},
+};
+use anyhow::{Error, Result};
+
+/// The standard benchmark scenes, from the cheapest to the most
+/// expensive to render. Their names are the ones they are registered
+/// under in [scenes::registry].
+pub fn scenes() -> Vec<(&'static str, SceneFn)> {
+    vec![
+        ("default-world", scenes::default_world),
+        ("chapter7", scenes::chapter7),
+        ("sphere-grid", scenes::sphere_grid),
+    ]
+}
+
+/// Renders the scene once at `size` x `size` pixels, with every other
+/// setting of its camera, and reports how long it took
+///
+/// ```
+/// use raytracer::{bench, scenes};
+///
+/// let report = bench::run(scenes::default_world, 8).unwrap();
+/// assert_eq!(report.primary_rays, 64);
+/// ```
+pub fn run(scene: SceneFn, size: usize) -> Result<BenchmarkReport> {
+    run_many(scene, size, 1)
+}
+
+/// Same as [run], but renders the scene `runs` times
+pub fn run_many(scene: SceneFn, size: usize, runs: usize) -> Result<BenchmarkReport> {
+    let (world, camera) = scene()?;
+    sized(&camera, size)?.benchmark(&world, runs)
+}
+
+/// A copy of the camera rendering `size` x `size` pixels
+fn sized(camera: &Camera, size: usize) -> Result<Camera> {
+    if size == 0 {
+        return Err(Error::msg("A benchmark image needs at least one pixel"));
+    }
+
+    let mut sized = *camera;
+    sized.resize(size, size)?;
+    Ok(sized)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, run_many, scenes, sized};
+    use crate::{
+        camera::{Camera, FovAxis},
+        scenes::by_name,
+        utils::rng::SamplerKind,
+    };
+    use anyhow::Result;
+    use std::{f64::consts::PI, time::Duration};
+
+    #[test]
+    fn every_benchmark_scene_renders_with_plausible_stats() -> Result<()> {
+        for (name, scene) in scenes() {
+            assert!(by_name(name).is_some(), "{} is not registered", name);
+
+            let report = run(scene, 8)?;
+            assert_eq!(report.size, (8, 8));
+            assert_eq!(report.primary_rays, 64);
+            assert_eq!(report.runs.len(), 1);
+            assert!(report.fastest() > Duration::ZERO);
+            assert!(report.runs[0].timings.total() <= report.runs[0].elapsed);
+            assert!(report
+                .to_string()
+                .starts_with("8x8: 64 primary rays, 1 runs, "));
+        }
+
+        assert_eq!(run_many(crate::scenes::default_world, 4, 3)?.runs.len(), 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn benchmarks_keep_every_camera_setting_but_the_size() -> Result<()> {
+        let mut camera = Camera::new(160, 90, PI / 3.0)?;
+        camera.set_fov_axis(FovAxis::Vertical);
+        camera.set_sampler(SamplerKind::Stratified);
+        camera.set_samples_per_pixel(4)?;
+        camera.set_seed(7);
+
+        let small = sized(&camera, 8)?;
+        assert_eq!((small.get_hsize(), small.get_vsize()), (8, 8));
+        assert_eq!(small.get_fov_axis(), FovAxis::Vertical);
+        assert_eq!(small.get_sampler(), SamplerKind::Stratified);
+        assert_eq!(small.get_samples_per_pixel(), 4);
+        assert_eq!(small.get_seed(), 7);
+        assert_eq!(small.get_field_of_view(), PI / 3.0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn benchmarks_need_pixels() {
+        assert!(run(crate::scenes::default_world, 0).is_err());
+    }
+}
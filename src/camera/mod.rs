@@ -1,11 +1,23 @@
 use crate::{
     canvas::Canvas,
+    color::Color,
+    error::RayTracerError,
     intersections::Ray,
-    matrix::{inverse_4x4, Matrix},
+    matrix::Matrix,
     spatial::Tuple,
+    utils::{
+        float_equals,
+        rng::{HaltonSampler, RandomSampler, Sampler, SamplerKind, StratifiedSampler},
+    },
     world::World,
 };
-use anyhow::Result;
+use anyhow::{Error, Result};
+use std::f64::consts::PI;
+
+mod stereo;
+mod timings;
+use timings::timed;
+pub use timings::{BenchmarkReport, BenchmarkRun, RenderTimings};
 
 #[derive(Debug, Clone, Copy)]
 /// Data structure that represents a camera that can
@@ -14,41 +26,206 @@ pub struct Camera {
     hsize: usize,
     vsize: usize,
     field_of_view: f64,
+    fov_axis: FovAxis,
     transform: Matrix<4, 4>,
     pixel_size: f64,
     half_width: f64,
     half_height: f64,
+    samples_per_pixel: usize,
+    seed: u64,
+    sampler: SamplerKind,
+    integrator: RenderIntegrator,
+    transparent_background: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// The method used to find the color of each camera ray
+pub enum RenderIntegrator {
+    /// Direct lighting from the world's point light using the Phong
+    /// reflection model, with shadows
+    #[default]
+    Phong,
+    /// Global illumination by averaging `spp` randomly bounced paths
+    /// per pixel, lit only by emissive materials.
+    /// See [World::color_at_pathtraced].
+    PathTrace { spp: usize },
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// The side of the image that the field of view of a [Camera] spans.
+/// The other side follows from the aspect ratio.
+pub enum FovAxis {
+    /// Whichever side has more pixels, so the whole field of view is
+    /// always visible
+    #[default]
+    Larger,
+    /// The width of the image, so the view gets taller or shorter as
+    /// the height changes
+    Horizontal,
+    /// The height of the image, so the view gets wider or narrower as
+    /// the width changes, as in most film and game cameras
+    Vertical,
+}
+
+impl Default for Camera {
+    /// A 100x100 camera with a field of view of π/3, at the origin and
+    /// looking down -z
+    fn default() -> Self {
+        Self::new_unchecked(100, 100, PI / 3.0)
+    }
 }
 
 impl Camera {
+    /// The most bounces a path takes with [RenderIntegrator::PathTrace]
+    const MAX_PATH_DEPTH: usize = 8;
+
     /// Create a new camera of size with a specific configuration of it's
     /// size and field of view
-    pub fn new(hsize: usize, vsize: usize, field_of_view: f64) -> Self {
-        let half_view = (field_of_view / 2.0).tan();
-        let aspect = hsize as f64 / vsize as f64;
+    ///
+    /// Note: This returns a result because it validates that the camera
+    /// is at least one pixel wide and tall, and that the field of view
+    /// is an angle between 0 and π (exclusive).
+    ///
+    /// ```
+    /// use raytracer::camera::Camera;
+    /// use std::f64::consts::PI;
+    ///
+    /// assert!(Camera::new(160, 120, PI / 2.0).is_ok());
+    /// assert!(Camera::new(0, 120, PI / 2.0).is_err());
+    /// assert!(Camera::new(160, 120, PI).is_err());
+    /// ```
+    pub fn new(hsize: usize, vsize: usize, field_of_view: f64) -> Result<Self> {
+        Self::validate_size(hsize, vsize)?;
+        Self::validate_field_of_view(field_of_view)?;
 
-        let half_width;
-        let half_height;
+        Ok(Self::new_unchecked(hsize, vsize, field_of_view))
+    }
 
-        if aspect >= 1.0 {
-            half_width = half_view;
-            half_height = half_view / aspect;
-        } else {
-            half_width = half_view * aspect;
-            half_height = half_view;
+    /// Create a new camera `hsize` pixels wide, and as tall as the
+    /// `aspect` ratio (width over height) makes it, rounded to the
+    /// nearest pixel. This is the same camera [Camera::new] makes for
+    /// that width and height.
+    ///
+    /// Note: This returns a result because it validates that the aspect
+    /// ratio is positive, as well as everything [Camera::new] checks.
+    ///
+    /// ```
+    /// use raytracer::camera::Camera;
+    /// use std::f64::consts::PI;
+    ///
+    /// let c = Camera::with_aspect(1920, 16.0 / 9.0, PI / 3.0).unwrap();
+    /// assert_eq!((c.get_hsize(), c.get_vsize()), (1920, 1080));
+    ///
+    /// assert!(Camera::with_aspect(1920, 0.0, PI / 3.0).is_err());
+    /// ```
+    pub fn with_aspect(hsize: usize, aspect: f64, field_of_view: f64) -> Result<Self> {
+        // also rejects NaN
+        if !(aspect > 0.0 && aspect.is_finite()) {
+            return Err(Error::msg(format!(
+                "The aspect ratio must be a positive number, but got {}",
+                aspect
+            )));
         }
 
-        let pixel_size = (half_width * 2.0) / hsize as f64;
+        let vsize = (hsize as f64 / aspect).round() as usize;
+        Self::new(hsize, vsize, field_of_view)
+    }
 
-        Self {
+    /// Builds a camera from inputs that are already known to be valid
+    fn new_unchecked(hsize: usize, vsize: usize, field_of_view: f64) -> Self {
+        let mut camera = Self {
             hsize,
             vsize,
             field_of_view,
+            fov_axis: FovAxis::Larger,
             transform: Matrix::<4, 4>::identity(),
-            pixel_size,
-            half_width,
-            half_height,
+            pixel_size: 0.0,
+            half_width: 0.0,
+            half_height: 0.0,
+            samples_per_pixel: 1,
+            seed: 0,
+            sampler: SamplerKind::Random,
+            integrator: RenderIntegrator::Phong,
+            transparent_background: false,
+        };
+        camera.recompute();
+        camera
+    }
+
+    fn validate_size(hsize: usize, vsize: usize) -> Result<()> {
+        if hsize == 0 || vsize == 0 {
+            return Err(Error::msg(format!(
+                "A camera must be at least 1x1 pixels, but got {}x{}",
+                hsize, vsize
+            )));
+        }
+        Ok(())
+    }
+
+    fn validate_field_of_view(field_of_view: f64) -> Result<()> {
+        // also rejects NaN
+        if !(field_of_view > 0.0 && field_of_view < PI) {
+            return Err(Error::msg(format!(
+                "The field of view must be between 0 and π radians, but got {}",
+                field_of_view
+            )));
         }
+        Ok(())
+    }
+
+    /// Updates the pixel size and the half extents of the view, which
+    /// are derived from the size and field of view of the camera
+    fn recompute(&mut self) {
+        let half_view = (self.field_of_view / 2.0).tan();
+        let aspect = self.aspect();
+
+        let horizontal = match self.fov_axis {
+            FovAxis::Larger => aspect >= 1.0,
+            FovAxis::Horizontal => true,
+            FovAxis::Vertical => false,
+        };
+
+        if horizontal {
+            self.half_width = half_view;
+            self.half_height = half_view / aspect;
+        } else {
+            self.half_width = half_view * aspect;
+            self.half_height = half_view;
+        }
+
+        self.pixel_size = (self.half_width * 2.0) / self.hsize as f64;
+    }
+
+    /// Returns a copy of this camera with its width and height divided
+    /// by `factor`, looking at the same view. This is handy for a
+    /// quick, low resolution preview of a render.
+    ///
+    /// ```
+    /// use raytracer::camera::Camera;
+    /// use std::f64::consts::PI;
+    ///
+    /// let c = Camera::new(400, 200, PI / 2.0).unwrap();
+    /// let preview = c.scaled(4);
+    ///
+    /// assert_eq!((preview.get_hsize(), preview.get_vsize()), (100, 50));
+    /// ```
+    pub fn scaled(&self, factor: usize) -> Camera {
+        let factor = factor.max(1);
+        let hsize = (self.hsize / factor).max(1);
+        let vsize = (self.vsize / factor).max(1);
+
+        let mut camera = Self {
+            fov_axis: self.fov_axis,
+            transform: self.transform,
+            samples_per_pixel: self.samples_per_pixel,
+            seed: self.seed,
+            sampler: self.sampler,
+            integrator: self.integrator,
+            transparent_background: self.transparent_background,
+            ..Self::new_unchecked(hsize, vsize, self.field_of_view)
+        };
+        camera.recompute();
+        camera
     }
 
     /// Get the width of the camera
@@ -66,11 +243,78 @@ impl Camera {
         self.field_of_view
     }
 
+    /// Get the side of the image that the field of view spans
+    pub fn get_fov_axis(&self) -> FovAxis {
+        self.fov_axis
+    }
+
+    /// Sets the side of the image that the field of view spans.
+    ///
+    /// ```
+    /// use raytracer::camera::{Camera, FovAxis};
+    /// use std::f64::consts::PI;
+    ///
+    /// // at 16:9 the view is only π/2 wide by default, but pinning the
+    /// // vertical field of view makes it π/2 tall instead
+    /// let mut c = Camera::new(160, 90, PI / 2.0).unwrap();
+    /// c.set_fov_axis(FovAxis::Vertical);
+    ///
+    /// assert!((c.get_pixel_size() * 90.0 - 2.0).abs() < 1e-9);
+    /// ```
+    pub fn set_fov_axis(&mut self, fov_axis: FovAxis) {
+        self.fov_axis = fov_axis;
+        self.recompute();
+    }
+
+    /// Sets the width of the camera, keeping its height and field of view
+    pub fn set_hsize(&mut self, hsize: usize) -> Result<()> {
+        self.resize(hsize, self.vsize)
+    }
+
+    /// Sets the height of the camera, keeping its width and field of view
+    pub fn set_vsize(&mut self, vsize: usize) -> Result<()> {
+        self.resize(self.hsize, vsize)
+    }
+
+    /// Sets both the width and the height of the camera. Like
+    /// [Camera::new], this fails if either of them is zero.
+    pub fn resize(&mut self, hsize: usize, vsize: usize) -> Result<()> {
+        Self::validate_size(hsize, vsize)?;
+
+        self.hsize = hsize;
+        self.vsize = vsize;
+        self.recompute();
+        Ok(())
+    }
+
+    /// Sets the field of view of the camera. Like [Camera::new], this
+    /// fails unless it is between 0 and π (exclusive).
+    pub fn set_field_of_view(&mut self, field_of_view: f64) -> Result<()> {
+        Self::validate_field_of_view(field_of_view)?;
+
+        self.field_of_view = field_of_view;
+        self.recompute();
+        Ok(())
+    }
+
+    /// The aspect ratio of the image, its width over its height
+    pub fn aspect(&self) -> f64 {
+        self.hsize as f64 / self.vsize as f64
+    }
+
     /// Get the transform matrix for the camera
     pub fn get_transform(&self) -> &Matrix<4, 4> {
         &self.transform
     }
 
+    /// The inverse of the camera transform, which takes points from
+    /// world space into camera space
+    fn inverse_transform(&self) -> Result<Matrix<4, 4>> {
+        self.transform
+            .try_inverse()
+            .ok_or_else(|| RayTracerError::NonInvertibleMatrix.into())
+    }
+
     /// Mutate the camera by setting a new transform matrix
     pub fn set_transform(&mut self, transform: Matrix<4, 4>) {
         self.transform = transform;
@@ -82,51 +326,491 @@ impl Camera {
         self.pixel_size
     }
 
+    /// Get the number of rays traced through each pixel
+    pub fn get_samples_per_pixel(&self) -> usize {
+        self.samples_per_pixel
+    }
+
+    /// Sets the number of rays traced through each pixel. With more
+    /// than one sample, each ray passes through a random point of the
+    /// pixel and their colors are averaged, which smooths jagged edges.
+    pub fn set_samples_per_pixel(&mut self, samples: usize) -> Result<()> {
+        if samples == 0 {
+            return Err(Error::msg("A pixel needs at least one sample"));
+        }
+
+        self.samples_per_pixel = samples;
+        Ok(())
+    }
+
+    /// Get the seed used for the random parts of rendering
+    pub fn get_seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Sets the seed used for the random parts of rendering. Renders
+    /// with the same seed produce identical images.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    /// Get the kind of sampler that places the samples of each pixel
+    pub fn get_sampler(&self) -> SamplerKind {
+        self.sampler
+    }
+
+    /// Sets the kind of sampler that places the samples of each pixel.
+    /// Spreading the samples more evenly than [SamplerKind::Random]
+    /// gives less noise for the same number of samples.
+    pub fn set_sampler(&mut self, sampler: SamplerKind) {
+        self.sampler = sampler;
+    }
+
+    /// Returns true if renders record which pixels show the background
+    pub fn get_transparent_background(&self) -> bool {
+        self.transparent_background
+    }
+
+    /// When enabled, renders carry an alpha channel that is 0 where
+    /// the camera rays miss every object and 1 where they hit one.
+    /// With several samples per pixel, edge pixels get the fraction
+    /// of their samples that hit. This makes it possible to composite
+    /// a render over another image.
+    pub fn set_transparent_background(&mut self, transparent: bool) {
+        self.transparent_background = transparent;
+    }
+
+    /// Get the method used to find the color of each camera ray
+    pub fn get_integrator(&self) -> RenderIntegrator {
+        self.integrator
+    }
+
+    /// Sets the method used to find the color of each camera ray
+    pub fn set_integrator(&mut self, integrator: RenderIntegrator) -> Result<()> {
+        if integrator == (RenderIntegrator::PathTrace { spp: 0 }) {
+            return Err(Error::msg(
+                "Path tracing needs at least one sample per pixel",
+            ));
+        }
+
+        self.integrator = integrator;
+        Ok(())
+    }
+
     /// Calculates a ray that pass through the given pixel coordinate (px,py)
     /// on the camera canvas
     pub fn ray_for_pixel(&self, px: usize, py: usize) -> Result<Ray> {
-        // The offset from the edge of the canvas to the pixel's center
-        let xoffset = (px as f64 + 0.5) * self.pixel_size;
-        let yoffset = (py as f64 + 0.5) * self.pixel_size;
+        let inverse = self.inverse_transform()?;
+        self.ray_for_subpixel(&inverse, px, py, (0.5, 0.5))
+    }
+
+    /// Calculates a ray that passes through the pixel (px, py), at an
+    /// offset in \[0, 1) from the top left corner of the pixel.
+    ///
+    /// `inverse` must be the inverse of the camera transform. Inverting
+    /// the transform is by far the most expensive part of building a
+    /// ray, so callers making many rays should invert it only once.
+    fn ray_for_subpixel(
+        &self,
+        inverse: &Matrix<4, 4>,
+        px: usize,
+        py: usize,
+        (dx, dy): (f64, f64),
+    ) -> Result<Ray> {
+        // using the camera matrix, transform the canvas point and the origin,
+        // and then compute the ray's direction vector.
+        let pixel = inverse * self.view_plane_point(px, py, (dx, dy));
+        let origin = inverse * Tuple::origin();
+        let direction = (pixel - origin).normalize();
+
+        Ok(Ray::new(origin.try_into()?, direction.try_into()?))
+    }
+
+    /// The point on the view plane that the ray for pixel (px, py)
+    /// passes through, before the camera transform is applied. The
+    /// camera sits at the origin looking toward -z, and the view plane
+    /// is at z = -1.
+    ///
+    /// ```
+    /// use raytracer::{camera::Camera, spatial::Tuple};
+    /// use std::f64::consts::PI;
+    ///
+    /// let c = Camera::new(11, 11, PI / 2.0).unwrap();
+    ///
+    /// assert_eq!(c.world_point_on_view_plane(5, 5), Tuple::point(0, 0, -1));
+    /// ```
+    pub fn world_point_on_view_plane(&self, px: usize, py: usize) -> Tuple {
+        self.view_plane_point(px, py, (0.5, 0.5))
+    }
+
+    fn view_plane_point(&self, px: usize, py: usize, (dx, dy): (f64, f64)) -> Tuple {
+        // The offset from the edge of the canvas to the sampled point
+        let xoffset = (px as f64 + dx) * self.pixel_size;
+        let yoffset = (py as f64 + dy) * self.pixel_size;
 
         // the untransformed coordinates of the pixel in world space.
         // (remember that the camera looks toward -z, so +x is to the *left*)
         let world_x = self.half_width - xoffset;
         let world_y = self.half_height - yoffset;
 
-        // using the camera matrix, transform the canvas point and the origin,
-        // and then compute the ray's direction vector.
-        // (remember that the canvas is at z=-1)
-        let pixel = &inverse_4x4(&self.transform)? * &Tuple::point(world_x, world_y, -1);
-        let origin = &inverse_4x4(&self.transform)? * &Tuple::point(0, 0, 0);
-        let direction = (&pixel - &origin).normalize();
+        Tuple::point(world_x, world_y, -1)
+    }
+
+    /// Finds where `world_point` appears on the canvas, as pixel
+    /// coordinates measured from the top left corner of the image. This
+    /// undoes [Camera::ray_for_pixel]: the point is brought into camera
+    /// space and divided by its depth to land on the view plane.
+    ///
+    /// Returns `None` for points behind the camera, or that fall outside
+    /// of the image.
+    ///
+    /// ```
+    /// use raytracer::{camera::Camera, spatial::Tuple};
+    /// use std::f64::consts::PI;
+    ///
+    /// let c = Camera::new(11, 11, PI / 2.0).unwrap();
+    ///
+    /// assert_eq!(c.project(&Tuple::point(0, 0, -3)), Some((5.5, 5.5)));
+    /// assert_eq!(c.project(&Tuple::point(0, 0, 3)), None);
+    /// assert_eq!(c.project(&Tuple::point(5, 0, -1)), None);
+    /// ```
+    pub fn project(&self, world_point: &Tuple) -> Option<(f64, f64)> {
+        let camera_point = self.transform * world_point;
+
+        // the camera looks toward -z, so anything else is behind it
+        let depth = -camera_point.get_z();
+        if depth <= 0.0 {
+            return None;
+        }
+
+        // where the point lands on the view plane at z = -1
+        let world_x = camera_point.get_x() / depth;
+        let world_y = camera_point.get_y() / depth;
+
+        let px = (self.half_width - world_x) / self.pixel_size;
+        let py = (self.half_height - world_y) / self.pixel_size;
+
+        // points on the very edge may land a rounding error outside
+        let within = |v: f64, max: f64| {
+            (0.0..=max).contains(&v) || float_equals(&v, &0.0) || float_equals(&v, &max)
+        };
+        if !within(px, self.hsize as f64) || !within(py, self.vsize as f64) {
+            return None;
+        }
 
-        Ray::new(origin, direction)
+        Some((
+            px.clamp(0.0, self.hsize as f64),
+            py.clamp(0.0, self.vsize as f64),
+        ))
+    }
+
+    /// Finds the color the world has along the ray through the center of
+    /// pixel (px, py). This is handy to check a single pixel without
+    /// rendering the whole image. It always uses the direct lighting of
+    /// [World::color_at], whatever the camera's integrator.
+    pub fn color_at_pixel(&self, world: &World, px: usize, py: usize) -> Result<Color> {
+        world.color_at(&self.ray_for_pixel(px, py)?)
     }
 
     /// Uses the camera to render an image of the given world
     pub fn render(&self, world: &World) -> Result<Canvas> {
-        let mut image = Canvas::new(self.get_hsize(), self.get_vsize());
+        self.render_with_progress(world, |_, _| {})
+    }
+
+    /// Renders the world into raw 8-bit RGBA bytes, row by row starting
+    /// from the top left pixel, returned along with the width and height
+    /// of the image. This is the layout of an HTML canvas' `ImageData`,
+    /// so a render can be shown in a browser without going through an
+    /// image file.
+    ///
+    /// ```
+    /// use raytracer::{camera::Camera, world::World};
+    /// use std::f64::consts::PI;
+    ///
+    /// let camera = Camera::new(8, 6, PI / 2.0).unwrap();
+    /// let (rgba, width, height) = camera.render_to_rgba(&World::default()).unwrap();
+    ///
+    /// assert_eq!((width, height), (8, 6));
+    /// assert_eq!(rgba.len(), 8 * 6 * 4);
+    /// ```
+    pub fn render_to_rgba(&self, world: &World) -> Result<(Vec<u8>, usize, usize)> {
+        let image = self.render(world)?;
+        Ok((image.to_rgba_bytes(), image.width, image.height))
+    }
+
+    /// Same as [Camera::render], but overwrites every pixel of an
+    /// existing canvas instead of allocating a new one. This suits
+    /// loops that render many frames of the same size.
+    ///
+    /// This returns an error if the canvas isn't the size of the camera.
+    ///
+    /// ```
+    /// use raytracer::{camera::Camera, canvas::Canvas, world::World};
+    /// use std::f64::consts::PI;
+    ///
+    /// let camera = Camera::new(8, 6, PI / 2.0).unwrap();
+    /// let mut frame = Canvas::new(8, 6);
+    ///
+    /// for _ in 0..3 {
+    ///     camera.render_into(&World::default(), &mut frame).unwrap();
+    /// }
+    /// ```
+    pub fn render_into(&self, world: &World, canvas: &mut Canvas) -> Result<()> {
+        self.render_inner(world, canvas, |_, _| {}, None)
+    }
+
+    /// Same as [Camera::render], but calls `on_progress` with the
+    /// number of rows rendered so far and the total number of rows
+    /// after each row of the image is finished
+    pub fn render_with_progress(
+        &self,
+        world: &World,
+        on_progress: impl FnMut(usize, usize),
+    ) -> Result<Canvas> {
+        let mut image = Canvas::new(self.hsize, self.vsize);
+        self.render_inner(world, &mut image, on_progress, None)?;
+        Ok(image)
+    }
+
+    /// Same as [Camera::render], but when `enabled` is true it also
+    /// measures the time spent in each phase of the render. The clock
+    /// is never read when timing is disabled.
+    pub fn render_with_timings(
+        &self,
+        world: &World,
+        enabled: bool,
+    ) -> Result<(Canvas, Option<RenderTimings>)> {
+        let mut timings = enabled.then(RenderTimings::default);
+        let mut image = Canvas::new(self.hsize, self.vsize);
+        self.render_inner(world, &mut image, |_, _| {}, timings.as_mut())?;
+        Ok((image, timings))
+    }
 
-        for y in 0..(self.vsize - 1) {
-            for x in 0..(self.hsize - 1) {
-                let ray = self.ray_for_pixel(x, y)?;
-                let color = world.color_at(&ray)?;
-                image.write_pixel(x, y, color)?;
+    /// Renders the rows `y_start..y_end` of the image on its own, and
+    /// returns each row's index along with its colors from left to
+    /// right. Placing every row at its index gives the same pixels as
+    /// [Camera::render].
+    ///
+    /// Both [Camera] and [World] are `Send + Sync`, so separate ranges
+    /// can be rendered from different threads sharing the same camera
+    /// and world, and stitched together afterwards.
+    ///
+    /// This returns an error if the range isn't within the image.
+    pub fn render_rows_range(
+        &self,
+        world: &World,
+        y_start: usize,
+        y_end: usize,
+    ) -> Result<Vec<(usize, Vec<Color>)>> {
+        if y_start > y_end || y_end > self.vsize {
+            return Err(Error::msg(format!(
+                "The rows {}..{} are not within the {} rows of the image",
+                y_start, y_end, self.vsize
+            )));
+        }
+
+        let inverse = self.inverse_transform()?;
+
+        (y_start..y_end)
+            .map(|y| {
+                let row = (0..self.hsize)
+                    .map(|x| Ok(self.render_pixel(world, &inverse, (x, y), None)?.0))
+                    .collect::<Result<_>>()?;
+                Ok((y, row))
+            })
+            .collect()
+    }
+
+    fn render_inner(
+        &self,
+        world: &World,
+        image: &mut Canvas,
+        mut on_progress: impl FnMut(usize, usize),
+        mut timings: Option<&mut RenderTimings>,
+    ) -> Result<()> {
+        if (image.width, image.height) != (self.hsize, self.vsize) {
+            return Err(RayTracerError::InvalidDimensions {
+                expected: (self.hsize, self.vsize),
+                got: (image.width, image.height),
             }
+            .into());
         }
 
-        Ok(image)
+        // leftover coverage from an earlier render would be wrong
+        if !self.transparent_background {
+            image.clear_alpha();
+        }
+        let inverse = self.inverse_transform()?;
+
+        for y in 0..self.vsize {
+            for x in 0..self.hsize {
+                let (color, coverage) =
+                    self.render_pixel(world, &inverse, (x, y), timings.as_deref_mut())?;
+
+                timed(
+                    timings.as_deref_mut().map(|t| &mut t.canvas_writing),
+                    || {
+                        if self.transparent_background {
+                            image.write_alpha(x, y, coverage)?;
+                        }
+                        image.write_pixel(x, y, color)
+                    },
+                )?;
+            }
+
+            on_progress(y + 1, self.vsize);
+        }
+
+        Ok(())
+    }
+
+    /// Finds the color of the pixel (x, y) by averaging its samples,
+    /// along with the fraction of them whose camera ray hit an object
+    fn render_pixel(
+        &self,
+        world: &World,
+        inverse: &Matrix<4, 4>,
+        (x, y): (usize, usize),
+        timings: Option<&mut RenderTimings>,
+    ) -> Result<(Color, f64)> {
+        let samples = match self.integrator {
+            RenderIntegrator::Phong => self.samples_per_pixel,
+            RenderIntegrator::PathTrace { spp } => spp,
+        };
+
+        // every pixel gets its own fork, so its samples don't depend
+        // on the order the pixels are rendered in
+        let stream = (y * self.hsize + x) as u64;
+        match self.sampler {
+            SamplerKind::Random => {
+                let sampler = RandomSampler::new(self.seed).fork(stream);
+                self.render_samples(world, inverse, (x, y), samples, sampler, timings)
+            }
+            SamplerKind::Stratified => {
+                let sampler = StratifiedSampler::new(samples, self.seed).fork(stream);
+                self.render_samples(world, inverse, (x, y), samples, sampler, timings)
+            }
+            SamplerKind::Halton => {
+                let sampler = HaltonSampler::new(self.seed).fork(stream);
+                self.render_samples(world, inverse, (x, y), samples, sampler, timings)
+            }
+        }
+    }
+
+    /// Averages `samples` samples of the pixel (x, y), placed by `sampler`
+    fn render_samples<S: Sampler>(
+        &self,
+        world: &World,
+        inverse: &Matrix<4, 4>,
+        (x, y): (usize, usize),
+        samples: usize,
+        mut sampler: S,
+        mut timings: Option<&mut RenderTimings>,
+    ) -> Result<(Color, f64)> {
+        if samples == 1 && self.integrator == RenderIntegrator::Phong {
+            let (color, hit) = self.sample(
+                world,
+                inverse,
+                (x, y),
+                (0.5, 0.5),
+                &mut sampler,
+                timings.as_deref_mut(),
+            )?;
+            Ok((color, if hit { 1.0 } else { 0.0 }))
+        } else {
+            let mut total = Color::black();
+            let mut hits = 0;
+            for _ in 0..samples {
+                let offset = sampler.next_2d();
+                let (color, hit) = self.sample(
+                    world,
+                    inverse,
+                    (x, y),
+                    offset,
+                    &mut sampler,
+                    timings.as_deref_mut(),
+                )?;
+                total = total + color;
+                hits += hit as usize;
+            }
+            let weight = 1.0 / samples as f64;
+            Ok((total * weight, hits as f64 * weight))
+        }
+    }
+
+    /// Finds the color seen through a single point of the pixel (x, y),
+    /// and whether the camera ray hit an object. Path tracing only looks
+    /// for the hit when the background is transparent.
+    ///
+    /// A ray that runs into an object whose transform can't be inverted
+    /// is shaded magenta, so one broken object shows up in the image
+    /// instead of failing the whole render.
+    fn sample(
+        &self,
+        world: &World,
+        inverse: &Matrix<4, 4>,
+        pixel: (usize, usize),
+        offset: (f64, f64),
+        sampler: &mut dyn Sampler,
+        timings: Option<&mut RenderTimings>,
+    ) -> Result<(Color, bool)> {
+        self.trace_sample(world, inverse, pixel, offset, sampler, timings)
+            .or_else(|err| match err.downcast_ref::<RayTracerError>() {
+                Some(
+                    RayTracerError::NonInvertibleMatrix
+                    | RayTracerError::NonInvertibleTransform { .. },
+                ) => Ok((Color::magenta(), true)),
+                _ => Err(err),
+            })
+    }
+
+    /// Does the work of [Camera::sample]
+    fn trace_sample(
+        &self,
+        world: &World,
+        inverse: &Matrix<4, 4>,
+        (x, y): (usize, usize),
+        offset: (f64, f64),
+        sampler: &mut dyn Sampler,
+        mut timings: Option<&mut RenderTimings>,
+    ) -> Result<(Color, bool)> {
+        let ray = timed(
+            timings.as_deref_mut().map(|t| &mut t.ray_generation),
+            || self.ray_for_subpixel(inverse, x, y, offset),
+        )?;
+
+        // a path alternates between intersecting and shading at every
+        // bounce, so its time is all counted as shading
+        if let RenderIntegrator::PathTrace { .. } = self.integrator {
+            let hit = self.transparent_background && world.intersect_world(&ray)?.hit().is_some();
+            let color = timed(timings.map(|t| &mut t.shading), || {
+                world.color_at_pathtraced(&ray, Self::MAX_PATH_DEPTH, sampler)
+            })?;
+            return Ok((color, hit));
+        }
+
+        let xs = timed(timings.as_deref_mut().map(|t| &mut t.intersection), || {
+            world.intersect_world(&ray)
+        })?;
+        let color = timed(timings.map(|t| &mut t.shading), || {
+            world.color_of_hit(&xs, &ray)
+        })?;
+        Ok((world.clamp_output_color(color), xs.hit().is_some()))
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::Camera;
+    use super::{Camera, FovAxis, RenderIntegrator, SamplerKind};
     use crate::{
+        canvas::Canvas,
         color::Color,
-        matrix::{rotation_y, translation, view_transform},
-        spatial::Tuple,
+        lights::Material,
+        matrix::{
+            inverse_4x4, rotation_y, scaling, translation, view_transform, DETERMINANT_4X4_CALLS,
+        },
+        shapes::{Object, Sphere},
+        spatial::{Point, Tuple, Vector},
         utils::float_equals,
         world::World,
     };
@@ -134,30 +818,277 @@ mod tests {
     use std::f64::consts::{PI, SQRT_2};
 
     #[test]
-    fn constructing_new_camera() {
+    fn constructing_new_camera() -> Result<()> {
         let hsize = 160;
         let vsize = 120;
         let field_of_view = PI / 2.0;
 
-        let c = Camera::new(hsize, vsize, field_of_view);
+        let c = Camera::new(hsize, vsize, field_of_view)?;
 
         assert_eq!(c.get_hsize(), hsize);
         assert_eq!(c.get_vsize(), vsize);
         assert_eq!(c.get_field_of_view(), field_of_view);
+
+        Ok(())
     }
 
     #[test]
-    fn calculate_pixel_size() {
-        let c1 = Camera::new(200, 125, PI / 2.0);
+    fn resizing_a_camera_updates_the_pixel_size() -> Result<()> {
+        let mut c = Camera::new(200, 125, PI / 2.0)?;
+        c.resize(125, 200)?;
+
+        let fresh = Camera::new(125, 200, PI / 2.0)?;
+        assert_eq!(c.get_pixel_size(), fresh.get_pixel_size());
+        assert_eq!(
+            (c.half_width, c.half_height),
+            (fresh.half_width, fresh.half_height)
+        );
+
+        c.set_hsize(250)?;
+        c.set_vsize(100)?;
+        assert_eq!(
+            c.get_pixel_size(),
+            Camera::new(250, 100, PI / 2.0)?.get_pixel_size()
+        );
+
+        assert!(c.resize(0, 100).is_err());
+        assert!(c.set_vsize(0).is_err());
+        assert_eq!((c.get_hsize(), c.get_vsize()), (250, 100));
+
+        Ok(())
+    }
+
+    #[test]
+    fn changing_the_field_of_view_updates_the_half_width() -> Result<()> {
+        let mut c = Camera::new(200, 100, PI / 2.0)?;
+        assert!(float_equals(&c.half_width, &1.0));
+
+        c.set_field_of_view(PI / 3.0)?;
+        assert!(float_equals(&c.half_width, &(PI / 6.0).tan()));
+        assert!(float_equals(&c.half_height, &((PI / 6.0).tan() / 2.0)));
+        assert!(float_equals(
+            &c.get_pixel_size(),
+            &(2.0 * (PI / 6.0).tan() / 200.0)
+        ));
+
+        assert!(c.set_field_of_view(PI).is_err());
+        assert_eq!(c.get_field_of_view(), PI / 3.0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn cameras_without_pixels_are_rejected() {
+        for (hsize, vsize) in [(0, 120), (160, 0), (0, 0)] {
+            let err = Camera::new(hsize, vsize, PI / 2.0).unwrap_err();
+            assert!(err.to_string().contains("at least 1x1 pixels"));
+        }
+    }
+
+    #[test]
+    fn a_pinned_vertical_fov_keeps_the_view_height() -> Result<()> {
+        let mut c = Camera::new(100, 100, PI / 2.0)?;
+        c.set_fov_axis(FovAxis::Vertical);
+        assert_eq!(c.get_fov_axis(), FovAxis::Vertical);
+
+        for hsize in [50, 100, 160, 400] {
+            c.set_hsize(hsize)?;
+            let view_height = c.get_pixel_size() * c.get_vsize() as f64;
+            assert!(float_equals(&view_height, &2.0), "hsize {}", hsize);
+        }
+
+        // the scaled preview keeps the pinned axis
+        let preview = c.scaled(2);
+        assert_eq!(preview.get_fov_axis(), FovAxis::Vertical);
+        assert!(float_equals(&(preview.get_pixel_size() * 50.0), &2.0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn a_pinned_horizontal_fov_keeps_the_view_width() -> Result<()> {
+        let mut c = Camera::new(100, 400, PI / 2.0)?;
+        c.set_fov_axis(FovAxis::Horizontal);
+
+        assert!(float_equals(&(c.get_pixel_size() * 100.0), &2.0));
+
+        // the default spans the larger side, which here is the height
+        c.set_fov_axis(FovAxis::Larger);
+        assert!(float_equals(&(c.get_pixel_size() * 400.0), &2.0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn field_of_view_must_be_between_zero_and_pi() {
+        for field_of_view in [0.0, -1.0, PI, 4.0, f64::NAN, f64::INFINITY] {
+            let err = Camera::new(160, 120, field_of_view).unwrap_err();
+            assert!(err.to_string().contains("field of view"));
+        }
+
+        assert!(Camera::new(160, 120, PI - 0.01).is_ok());
+    }
+
+    #[test]
+    fn the_default_camera_renders_the_default_world() -> Result<()> {
+        let c = Camera::default();
+        assert_eq!((c.get_hsize(), c.get_vsize()), (100, 100));
+        assert_eq!(c.get_field_of_view(), PI / 3.0);
+
+        let image = c.render(&World::default())?;
+        assert_eq!((image.width, image.height), (100, 100));
+
+        Ok(())
+    }
+
+    #[test]
+    fn calculate_pixel_size() -> Result<()> {
+        let c1 = Camera::new(200, 125, PI / 2.0)?;
         assert!(float_equals(&c1.get_pixel_size(), &0.01));
 
-        let c2 = Camera::new(125, 200, PI / 2.0);
+        let c2 = Camera::new(125, 200, PI / 2.0)?;
         assert!(float_equals(&c2.get_pixel_size(), &0.01));
+
+        Ok(())
+    }
+
+    #[test]
+    fn rgba_renders_hold_every_pixel_in_row_order() -> Result<()> {
+        let w = World::default();
+        let mut c = Camera::new(7, 5, PI / 2.0)?;
+        c.set_transform(view_transform(
+            &Point::new(1, 0.5, -5),
+            &Point::new(0, 0, 0),
+            &Vector::new(0, 1, 0),
+        ));
+
+        let (rgba, width, height) = c.render_to_rgba(&w)?;
+        let image = c.render(&w)?;
+
+        assert_eq!((width, height), (7, 5));
+        assert_eq!(rgba.len(), 7 * 5 * 4);
+
+        for y in 0..height {
+            for x in 0..width {
+                let (r, g, b) = image.pixel_at(x, y)?.get_255_scaled_tuple();
+                let i = (y * width + x) * 4;
+                assert_eq!(rgba[i..i + 4], [r as u8, g as u8, b as u8, 255]);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn color_at_pixel_matches_the_render() -> Result<()> {
+        let w = World::default();
+        let mut c = Camera::new(11, 11, PI / 2.0)?;
+        c.set_transform(view_transform(
+            &Point::new(0, 0, -5),
+            &Point::new(0, 0, 0),
+            &Vector::new(0, 1, 0),
+        ));
+
+        let color = c.color_at_pixel(&w, 5, 5)?;
+
+        assert_eq!(color, Color::new(0.38066, 0.47583, 0.2855));
+        assert_eq!(&color, c.render(&w)?.pixel_at(5, 5)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn view_plane_points_lie_on_the_pixel_rays() -> Result<()> {
+        let mut c = Camera::new(11, 11, PI / 2.0)?;
+        assert_eq!(c.world_point_on_view_plane(5, 5), Tuple::point(0, 0, -1));
+        assert_eq!(
+            c.world_point_on_view_plane(0, 0),
+            Tuple::point(10.0 / 11.0, 10.0 / 11.0, -1)
+        );
+
+        c.set_transform((rotation_y(PI / 4.0) * translation(0, -2, 5))?);
+        let inverse = inverse_4x4(c.get_transform())?;
+
+        for (x, y) in [(5, 5), (0, 0), (10, 3)] {
+            let r = c.ray_for_pixel(x, y)?;
+            let on_plane = inverse * c.world_point_on_view_plane(x, y);
+            assert_eq!(r.position((on_plane - r.origin).magnitude()), on_plane);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn cameras_can_be_built_from_an_aspect_ratio() -> Result<()> {
+        let c = Camera::with_aspect(1920, 1.7777, PI / 3.0)?;
+        let direct = Camera::new(1920, 1080, PI / 3.0)?;
+
+        assert_eq!(c.get_vsize(), 1080);
+        assert_eq!(c.get_pixel_size(), direct.get_pixel_size());
+        assert_eq!(c.aspect(), direct.aspect());
+        assert!(float_equals(&c.aspect(), &(16.0 / 9.0)));
+
+        for aspect in [0.0, -1.5, f64::NAN, f64::INFINITY] {
+            assert!(Camera::with_aspect(1920, aspect, PI / 3.0).is_err());
+        }
+
+        // too wide to leave a single row of pixels
+        assert!(Camera::with_aspect(10, 50.0, PI / 3.0).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn projecting_undoes_ray_for_pixel() -> Result<()> {
+        let mut c = Camera::new(11, 11, PI / 2.0)?;
+        c.set_transform(view_transform(
+            &Point::new(0, 0, -5),
+            &Point::origin(),
+            &Vector::new(0, 1, 0),
+        ));
+
+        let r = c.ray_for_pixel(5, 5)?;
+        let xs = Sphere::default().intersect(&r)?;
+        let hit = xs.hit().expect("the ray hits the sphere");
+        let (px, py) = c.project(&r.position(hit.t)).expect("the hit is in view");
+        assert!((px - 5.5).abs() <= 0.5 && (py - 5.5).abs() <= 0.5);
+
+        let behind = Tuple::point(0, 0, -6);
+        assert_eq!(c.project(&behind), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn projecting_the_view_plane_corners() -> Result<()> {
+        let mut c = Camera::new(16, 9, PI / 2.0)?;
+        c.set_transform((rotation_y(PI / 4.0) * translation(0, -2, 5))?);
+        let inverse = inverse_4x4(c.get_transform())?;
+
+        let top_left = inverse * c.view_plane_point(0, 0, (0.0, 0.0));
+        let bottom_right = inverse * c.view_plane_point(15, 8, (1.0, 1.0));
+
+        let lands_on = |point: &Tuple, (x, y): (f64, f64)| match c.project(point) {
+            Some((px, py)) => float_equals(&px, &x) && float_equals(&py, &y),
+            None => false,
+        };
+
+        assert!(lands_on(&top_left, (0.0, 0.0)));
+        assert!(lands_on(&bottom_right, (16.0, 9.0)));
+
+        // further along the same ray still lands on the corner
+        let origin = inverse * Tuple::origin();
+        assert!(lands_on(
+            &(origin + (bottom_right - origin) * 10.0),
+            (16.0, 9.0)
+        ));
+
+        Ok(())
     }
 
     #[test]
     fn ray_for_pixel_works() -> Result<()> {
-        let mut c = Camera::new(201, 101, PI / 2.0);
+        let mut c = Camera::new(201, 101, PI / 2.0)?;
 
         // Constructing a ray through the center of the canvas
         let r = c.ray_for_pixel(100, 50)?;
@@ -170,7 +1101,7 @@ mod tests {
         assert_eq!(r.direction, Tuple::vector(0.66519, 0.33259, -0.66851));
 
         // Constructing a ray when the camera is transformed
-        c.set_transform((&rotation_y(PI / 4.0) * &translation(0, -2, 5))?);
+        c.set_transform((rotation_y(PI / 4.0) * translation(0, -2, 5))?);
         let r = c.ray_for_pixel(100, 50)?;
         assert_eq!(r.origin, Tuple::point(0, 2, -5));
         assert_eq!(r.direction, Tuple::vector(SQRT_2 / 2.0, 0, -SQRT_2 / 2.0));
@@ -178,14 +1109,33 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn the_camera_transform_is_inverted_once_per_render() -> Result<()> {
+        let mut c = Camera::new(4, 3, PI / 2.0)?;
+        c.set_transform((rotation_y(PI / 4.0) * translation(0, -2, 5))?);
+        c.set_samples_per_pixel(2)?;
+
+        // an empty world, so only the camera inverts matrices
+        let calls = |f: &dyn Fn() -> Result<()>| -> Result<usize> {
+            DETERMINANT_4X4_CALLS.with(|calls| calls.set(0));
+            f()?;
+            Ok(DETERMINANT_4X4_CALLS.with(|calls| calls.get()))
+        };
+
+        assert_eq!(calls(&|| c.ray_for_pixel(1, 1).map(|_| ()))?, 1);
+        assert_eq!(calls(&|| c.render(&World::empty()).map(|_| ()))?, 1);
+
+        Ok(())
+    }
+
     #[test]
     fn rendering_a_world_with_a_camera() -> Result<()> {
         let w = World::default();
 
-        let mut c = Camera::new(11, 11, PI / 2.0);
-        let from = Tuple::point(0, 0, -5);
-        let to = Tuple::point(0, 0, 0);
-        let up = Tuple::vector(0, 1, 0);
+        let mut c = Camera::new(11, 11, PI / 2.0)?;
+        let from = Point::new(0, 0, -5);
+        let to = Point::new(0, 0, 0);
+        let up = Vector::new(0, 1, 0);
         c.set_transform(view_transform(&from, &to, &up));
 
         let image = c.render(&w)?;
@@ -193,4 +1143,331 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn rendering_into_a_canvas_overwrites_every_pixel() -> Result<()> {
+        let w = World::default();
+        let mut c = Camera::new(11, 11, PI / 2.0)?;
+        c.set_transform(view_transform(
+            &Point::new(0, 0, -5),
+            &Point::new(0, 0, 0),
+            &Vector::new(0, 1, 0),
+        ));
+
+        let mut canvas = Canvas::new(11, 11);
+        canvas.fill(Color::red());
+        c.render_into(&w, &mut canvas)?;
+
+        assert_eq!(canvas, c.render(&w)?);
+        for y in 0..11 {
+            for x in 0..11 {
+                assert_ne!(canvas.pixel_at(x, y)?, &Color::red());
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn rendering_into_a_canvas_of_the_wrong_size_fails() -> Result<()> {
+        let c = Camera::new(11, 11, PI / 2.0)?;
+
+        for (width, height) in [(10, 11), (11, 12), (0, 0)] {
+            let mut canvas = Canvas::new(width, height);
+            assert!(c.render_into(&World::default(), &mut canvas).is_err());
+        }
+
+        Ok(())
+    }
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn scene_types_can_be_shared_between_threads() {
+        assert_send_sync::<World>();
+        assert_send_sync::<Camera>();
+        assert_send_sync::<Object>();
+        assert_send_sync::<Material>();
+    }
+
+    #[test]
+    fn row_ranges_rendered_on_separate_threads_stitch_into_the_full_image() -> Result<()> {
+        let w = World::default();
+        let mut c = Camera::new(11, 9, PI / 2.0)?;
+        c.set_transform(view_transform(
+            &Point::new(0, 0, -5),
+            &Point::new(0, 0, 0),
+            &Vector::new(0, 1, 0),
+        ));
+        c.set_samples_per_pixel(2)?;
+
+        let (top, bottom) = std::thread::scope(|scope| {
+            let top = scope.spawn(|| c.render_rows_range(&w, 0, 4));
+            let bottom = scope.spawn(|| c.render_rows_range(&w, 4, 9));
+            (top.join().unwrap(), bottom.join().unwrap())
+        });
+
+        let mut stitched = Canvas::new(11, 9);
+        for (y, row) in top?.into_iter().chain(bottom?) {
+            for (x, color) in row.into_iter().enumerate() {
+                stitched.write_pixel(x, y, color)?;
+            }
+        }
+
+        assert_eq!(stitched, c.render(&w)?);
+
+        assert!(c.render_rows_range(&w, 5, 10).is_err());
+        assert!(c.render_rows_range(&w, 5, 4).is_err());
+        assert!(c.render_rows_range(&w, 9, 9)?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn rendering_reports_progress_for_every_row() -> Result<()> {
+        let w = World::default();
+        let c = Camera::new(4, 3, PI / 2.0)?;
+
+        let mut reports = vec![];
+        let image = c.render_with_progress(&w, |done, total| reports.push((done, total)))?;
+
+        assert_eq!(reports, vec![(1, 3), (2, 3), (3, 3)]);
+        assert_eq!(image.pixel_at(3, 2)?, c.render(&w)?.pixel_at(3, 2)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn rendering_covers_the_last_row_and_column() -> Result<()> {
+        let w = World::default();
+        // a narrow field of view, so every pixel sees the sphere
+        let mut c = Camera::new(11, 11, 0.1)?;
+        c.set_transform(view_transform(
+            &Point::new(0, 0, -5),
+            &Point::new(0, 0, 0),
+            &Vector::new(0, 1, 0),
+        ));
+
+        let image = c.render(&w)?;
+        let expected = w.color_at(&c.ray_for_pixel(10, 10)?)?;
+
+        assert_ne!(expected, Color::black());
+        assert_eq!(image.pixel_at(10, 10)?, &expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn samples_per_pixel_must_be_positive() -> Result<()> {
+        let mut c = Camera::new(10, 10, PI / 2.0)?;
+        assert_eq!(c.get_samples_per_pixel(), 1);
+
+        c.set_samples_per_pixel(4)?;
+        assert_eq!(c.get_samples_per_pixel(), 4);
+        assert!(c.set_samples_per_pixel(0).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn jittered_renders_depend_only_on_the_seed() -> Result<()> {
+        let w = World::default();
+        let render = |seed| -> Result<Vec<Color>> {
+            let mut c = Camera::new(9, 9, PI / 2.0)?;
+            c.set_transform(view_transform(
+                &Point::new(0, 0, -5),
+                &Point::new(0, 0, 0),
+                &Vector::new(0, 1, 0),
+            ));
+            c.set_samples_per_pixel(4)?;
+            c.set_seed(seed);
+
+            let image = c.render(&w)?;
+            (0..81)
+                .map(|i| Ok(*image.pixel_at(i % 9, i / 9)?))
+                .collect()
+        };
+
+        assert_eq!(render(1)?, render(1)?);
+        assert_ne!(render(1)?, render(2)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn samplers_change_the_noise_but_not_the_mean_color() -> Result<()> {
+        let w = World::default();
+        let render = |sampler| -> Result<Vec<Color>> {
+            let mut c = Camera::new(21, 21, PI / 2.0)?;
+            c.set_transform(view_transform(
+                &Point::new(0, 0, -5),
+                &Point::new(0, 0, 0),
+                &Vector::new(0, 1, 0),
+            ));
+            c.set_samples_per_pixel(16)?;
+            c.set_sampler(sampler);
+
+            let image = c.render(&w)?;
+            (0..21 * 21)
+                .map(|i| Ok(*image.pixel_at(i % 21, i / 21)?))
+                .collect()
+        };
+        let mean = |pixels: &[Color]| {
+            pixels.iter().map(|c| c.red + c.green + c.blue).sum::<f64>() / pixels.len() as f64
+        };
+
+        let random = render(SamplerKind::Random)?;
+        for sampler in [SamplerKind::Stratified, SamplerKind::Halton] {
+            let other = render(sampler)?;
+
+            assert_ne!(other, random);
+            assert!(
+                (mean(&other) - mean(&random)).abs() < 0.01,
+                "{:?} changed the mean color",
+                sampler
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn objects_with_broken_transforms_render_magenta() -> Result<()> {
+        let mut w = World::default();
+        w.add_object(Sphere::with_unchecked_transform(
+            (translation(0, 0, -3) * scaling(1, 0, 1))?,
+            Material::default(),
+        ));
+
+        let mut c = Camera::new(11, 11, PI / 2.0)?;
+        c.set_transform(view_transform(
+            &Point::new(0, 0, -5),
+            &Point::new(0, 0, 0),
+            &Vector::new(0, 1, 0),
+        ));
+
+        let image = c.render(&w)?;
+        assert_eq!(*image.pixel_at(5, 5)?, Color::magenta());
+
+        Ok(())
+    }
+
+    #[test]
+    fn scaling_a_camera_keeps_the_view() -> Result<()> {
+        let mut c = Camera::new(27, 27, PI / 2.0)?;
+        c.set_transform((rotation_y(PI / 4.0) * translation(0, -2, 5))?);
+
+        let preview = c.scaled(3);
+
+        assert_eq!((preview.get_hsize(), preview.get_vsize()), (9, 9));
+        assert_eq!(preview.get_field_of_view(), c.get_field_of_view());
+        assert_eq!(preview.get_transform(), c.get_transform());
+        assert!(float_equals(
+            &preview.get_pixel_size(),
+            &(c.get_pixel_size() * 3.0)
+        ));
+        assert_eq!(preview.ray_for_pixel(4, 4)?, c.ray_for_pixel(13, 13)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn a_downsampled_render_matches_the_preview() -> Result<()> {
+        let w = World::default();
+        let mut c = Camera::new(40, 40, PI / 2.0)?;
+        c.set_transform(view_transform(
+            &Point::new(0, 0, -5),
+            &Point::new(0, 0, 0),
+            &Vector::new(0, 1, 0),
+        ));
+
+        let full = c.render(&w)?;
+        let preview = c.scaled(2).render(&w)?;
+
+        let mut total_difference = 0.0;
+        for y in 0..20 {
+            for x in 0..20 {
+                let mut block = Color::black();
+                for (dx, dy) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+                    block = block + *full.pixel_at(2 * x + dx, 2 * y + dy)?;
+                }
+                let block = block * 0.25;
+                let expected = preview.pixel_at(x, y)?;
+
+                let difference = (block.red - expected.red).abs()
+                    + (block.green - expected.green).abs()
+                    + (block.blue - expected.blue).abs();
+
+                assert!(difference < 1.5, "pixel ({}, {}) is too different", x, y);
+                total_difference += difference;
+            }
+        }
+
+        assert!(total_difference / 400.0 < 0.1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn path_tracing_is_opt_in() -> Result<()> {
+        let w = World::default();
+        let mut c = Camera::new(5, 5, PI / 2.0)?;
+        c.set_transform(view_transform(
+            &Point::new(0, 0, -5),
+            &Point::new(0, 0, 0),
+            &Vector::new(0, 1, 0),
+        ));
+
+        // the Phong integrator renders exactly what color_at finds
+        assert_eq!(c.get_integrator(), RenderIntegrator::Phong);
+        let image = c.render(&w)?;
+        assert_eq!(image.pixel_at(2, 2)?, &w.color_at(&c.ray_for_pixel(2, 2)?)?);
+
+        assert!(c
+            .set_integrator(RenderIntegrator::PathTrace { spp: 0 })
+            .is_err());
+        c.set_integrator(RenderIntegrator::PathTrace { spp: 2 })?;
+
+        // nothing in the default world is emissive, so it stays dark
+        let image = c.render(&w)?;
+        assert_eq!(image.pixel_at(2, 2)?, &Color::black());
+
+        Ok(())
+    }
+
+    #[test]
+    fn transparent_backgrounds_record_coverage_in_alpha() -> Result<()> {
+        let w = World::default();
+        let mut c = Camera::new(11, 11, 0.6)?;
+        c.set_transform(view_transform(
+            &Point::new(0, 0, -5),
+            &Point::new(0, 0, 0),
+            &Vector::new(0, 1, 0),
+        ));
+
+        assert!(!c.render(&w)?.has_alpha());
+
+        c.set_transparent_background(true);
+        let image = c.render(&w)?;
+        assert_eq!(image.alpha_at(5, 5)?, 1.0);
+        assert_eq!(image.alpha_at(0, 0)?, 0.0);
+
+        c.set_samples_per_pixel(16)?;
+        let image = c.render(&w)?;
+        let mut edges = 0;
+        for y in 0..11 {
+            for x in 0..11 {
+                let alpha = image.alpha_at(x, y)?;
+                if alpha > 0.0 && alpha < 1.0 {
+                    edges += 1;
+                }
+            }
+        }
+
+        assert_eq!(image.alpha_at(5, 5)?, 1.0);
+        assert_eq!(image.alpha_at(0, 0)?, 0.0);
+        assert!(edges > 0);
+
+        Ok(())
+    }
 }
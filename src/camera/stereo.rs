@@ -0,0 +1,89 @@
+use super::Camera;
+use crate::{canvas::Canvas, matrix::translation, world::World};
+use anyhow::Result;
+
+impl Camera {
+    /// Renders the world as seen by a left and a right eye, which sit
+    /// `eye_separation` apart along the camera's own x axis, centered
+    /// on the camera. Both eyes look in the same direction as the
+    /// camera. The views are returned as `(left, right)`.
+    ///
+    /// See [Canvas::anaglyph] to combine the two into a single image.
+    pub fn render_stereo(&self, world: &World, eye_separation: f64) -> Result<(Canvas, Canvas)> {
+        let eye = |offset: f64| -> Result<Camera> {
+            Ok(Camera {
+                // moving the eye along its x axis moves the world the
+                // other way in the eye's view
+                transform: (translation(-offset, 0, 0) * self.transform)?,
+                ..*self
+            })
+        };
+
+        // the camera's x axis points to the viewer's left, see
+        // view_transform
+        let left = eye(eye_separation / 2.0)?.render(world)?;
+        let right = eye(-eye_separation / 2.0)?.render(world)?;
+
+        Ok((left, right))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        camera::Camera,
+        canvas::Canvas,
+        matrix::view_transform,
+        spatial::{Point, Vector},
+        world::World,
+    };
+    use anyhow::Result;
+
+    fn camera() -> Result<Camera> {
+        let mut c = Camera::new(21, 11, 0.8)?;
+        c.set_transform(view_transform(
+            &Point::new(0, 0, -5),
+            &Point::new(0, 0, 0),
+            &Vector::new(0, 1, 0),
+        ));
+        Ok(c)
+    }
+
+    /// The columns of the middle row covered by the sphere
+    fn silhouette(image: &Canvas) -> Result<Vec<usize>> {
+        let mut columns = vec![];
+        for x in 0..image.width {
+            if image.alpha_at(x, 5)? > 0.0 {
+                columns.push(x);
+            }
+        }
+        Ok(columns)
+    }
+
+    #[test]
+    fn without_separation_both_eyes_see_the_same_image() -> Result<()> {
+        let (left, right) = camera()?.render_stereo(&World::default(), 0.0)?;
+
+        assert_eq!(left, right);
+        assert_eq!(left, camera()?.render(&World::default())?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn each_eye_sees_the_sphere_shifted_sideways() -> Result<()> {
+        let mut c = camera()?;
+        c.set_transparent_background(true);
+
+        let (left, right) = c.render_stereo(&World::default(), 1.0)?;
+        let (left, right) = (silhouette(&left)?, silhouette(&right)?);
+
+        assert!(!left.is_empty() && !right.is_empty());
+
+        // the left eye sits to the left, so it sees the sphere further right
+        assert!(left[0] > right[0]);
+        assert!(left.last() > right.last());
+
+        Ok(())
+    }
+}
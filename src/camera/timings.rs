@@ -0,0 +1,275 @@
+use super::Camera;
+use crate::world::World;
+use anyhow::{Error, Result};
+use std::{
+    fmt::{self, Display},
+    time::{Duration, Instant},
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// The time spent in each phase of a render
+pub struct RenderTimings {
+    /// Building the ray through each pixel
+    pub ray_generation: Duration,
+    /// Intersecting the rays with the objects in the world
+    pub intersection: Duration,
+    /// Finding the color of each hit, including shadow rays
+    pub shading: Duration,
+    /// Writing the colors to the canvas
+    pub canvas_writing: Duration,
+}
+
+impl RenderTimings {
+    /// The time spent in all the phases together
+    pub fn total(&self) -> Duration {
+        self.ray_generation + self.intersection + self.shading + self.canvas_writing
+    }
+
+    /// The phases along with their names, in the order they run
+    fn phases(&self) -> [(&'static str, Duration); 4] {
+        [
+            ("ray generation", self.ray_generation),
+            ("intersection", self.intersection),
+            ("shading", self.shading),
+            ("canvas writing", self.canvas_writing),
+        ]
+    }
+}
+
+/// Runs `f`, adding the time it took to `total` when timing is enabled
+pub(super) fn timed<T>(total: Option<&mut Duration>, f: impl FnOnce() -> T) -> T {
+    match total {
+        Some(total) => {
+            let start = Instant::now();
+            let result = f();
+            *total += start.elapsed();
+            result
+        }
+        None => f(),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// How long a single render of a benchmark took
+pub struct BenchmarkRun {
+    /// The wall clock time of the whole render
+    pub elapsed: Duration,
+    /// The time spent in each phase of the render
+    pub timings: RenderTimings,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// The timings collected by rendering the same scene several times
+pub struct BenchmarkReport {
+    /// The width and height of the rendered images, in pixels
+    pub size: (usize, usize),
+    /// The number of rays cast from the camera in each run
+    pub primary_rays: usize,
+    pub runs: Vec<BenchmarkRun>,
+}
+
+impl BenchmarkReport {
+    /// The wall clock time of the fastest run
+    pub fn fastest(&self) -> Duration {
+        self.runs
+            .iter()
+            .map(|run| run.elapsed)
+            .min()
+            .unwrap_or_default()
+    }
+
+    /// The number of primary rays cast per second in the fastest run
+    pub fn rays_per_second(&self) -> f64 {
+        self.primary_rays as f64 / self.fastest().as_secs_f64().max(f64::MIN_POSITIVE)
+    }
+
+    /// The fastest time of each phase across all the runs
+    pub fn min(&self) -> RenderTimings {
+        let min_of = |phase: fn(&RenderTimings) -> Duration| {
+            self.runs
+                .iter()
+                .map(|run| phase(&run.timings))
+                .min()
+                .unwrap_or_default()
+        };
+
+        RenderTimings {
+            ray_generation: min_of(|t| t.ray_generation),
+            intersection: min_of(|t| t.intersection),
+            shading: min_of(|t| t.shading),
+            canvas_writing: min_of(|t| t.canvas_writing),
+        }
+    }
+
+    /// The average time of each phase across all the runs
+    pub fn average(&self) -> RenderTimings {
+        let runs = self.runs.len().max(1) as u32;
+        let average_of = |phase: fn(&RenderTimings) -> Duration| {
+            self.runs
+                .iter()
+                .map(|run| phase(&run.timings))
+                .sum::<Duration>()
+                / runs
+        };
+
+        RenderTimings {
+            ray_generation: average_of(|t| t.ray_generation),
+            intersection: average_of(|t| t.intersection),
+            shading: average_of(|t| t.shading),
+            canvas_writing: average_of(|t| t.canvas_writing),
+        }
+    }
+}
+
+/// Summarizes the runs on one line, e.g.
+/// `64x64: 4096 primary rays, 3 runs, fastest in 12.345ms (0.332 Mrays/s)`,
+/// followed by a table of the time spent in each phase
+impl Display for BenchmarkReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (min, average) = (self.min(), self.average());
+
+        writeln!(
+            f,
+            "{}x{}: {} primary rays, {} runs, fastest in {:.3}ms ({:.3} Mrays/s)",
+            self.size.0,
+            self.size.1,
+            self.primary_rays,
+            self.runs.len(),
+            self.fastest().as_secs_f64() * 1000.0,
+            self.rays_per_second() / 1e6
+        )?;
+        writeln!(f, "{:<16}{:>12}{:>12}", "phase", "min", "avg")?;
+
+        let rows = min
+            .phases()
+            .into_iter()
+            .zip(average.phases())
+            .map(|((name, min), (_, average))| (name, min, average))
+            .chain([("total", min.total(), average.total())]);
+
+        for (name, min, average) in rows {
+            writeln!(
+                f,
+                "{:<16}{:>10.3}ms{:>10.3}ms",
+                name,
+                min.as_secs_f64() * 1000.0,
+                average.as_secs_f64() * 1000.0
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Camera {
+    /// Renders the world `runs` times, collecting the time spent in
+    /// each phase of every render
+    pub fn benchmark(&self, world: &World, runs: usize) -> Result<BenchmarkReport> {
+        if runs == 0 {
+            return Err(Error::msg("A benchmark needs at least one run"));
+        }
+
+        let runs = (0..runs)
+            .map(|_| {
+                let start = Instant::now();
+                let (_, timings) = self.render_with_timings(world, true)?;
+                Ok(BenchmarkRun {
+                    elapsed: start.elapsed(),
+                    timings: timings.unwrap_or_default(),
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(BenchmarkReport {
+            size: (self.hsize, self.vsize),
+            primary_rays: self.hsize * self.vsize * self.samples_per_pixel,
+            runs,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BenchmarkReport, BenchmarkRun, RenderTimings};
+    use crate::{camera::Camera, world::World};
+    use anyhow::Result;
+    use std::{f64::consts::PI, time::Duration};
+
+    #[test]
+    fn benchmark_runs_time_every_phase() -> Result<()> {
+        let w = World::default();
+        let c = Camera::new(10, 10, PI / 2.0)?;
+
+        let report = c.benchmark(&w, 3)?;
+        assert_eq!(report.runs.len(), 3);
+        assert_eq!(report.size, (10, 10));
+        assert_eq!(report.primary_rays, 100);
+        assert!(report
+            .runs
+            .iter()
+            .all(|run| run.timings.total() <= run.elapsed));
+
+        for timings in [report.min(), report.average()] {
+            assert!(timings.ray_generation > Duration::ZERO);
+            assert!(timings.intersection > Duration::ZERO);
+            assert!(timings.shading > Duration::ZERO);
+            assert!(timings.canvas_writing > Duration::ZERO);
+        }
+
+        let printed = report.to_string();
+        assert!(printed.starts_with("10x10: 100 primary rays, 3 runs, fastest in "));
+        assert!(printed.contains("intersection"));
+        assert!(printed.contains("total"));
+
+        assert!(c.benchmark(&w, 0).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn disabled_timings_render_the_same_pixels() -> Result<()> {
+        let w = World::default();
+        let c = Camera::new(5, 5, PI / 2.0)?;
+
+        let (timed, timings) = c.render_with_timings(&w, true)?;
+        let (untimed, no_timings) = c.render_with_timings(&w, false)?;
+
+        assert!(timings.is_some());
+        assert_eq!(no_timings, None);
+
+        for y in 0..5 {
+            for x in 0..5 {
+                assert_eq!(timed.pixel_at(x, y)?, untimed.pixel_at(x, y)?);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn min_and_average_of_runs() {
+        let ms = Duration::from_millis;
+        let timings = |n| RenderTimings {
+            ray_generation: ms(n),
+            intersection: ms(2 * n),
+            shading: ms(3 * n),
+            canvas_writing: ms(4 * n),
+        };
+        let run = |n| BenchmarkRun {
+            elapsed: ms(11 * n),
+            timings: timings(n),
+        };
+
+        let report = BenchmarkReport {
+            size: (10, 10),
+            primary_rays: 1000,
+            runs: vec![run(3), run(1)],
+        };
+
+        assert_eq!(report.min(), timings(1));
+        assert_eq!(report.average(), timings(2));
+        assert_eq!(report.average().total(), ms(20));
+        assert_eq!(report.fastest(), ms(11));
+        assert_eq!(report.rays_per_second(), 1000.0 / 0.011);
+    }
+}
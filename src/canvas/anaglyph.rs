@@ -0,0 +1,72 @@
+use super::Canvas;
+use crate::{color::Color, error::RayTracerError};
+use anyhow::Result;
+
+impl Canvas {
+    /// Combines a stereo pair into a red-cyan anaglyph: the red channel
+    /// comes from the `left` image, and the green and blue channels
+    /// from the `right` one. Seen through red-cyan glasses, the result
+    /// looks three dimensional.
+    ///
+    /// This returns an error if the two images have different sizes.
+    ///
+    /// ```
+    /// use raytracer::{canvas::Canvas, color::Color};
+    ///
+    /// let mut left = Canvas::new(1, 1);
+    /// left.fill(Color::new(1, 1, 1));
+    /// let right = Canvas::new(1, 1);
+    ///
+    /// let anaglyph = Canvas::anaglyph(&left, &right).unwrap();
+    /// assert_eq!(*anaglyph.pixel_at(0, 0).unwrap(), Color::red());
+    /// ```
+    pub fn anaglyph(left: &Canvas, right: &Canvas) -> Result<Canvas> {
+        if (left.width, left.height) != (right.width, right.height) {
+            // both images of a stereo pair must be the same size
+            return Err(RayTracerError::InvalidDimensions {
+                expected: (left.width, left.height),
+                got: (right.width, right.height),
+            }
+            .into());
+        }
+
+        let mut combined = Canvas::new(left.width, left.height);
+        for ((pixel, l), r) in combined
+            .pixels_mut()
+            .iter_mut()
+            .zip(left.pixels())
+            .zip(right.pixels())
+        {
+            *pixel = Color::new(l.red, r.green, r.blue);
+        }
+
+        Ok(combined)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{canvas::Canvas, color::Color};
+    use anyhow::Result;
+
+    #[test]
+    fn anaglyph_takes_red_from_the_left_and_cyan_from_the_right() -> Result<()> {
+        let mut left = Canvas::new(2, 1);
+        let mut right = Canvas::new(2, 1);
+        left.write_pixel(0, 0, Color::new(0.1, 0.2, 0.3))?;
+        right.write_pixel(0, 0, Color::new(0.4, 0.5, 0.6))?;
+        left.write_pixel(1, 0, Color::new(0.7, 0.8, 0.9))?;
+
+        let anaglyph = Canvas::anaglyph(&left, &right)?;
+
+        assert_eq!(*anaglyph.pixel_at(0, 0)?, Color::new(0.1, 0.5, 0.6));
+        assert_eq!(*anaglyph.pixel_at(1, 0)?, Color::new(0.7, 0, 0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn anaglyph_needs_images_of_the_same_size() {
+        assert!(Canvas::anaglyph(&Canvas::new(2, 1), &Canvas::new(1, 2)).is_err());
+    }
+}
@@ -0,0 +1,135 @@
+use super::Canvas;
+use crate::color::Color;
+use std::fmt;
+
+/// Characters from darkest to brightest
+const RAMP: &[u8] = b" .:-=+*#%@";
+
+/// The width used when a [Canvas] is displayed with `{}`
+const DEFAULT_ASCII_WIDTH: usize = 80;
+
+impl Canvas {
+    /// Draws the canvas as text, one character per block of pixels,
+    /// picking brighter characters for brighter blocks. This makes for
+    /// a quick preview of a render in a terminal or a test log.
+    ///
+    /// The canvas is scaled down to at most `max_width` characters per
+    /// line, and every character covers twice as many rows as columns,
+    /// since characters are about twice as tall as they are wide. Every
+    /// line, including the last, ends with a newline.
+    ///
+    /// ```
+    /// use raytracer::{canvas::Canvas, color::Color};
+    ///
+    /// let mut canvas = Canvas::new(4, 2);
+    /// canvas.write_pixel(3, 0, Color::new(1, 1, 1)).unwrap();
+    /// canvas.write_pixel(3, 1, Color::new(1, 1, 1)).unwrap();
+    ///
+    /// assert_eq!(canvas.to_ascii(80), "   @\n");
+    /// ```
+    pub fn to_ascii(&self, max_width: usize) -> String {
+        if self.width == 0 || self.height == 0 {
+            return String::new();
+        }
+
+        let columns = self.width.min(max_width.max(1));
+        let rows = (self.height * columns)
+            .div_ceil(2 * self.width)
+            .clamp(1, self.height);
+
+        let mut ascii = String::with_capacity((columns + 1) * rows);
+        for row in 0..rows {
+            let ys = (row * self.height / rows)..((row + 1) * self.height / rows);
+            for column in 0..columns {
+                let xs = (column * self.width / columns)..((column + 1) * self.width / columns);
+
+                let mut total = 0.0;
+                for y in ys.clone() {
+                    for x in xs.clone() {
+                        total += luminance(&self.pixels()[y * self.width + x]);
+                    }
+                }
+                let average = total / (ys.len() * xs.len()) as f64;
+
+                let index = (average.clamp(0.0, 1.0) * (RAMP.len() - 1) as f64).round();
+                ascii.push(RAMP[index as usize] as char);
+            }
+            ascii.push('\n');
+        }
+
+        ascii
+    }
+}
+
+/// The perceived brightness of a color
+fn luminance(color: &Color) -> f64 {
+    0.2126 * color.red + 0.7152 * color.green + 0.0722 * color.blue
+}
+
+/// Draws the canvas as text at most 80 characters wide, see
+/// [Canvas::to_ascii]
+impl fmt::Display for Canvas {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_ascii(DEFAULT_ASCII_WIDTH))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{canvas::Canvas, color::Color};
+    use anyhow::Result;
+
+    fn half_white(width: usize, height: usize) -> Result<Canvas> {
+        let mut canvas = Canvas::new(width, height);
+        for y in 0..height {
+            for x in width / 2..width {
+                canvas.write_pixel(x, y, Color::new(1, 1, 1))?;
+            }
+        }
+        Ok(canvas)
+    }
+
+    #[test]
+    fn dark_pixels_are_spaces_and_bright_ones_are_at_signs() -> Result<()> {
+        let ascii = half_white(10, 4)?.to_ascii(80);
+
+        assert_eq!(ascii, "     @@@@@\n     @@@@@\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn wide_canvases_are_scaled_down_to_fit() -> Result<()> {
+        let ascii = half_white(200, 100)?.to_ascii(40);
+        let lines: Vec<_> = ascii.lines().collect();
+
+        // 5 pixels per column, and 10 per row
+        assert_eq!(lines.len(), 10);
+        for line in lines {
+            assert_eq!(line, format!("{}{}", " ".repeat(20), "@".repeat(20)));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn display_uses_a_width_of_80() -> Result<()> {
+        let canvas = half_white(160, 160)?;
+
+        assert_eq!(canvas.to_string(), canvas.to_ascii(80));
+        assert_eq!(canvas.to_string().lines().count(), 40);
+
+        Ok(())
+    }
+
+    #[test]
+    fn gray_levels_pick_characters_along_the_ramp() -> Result<()> {
+        let mut canvas = Canvas::new(3, 1);
+        canvas.write_pixel(1, 0, Color::new(0.5, 0.5, 0.5))?;
+        canvas.write_pixel(2, 0, Color::new(5, 5, 5))?;
+
+        assert_eq!(canvas.to_ascii(80), " +@\n");
+
+        Ok(())
+    }
+}
@@ -1,11 +1,13 @@
-use crate::color::Color;
+use super::BitDepth;
+use crate::{color::Color, error::RayTracerError};
 use anyhow::{Error, Result};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 /// Representing a rectangular grid of pixels, that hold a
 /// single [Color] each
 pub struct Canvas {
     inner: Vec<Color>,
+    alpha: Option<Vec<f64>>,
     /// The measure of the Width of the canvas (in pixels)
     pub width: usize,
     /// The measure of the Height of the canvas (in pixels)
@@ -28,16 +30,19 @@ impl Canvas {
             width,
             height,
             inner: vec![Color::default(); width * height],
+            alpha: None,
         }
     }
 
-    /// Helper method to generate a consistent out-of-bounds error message
-    fn get_out_of_bounds_error_message(&self, x: &usize, y: &usize) -> String {
-        format!(
-            "Index ({}, {}) is out-of-bounds for \
-        this canvas of size ({}, {})",
-            x, y, self.width, self.height
-        )
+    /// Helper method to generate a consistent out-of-bounds error
+    fn out_of_bounds_error(&self, x: usize, y: usize) -> Error {
+        RayTracerError::CanvasOutOfBounds {
+            x,
+            y,
+            width: self.width,
+            height: self.height,
+        }
+        .into()
     }
 
     /// Helper method to check if a queried index is out-of-bounds in our [Canvas]
@@ -70,7 +75,7 @@ impl Canvas {
     /// ```
     pub fn pixel_at(&self, x: usize, y: usize) -> Result<&Color> {
         if self.is_out_of_bounds(&x, &y) {
-            Err(Error::msg(self.get_out_of_bounds_error_message(&x, &y)))
+            Err(self.out_of_bounds_error(x, y))
         } else {
             Ok(&self.inner[self.map_index(x, y)])
         }
@@ -101,7 +106,7 @@ impl Canvas {
     /// ```
     pub fn write_pixel(&mut self, x: usize, y: usize, color: Color) -> Result<()> {
         if self.is_out_of_bounds(&x, &y) {
-            Err(Error::msg(self.get_out_of_bounds_error_message(&x, &y)))
+            Err(self.out_of_bounds_error(x, y))
         } else {
             let map_index = self.map_index(x, y);
             self.inner[map_index] = color;
@@ -109,6 +114,107 @@ impl Canvas {
         }
     }
 
+    /// Writes a [Color] to the pixel at `(x,y)` if it is on the canvas,
+    /// and silently does nothing if it isn't. Returns whether the pixel
+    /// was written.
+    ///
+    /// This saves plotting code from checking bounds itself, and takes
+    /// signed coordinates since plotted points often land to the left
+    /// of, or above, the canvas.
+    ///
+    /// ```
+    /// use raytracer::{canvas::Canvas, color::Color};
+    ///
+    /// let mut canvas = Canvas::new(10, 10);
+    ///
+    /// assert!(canvas.write_pixel_clipped(3, 4, Color::red()));
+    /// assert!(!canvas.write_pixel_clipped(-1, 4, Color::red()));
+    /// assert_eq!(*canvas.pixel_at(3, 4).unwrap(), Color::red());
+    /// ```
+    pub fn write_pixel_clipped(&mut self, x: isize, y: isize, color: Color) -> bool {
+        match (usize::try_from(x), usize::try_from(y)) {
+            (Ok(x), Ok(y)) => self.write_pixel(x, y, color).is_ok(),
+            _ => false,
+        }
+    }
+
+    /// Writes a [Color] to the pixel at `(x,y)`, wrapping coordinates
+    /// that are off the canvas around to the other side, as if the
+    /// canvas was tiled in every direction. Writes to an empty canvas
+    /// are dropped.
+    ///
+    /// ```
+    /// use raytracer::{canvas::Canvas, color::Color};
+    ///
+    /// let mut canvas = Canvas::new(10, 10);
+    /// canvas.write_pixel_wrapped(-1, 12, Color::red());
+    ///
+    /// assert_eq!(*canvas.pixel_at(9, 2).unwrap(), Color::red());
+    /// ```
+    pub fn write_pixel_wrapped(&mut self, x: isize, y: isize, color: Color) {
+        if self.width == 0 || self.height == 0 {
+            return;
+        }
+
+        let x = x.rem_euclid(self.width as isize) as usize;
+        let y = y.rem_euclid(self.height as isize) as usize;
+        let map_index = self.map_index(x, y);
+        self.inner[map_index] = color;
+    }
+
+    /// Gets the opacity of the pixel at `(x,y)`, from 0 (transparent)
+    /// to 1 (opaque). Every pixel is opaque until an alpha value is
+    /// written to the canvas.
+    /// This returns an error, if the requested pixel is out-of-bounds.
+    pub fn alpha_at(&self, x: usize, y: usize) -> Result<f64> {
+        if self.is_out_of_bounds(&x, &y) {
+            return Err(self.out_of_bounds_error(x, y));
+        }
+
+        Ok(self
+            .alpha
+            .as_ref()
+            .map_or(1.0, |alpha| alpha[self.map_index(x, y)]))
+    }
+
+    /// Writes the opacity of the pixel at `(x,y)`, clamped to \[0, 1\].
+    /// Once a value is written, the canvas carries an alpha channel,
+    /// which image formats that support one will include.
+    /// This returns an error, if the pixel written to is out-of-bounds.
+    ///
+    /// ```
+    /// use raytracer::canvas::Canvas;
+    ///
+    /// let mut canvas = Canvas::new(2, 1);
+    /// assert!(!canvas.has_alpha());
+    ///
+    /// canvas.write_alpha(0, 0, 0.25).unwrap();
+    ///
+    /// assert!(canvas.has_alpha());
+    /// assert_eq!(canvas.alpha_at(0, 0).unwrap(), 0.25);
+    /// assert_eq!(canvas.alpha_at(1, 0).unwrap(), 1.0);
+    /// ```
+    pub fn write_alpha(&mut self, x: usize, y: usize, alpha: f64) -> Result<()> {
+        if self.is_out_of_bounds(&x, &y) {
+            return Err(self.out_of_bounds_error(x, y));
+        }
+
+        let index = self.map_index(x, y);
+        let size = self.width * self.height;
+        self.alpha.get_or_insert_with(|| vec![1.0; size])[index] = alpha.clamp(0.0, 1.0);
+        Ok(())
+    }
+
+    /// Returns true if an alpha value was ever written to the canvas
+    pub fn has_alpha(&self) -> bool {
+        self.alpha.is_some()
+    }
+
+    /// Drops the alpha channel, making every pixel opaque again
+    pub(crate) fn clear_alpha(&mut self) {
+        self.alpha = None;
+    }
+
     /// Convenient way to color the entire canvas with the same [Color]
     ///
     /// ```
@@ -124,22 +230,19 @@ impl Canvas {
     }
 
     /// Builds a PPM header string
-    fn get_ppm_header(&self) -> String {
-        format!("P3\n{} {}\n255\n", self.width, self.height)
+    fn get_ppm_header(&self, max_value: u16) -> String {
+        format!("P3\n{} {}\n{}\n", self.width, self.height, max_value)
     }
 
     /// Builds the body of the PPM file by applying our canvas
     /// into a valid PPM format string
-    fn build_ppm_body(&self) -> Result<String> {
+    fn build_ppm_body(&self, max_value: u16) -> Result<String> {
         let mut pixels = String::new();
 
         for y in 0..self.height {
             for x in 0..self.width {
-                let scaled_color_tuple = self.pixel_at(x, y)?.get_255_scaled_tuple();
-                let scaled_color_string = format!(
-                    "{} {} {}",
-                    scaled_color_tuple.0, scaled_color_tuple.1, scaled_color_tuple.2
-                );
+                let (r, g, b) = self.pixel_at(x, y)?.get_scaled_tuple(max_value);
+                let scaled_color_string = format!("{} {} {}", r, g, b);
 
                 pixels.push_str(scaled_color_string.as_str());
 
@@ -195,11 +298,100 @@ impl Canvas {
     /// // """
     /// ```
     pub fn to_ppm(&self) -> Result<String> {
-        let header = self.get_ppm_header();
-        let pixels = self.build_ppm_body()?;
+        self.to_ppm_with_depth(BitDepth::Eight)
+    }
+
+    /// Same as [Canvas::to_ppm], with a choice of bits per channel.
+    /// With [BitDepth::Sixteen], the header's maximum value is 65535
+    /// and the channels are scaled to match.
+    ///
+    /// ```
+    /// use raytracer::{
+    ///     canvas::{BitDepth, Canvas},
+    ///     color::Color,
+    /// };
+    ///
+    /// let mut canvas = Canvas::new(1, 1);
+    /// canvas.write_pixel(0, 0, Color::new(1, 0.5, 0)).unwrap();
+    ///
+    /// let ppm = canvas.to_ppm_with_depth(BitDepth::Sixteen).unwrap();
+    /// assert_eq!(ppm, "P3\n1 1\n65535\n65535 32767 0\n");
+    /// ```
+    pub fn to_ppm_with_depth(&self, depth: BitDepth) -> Result<String> {
+        self.to_ppm_with_max_value(depth.max_value())
+    }
+
+    /// Same as [Canvas::to_ppm], with the channels scaled to any
+    /// maximum value from 1 to 65535, which is written in the header.
+    ///
+    /// ```
+    /// use raytracer::{canvas::Canvas, color::Color};
+    ///
+    /// let mut canvas = Canvas::new(1, 1);
+    /// canvas.write_pixel(0, 0, Color::new(1, 0.5, 0)).unwrap();
+    ///
+    /// let ppm = canvas.to_ppm_with_max_value(100).unwrap();
+    /// assert_eq!(ppm, "P3\n1 1\n100\n100 50 0\n");
+    /// assert!(canvas.to_ppm_with_max_value(0).is_err());
+    /// ```
+    pub fn to_ppm_with_max_value(&self, max_value: u16) -> Result<String> {
+        BitDepth::check_max_value(max_value)?;
+
+        let header = self.get_ppm_header(max_value);
+        let pixels = self.build_ppm_body(max_value)?;
         Ok(header + &pixels)
     }
 
+    /// Returns all the pixels of the canvas, row by row starting from
+    /// the top left corner
+    pub(crate) fn pixels(&self) -> &[Color] {
+        &self.inner
+    }
+
+    /// Returns all the pixels of the canvas for writing, row by row
+    /// starting from the top left corner
+    pub(crate) fn pixels_mut(&mut self) -> &mut [Color] {
+        &mut self.inner
+    }
+
+    /// Iterates over the pixels of the canvas along with their `(x, y)`
+    /// coordinates. The rows are visited from the top down, and each
+    /// row from left to right, so `y` changes slowest.
+    ///
+    /// ```
+    /// use raytracer::{canvas::Canvas, color::Color};
+    ///
+    /// let canvas = Canvas::new(2, 2);
+    /// let coordinates: Vec<_> = canvas.enumerate_pixels().map(|(x, y, _)| (x, y)).collect();
+    ///
+    /// assert_eq!(coordinates, [(0, 0), (1, 0), (0, 1), (1, 1)]);
+    /// ```
+    pub fn enumerate_pixels(&self) -> impl Iterator<Item = (usize, usize, &Color)> {
+        let width = self.width;
+        self.inner
+            .iter()
+            .enumerate()
+            .map(move |(i, color)| (i % width, i / width, color))
+    }
+
+    /// Same as [Canvas::enumerate_pixels], but lets the pixels be
+    /// changed in place
+    pub fn enumerate_pixels_mut(&mut self) -> impl Iterator<Item = (usize, usize, &mut Color)> {
+        let width = self.width;
+        self.inner
+            .iter_mut()
+            .enumerate()
+            .map(move |(i, color)| (i % width, i / width, color))
+    }
+
+    /// Calls `f` with the coordinates of every pixel and the pixel
+    /// itself, in the same order as [Canvas::enumerate_pixels]
+    pub fn for_each_pixel_mut(&mut self, mut f: impl FnMut(usize, usize, &mut Color)) {
+        for (x, y, color) in self.enumerate_pixels_mut() {
+            f(x, y, color);
+        }
+    }
+
     fn map_index(&self, x: usize, y: usize) -> usize {
         y * self.width + x
     }
@@ -226,6 +418,74 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn enumerating_visits_every_pixel_once_row_by_row() {
+        let canvas = Canvas::new(3, 2);
+
+        let visited: Vec<_> = canvas.enumerate_pixels().map(|(x, y, _)| (x, y)).collect();
+
+        assert_eq!(visited, [(0, 0), (1, 0), (2, 0), (0, 1), (1, 1), (2, 1)]);
+    }
+
+    #[test]
+    fn changes_through_enumerated_pixels_are_kept() -> Result<()> {
+        let mut canvas = Canvas::new(4, 3);
+
+        for (x, y, color) in canvas.enumerate_pixels_mut() {
+            *color = Color::new(x as f64, y as f64, 0);
+        }
+        canvas.for_each_pixel_mut(|_, y, color| color.blue = y as f64 * 2.0);
+
+        for y in 0..3 {
+            for x in 0..4 {
+                assert_eq!(
+                    canvas.pixel_at(x, y)?,
+                    &Color::new(x as f64, y as f64, y as f64 * 2.0)
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn clipped_writes_off_the_canvas_are_dropped() {
+        let mut canvas = Canvas::new(4, 3);
+        let untouched = canvas.clone();
+
+        for (x, y) in [(-1, 0), (0, -1), (4, 0), (0, 3), (isize::MIN, isize::MAX)] {
+            assert!(!canvas.write_pixel_clipped(x, y, Color::red()));
+        }
+
+        assert_eq!(canvas, untouched);
+    }
+
+    #[test]
+    fn clipped_writes_on_the_canvas_match_write_pixel() -> Result<()> {
+        let mut clipped = Canvas::new(4, 3);
+        let mut checked = Canvas::new(4, 3);
+
+        assert!(clipped.write_pixel_clipped(3, 2, Color::blue()));
+        checked.write_pixel(3, 2, Color::blue())?;
+
+        assert_eq!(clipped, checked);
+
+        Ok(())
+    }
+
+    #[test]
+    fn wrapped_writes_tile_the_canvas() -> Result<()> {
+        let mut canvas = Canvas::new(4, 3);
+
+        canvas.write_pixel_wrapped(5, -1, Color::red());
+        canvas.write_pixel_wrapped(-8, 6, Color::blue());
+
+        assert_eq!(canvas.pixel_at(1, 2)?, &Color::red());
+        assert_eq!(canvas.pixel_at(0, 0)?, &Color::blue());
+
+        Ok(())
+    }
+
     #[test]
     fn write_to_canvas() -> Result<()> {
         let w = 10;
@@ -238,6 +498,21 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn alpha_is_clamped_and_bounds_checked() -> Result<()> {
+        let mut canvas = Canvas::new(2, 2);
+
+        canvas.write_alpha(1, 1, 1.5)?;
+        canvas.write_alpha(0, 1, -0.5)?;
+
+        assert_eq!(canvas.alpha_at(1, 1)?, 1.0);
+        assert_eq!(canvas.alpha_at(0, 1)?, 0.0);
+        assert!(canvas.write_alpha(2, 0, 0.5).is_err());
+        assert!(canvas.alpha_at(0, 2).is_err());
+
+        Ok(())
+    }
+
     #[test]
     fn to_ppm() -> Result<()> {
         let w = 5;
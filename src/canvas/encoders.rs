@@ -0,0 +1,532 @@
+use super::Canvas;
+use crate::color::scale_channel;
+use anyhow::{Error, Result};
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// The number of bits used for each color channel of a PPM image.
+/// Eight bits can band smooth gradients, which sixteen bits avoid.
+pub enum BitDepth {
+    /// Channels range from 0 to 255
+    #[default]
+    Eight,
+    /// Channels range from 0 to 65535
+    Sixteen,
+}
+
+impl BitDepth {
+    /// The largest value a channel can take
+    pub fn max_value(&self) -> u16 {
+        match self {
+            BitDepth::Eight => 255,
+            BitDepth::Sixteen => 65535,
+        }
+    }
+
+    /// Returns an error unless `max_value` is a valid maximum color
+    /// value for a PPM image, which is anything from 1 to 65535
+    pub(super) fn check_max_value(max_value: u16) -> Result<()> {
+        if max_value == 0 {
+            return Err(Error::msg(
+                "The maximum color value of a PPM image must be between 1 and 65535",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Scales a channel value in \[0, 1\] to the range of this depth,
+    /// rounding down, so 0.5 becomes 127 with eight bits and 32767 with
+    /// sixteen. Values outside of \[0, 1\] are clamped.
+    pub fn quantize(&self, value: f64) -> u16 {
+        scale_channel(value, self.max_value())
+    }
+}
+
+impl Canvas {
+    /// Returns the 8-bit RGB values of every row of the canvas,
+    /// starting from the top
+    fn rgb_rows(&self) -> impl Iterator<Item = Vec<u8>> + '_ {
+        self.pixels()
+            .chunks(self.width.max(1))
+            .take(self.height)
+            .map(|row| {
+                row.iter()
+                    .flat_map(|c| {
+                        let (r, g, b) = c.get_255_scaled_tuple();
+                        [r as u8, g as u8, b as u8]
+                    })
+                    .collect()
+            })
+    }
+
+    /// Returns the 8-bit RGBA values of every pixel, row by row
+    /// starting from the top left corner. Canvases without an alpha
+    /// channel are fully opaque.
+    ///
+    /// ```
+    /// use raytracer::{canvas::Canvas, color::Color};
+    ///
+    /// let mut canvas = Canvas::new(2, 1);
+    /// canvas.write_pixel(0, 0, Color::red()).unwrap();
+    /// canvas.write_alpha(1, 0, 0.0).unwrap();
+    ///
+    /// assert_eq!(canvas.to_rgba_bytes(), vec![255, 0, 0, 255, 0, 0, 0, 0]);
+    /// ```
+    pub fn to_rgba_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.width * self.height * 4);
+        for (y, row) in self.rgb_rows().enumerate() {
+            for (x, rgb) in row.chunks(3).enumerate() {
+                let alpha = self.alpha_at(x, y).unwrap_or(1.0);
+                bytes.extend(rgb);
+                bytes.push((alpha * 255.0).round() as u8);
+            }
+        }
+        bytes
+    }
+
+    /// Encodes the canvas as a binary (P6) PPM image, which is much
+    /// smaller and faster to read than the text based [Canvas::to_ppm]
+    ///
+    /// ```
+    /// use raytracer::{canvas::Canvas, color::Color};
+    ///
+    /// let mut canvas = Canvas::new(2, 1);
+    /// canvas.write_pixel(0, 0, Color::red()).unwrap();
+    ///
+    /// let ppm = canvas.to_ppm_binary();
+    /// assert_eq!(&ppm[..11], b"P6\n2 1\n255\n");
+    /// assert_eq!(&ppm[11..], &[255, 0, 0, 0, 0, 0]);
+    /// ```
+    pub fn to_ppm_binary(&self) -> Vec<u8> {
+        self.to_ppm_binary_with_depth(BitDepth::Eight)
+    }
+
+    /// Same as [Canvas::to_ppm_binary], with a choice of bits per
+    /// channel. With [BitDepth::Sixteen], each channel takes two bytes,
+    /// most significant first as the PPM format requires.
+    ///
+    /// ```
+    /// use raytracer::{
+    ///     canvas::{BitDepth, Canvas},
+    ///     color::Color,
+    /// };
+    ///
+    /// let mut canvas = Canvas::new(1, 1);
+    /// canvas.write_pixel(0, 0, Color::new(1, 0.5, 0)).unwrap();
+    ///
+    /// let ppm = canvas.to_ppm_binary_with_depth(BitDepth::Sixteen);
+    /// assert_eq!(&ppm[..13], b"P6\n1 1\n65535\n");
+    /// assert_eq!(&ppm[13..], &[0xff, 0xff, 0x7f, 0xff, 0, 0]);
+    /// ```
+    pub fn to_ppm_binary_with_depth(&self, depth: BitDepth) -> Vec<u8> {
+        self.ppm_binary(depth.max_value())
+    }
+
+    /// Same as [Canvas::to_ppm_binary], with channels scaled to any
+    /// maximum value from 1 to 65535. Channels take one byte when the
+    /// maximum is below 256, and two bytes otherwise.
+    ///
+    /// ```
+    /// use raytracer::{canvas::Canvas, color::Color};
+    ///
+    /// let mut canvas = Canvas::new(1, 1);
+    /// canvas.write_pixel(0, 0, Color::new(1, 0.5, 0)).unwrap();
+    ///
+    /// let ppm = canvas.to_ppm_binary_with_max_value(100).unwrap();
+    /// assert_eq!(ppm, b"P6\n1 1\n100\n\x64\x32\x00");
+    /// ```
+    pub fn to_ppm_binary_with_max_value(&self, max_value: u16) -> Result<Vec<u8>> {
+        BitDepth::check_max_value(max_value)?;
+        Ok(self.ppm_binary(max_value))
+    }
+
+    fn ppm_binary(&self, max_value: u16) -> Vec<u8> {
+        let mut bytes = format!("P6\n{} {}\n{}\n", self.width, self.height, max_value).into_bytes();
+
+        for color in self.pixels() {
+            let (r, g, b) = color.get_scaled_tuple(max_value);
+            for channel in [r, g, b] {
+                if max_value < 256 {
+                    bytes.push(channel as u8);
+                } else {
+                    bytes.extend((channel as u16).to_be_bytes());
+                }
+            }
+        }
+
+        bytes
+    }
+
+    /// Encodes the canvas as an uncompressed 24-bit BMP image
+    pub fn to_bmp(&self) -> Vec<u8> {
+        const HEADER_SIZE: u32 = 14 + 40;
+
+        let row_size = (self.width * 3).next_multiple_of(4);
+        let image_size = (row_size * self.height) as u32;
+
+        let mut bytes = Vec::with_capacity((HEADER_SIZE + image_size) as usize);
+
+        // file header
+        bytes.extend(b"BM");
+        bytes.extend((HEADER_SIZE + image_size).to_le_bytes());
+        bytes.extend([0; 4]);
+        bytes.extend(HEADER_SIZE.to_le_bytes());
+
+        // info header, with a positive height so rows are stored bottom up
+        bytes.extend(40_u32.to_le_bytes());
+        bytes.extend((self.width as i32).to_le_bytes());
+        bytes.extend((self.height as i32).to_le_bytes());
+        bytes.extend(1_u16.to_le_bytes());
+        bytes.extend(24_u16.to_le_bytes());
+        bytes.extend(0_u32.to_le_bytes());
+        bytes.extend(image_size.to_le_bytes());
+        bytes.extend(2835_i32.to_le_bytes());
+        bytes.extend(2835_i32.to_le_bytes());
+        bytes.extend([0; 8]);
+
+        let rows: Vec<Vec<u8>> = self.rgb_rows().collect();
+        for row in rows.iter().rev() {
+            let start = bytes.len();
+            for rgb in row.chunks(3) {
+                bytes.extend([rgb[2], rgb[1], rgb[0]]);
+            }
+            bytes.resize(start + row_size, 0);
+        }
+
+        bytes
+    }
+
+    /// Encodes the canvas as a PNG image. The image data is stored
+    /// without compression, which keeps the encoder small at the
+    /// cost of larger files.
+    ///
+    /// Canvases with an alpha channel are stored as RGBA, and all
+    /// others as RGB.
+    pub fn to_png(&self) -> Vec<u8> {
+        let (channels, color_type) = if self.has_alpha() { (4, 6) } else { (3, 2) };
+        let pixels = if self.has_alpha() {
+            self.to_rgba_bytes()
+        } else {
+            self.rgb_rows().flatten().collect()
+        };
+
+        let mut raw = Vec::with_capacity((self.width * channels + 1) * self.height);
+        for row in pixels
+            .chunks((self.width * channels).max(1))
+            .take(self.height)
+        {
+            // every row starts with its filter type, which is "none" here
+            raw.push(0);
+            raw.extend(row);
+        }
+
+        let mut header = Vec::with_capacity(13);
+        header.extend((self.width as u32).to_be_bytes());
+        header.extend((self.height as u32).to_be_bytes());
+        // 8 bits per channel, RGB(A), default compression, filtering and no interlacing
+        header.extend([8, color_type, 0, 0, 0]);
+
+        let mut bytes = b"\x89PNG\r\n\x1a\n".to_vec();
+        write_png_chunk(&mut bytes, b"IHDR", &header);
+        write_png_chunk(&mut bytes, b"IDAT", &zlib_stored(&raw));
+        write_png_chunk(&mut bytes, b"IEND", &[]);
+        bytes
+    }
+}
+
+impl Canvas {
+    /// Encodes the canvas as a PFM (portable float map) image, which
+    /// keeps the raw floating point value of every channel. Unlike the
+    /// other formats nothing is clamped, so colors brighter than white
+    /// survive for later processing.
+    ///
+    /// Following the format, the rows are stored from the bottom of the
+    /// image to the top, with little-endian `f32` values.
+    ///
+    /// ```
+    /// use raytracer::{canvas::Canvas, color::Color};
+    ///
+    /// let mut canvas = Canvas::new(1, 1);
+    /// canvas.write_pixel(0, 0, Color::new(3.5, 0, 0)).unwrap();
+    ///
+    /// let pfm = canvas.to_pfm();
+    /// assert_eq!(&pfm[..12], b"PF\n1 1\n-1.0\n");
+    /// assert_eq!(&pfm[12..16], &3.5_f32.to_le_bytes());
+    /// ```
+    pub fn to_pfm(&self) -> Vec<u8> {
+        let mut bytes = format!("PF\n{} {}\n-1.0\n", self.width, self.height).into_bytes();
+
+        for row in self
+            .pixels()
+            .chunks(self.width.max(1))
+            .take(self.height)
+            .rev()
+        {
+            for color in row {
+                for channel in [color.red, color.green, color.blue] {
+                    bytes.extend((channel as f32).to_le_bytes());
+                }
+            }
+        }
+
+        bytes
+    }
+
+    /// Writes the canvas to a PFM file at `path`, see [Canvas::to_pfm]
+    pub fn write_pfm(&self, path: impl AsRef<Path>) -> Result<()> {
+        std::fs::write(path, self.to_pfm())?;
+        Ok(())
+    }
+}
+
+/// Appends a PNG chunk (length, type, data and checksum) to `bytes`
+fn write_png_chunk(bytes: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    bytes.extend((data.len() as u32).to_be_bytes());
+
+    let start = bytes.len();
+    bytes.extend(kind);
+    bytes.extend(data);
+
+    let crc = crc32(&bytes[start..]);
+    bytes.extend(crc.to_be_bytes());
+}
+
+/// Wraps `data` in a zlib stream made of uncompressed deflate blocks
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    const MAX_BLOCK: usize = u16::MAX as usize;
+
+    let mut bytes = vec![0x78, 0x01];
+
+    let mut blocks = data.chunks(MAX_BLOCK).peekable();
+    if blocks.peek().is_none() {
+        bytes.extend([1, 0, 0, 0xff, 0xff]);
+    }
+
+    while let Some(block) = blocks.next() {
+        let last = blocks.peek().is_none();
+        let len = block.len() as u16;
+
+        bytes.push(last as u8);
+        bytes.extend(len.to_le_bytes());
+        bytes.extend((!len).to_le_bytes());
+        bytes.extend(block);
+    }
+
+    bytes.extend(adler32(data).to_be_bytes());
+    bytes
+}
+
+/// The CRC-32 checksum used by PNG chunks
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = u32::MAX;
+
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+
+    !crc
+}
+
+/// The Adler-32 checksum that ends a zlib stream
+fn adler32(data: &[u8]) -> u32 {
+    const MOD: u32 = 65521;
+
+    let (a, b) = data.iter().fold((1_u32, 0_u32), |(a, b), byte| {
+        let a = (a + *byte as u32) % MOD;
+        (a, (b + a) % MOD)
+    });
+
+    (b << 16) | a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{adler32, crc32, BitDepth};
+    use crate::{canvas::Canvas, color::Color};
+    use anyhow::Result;
+
+    #[test]
+    fn checksums_match_known_values() {
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+        assert_eq!(adler32(b""), 1);
+    }
+
+    #[test]
+    fn sixteen_bit_ppms_keep_more_precision() -> Result<()> {
+        let mut canvas = Canvas::new(256, 1);
+        for x in 0..256 {
+            canvas.write_pixel(x, 0, Color::new(x as f64 / 255.5, 0, 0))?;
+        }
+
+        // read the red channel of every pixel back from the encoded image
+        let decoded_reds = |depth: BitDepth| {
+            let ppm = canvas.to_ppm_binary_with_depth(depth);
+            let header = format!("P6\n256 1\n{}\n", depth.max_value());
+            assert!(ppm.starts_with(header.as_bytes()));
+
+            let bytes_per_channel = if depth == BitDepth::Eight { 1 } else { 2 };
+            ppm[header.len()..]
+                .chunks(3 * bytes_per_channel)
+                .map(|pixel| {
+                    let red = pixel[..bytes_per_channel]
+                        .iter()
+                        .fold(0, |red, &byte| red * 256 + byte as u32);
+                    red as f64 / depth.max_value() as f64
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let worst_error = |depth: BitDepth| {
+            canvas
+                .pixels()
+                .iter()
+                .zip(decoded_reds(depth))
+                .map(|(c, red)| (c.red - red).abs())
+                .fold(0.0, f64::max)
+        };
+
+        assert!(worst_error(BitDepth::Sixteen) < 1.0 / 65535.0);
+        assert!(worst_error(BitDepth::Sixteen) * 100.0 < worst_error(BitDepth::Eight));
+
+        Ok(())
+    }
+
+    #[test]
+    fn eight_bit_depth_matches_the_plain_ppms() -> Result<()> {
+        let mut canvas = Canvas::new(3, 2);
+        canvas.write_pixel(0, 0, Color::new(1.5, 0.5, -0.5))?;
+        canvas.write_pixel(2, 1, Color::new(0.2, 0.4, 0.6))?;
+
+        assert_eq!(canvas.to_ppm_with_depth(BitDepth::Eight)?, canvas.to_ppm()?);
+        assert_eq!(
+            canvas.to_ppm_binary_with_depth(BitDepth::Eight),
+            canvas.to_ppm_binary()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn sixteen_bit_binary_ppms_are_big_endian() {
+        let mut canvas = Canvas::new(1, 1);
+        canvas.fill(Color::new(0.0039, 1, 2));
+
+        let ppm = canvas.to_ppm_binary_with_depth(BitDepth::Sixteen);
+        let header = b"P6\n1 1\n65535\n";
+
+        assert_eq!(&ppm[..header.len()], header);
+        // 0.0039 * 65535 = 255.59, so 0x00ff
+        assert_eq!(&ppm[header.len()..], &[0x00, 0xff, 0xff, 0xff, 0xff, 0xff]);
+    }
+
+    #[test]
+    fn binary_ppm_contains_the_pixels() -> Result<()> {
+        let mut canvas = Canvas::new(2, 2);
+        canvas.write_pixel(1, 1, Color::new(0, 0.5, 1))?;
+
+        let ppm = canvas.to_ppm_binary();
+
+        assert!(ppm.starts_with(b"P6\n2 2\n255\n"));
+        assert_eq!(&ppm[ppm.len() - 3..], &[0, 127, 255]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn bmp_rows_are_padded_and_stored_bottom_up() -> Result<()> {
+        let mut canvas = Canvas::new(1, 2);
+        canvas.write_pixel(0, 0, Color::red())?;
+
+        let bmp = canvas.to_bmp();
+
+        // 54 bytes of headers, then two rows of 3 bytes padded to 4
+        assert_eq!(bmp.len(), 54 + 8);
+        assert_eq!(&bmp[..2], b"BM");
+        assert_eq!(&bmp[2..6], &62_u32.to_le_bytes());
+
+        // the bottom row is black, the top row is red in BGR order
+        assert_eq!(&bmp[54..58], &[0, 0, 0, 0]);
+        assert_eq!(&bmp[58..62], &[0, 0, 255, 0]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn pfm_keeps_float_values_bottom_row_first() -> Result<()> {
+        let mut canvas = Canvas::new(2, 2);
+        canvas.write_pixel(0, 0, Color::new(3.5, 0, 0))?;
+        canvas.write_pixel(1, 1, Color::new(0, 0, -0.25))?;
+
+        let pfm = canvas.to_pfm();
+        let header = b"PF\n2 2\n-1.0\n";
+
+        // the negative scale marks the data as little-endian
+        assert!(pfm.starts_with(header));
+        assert_eq!(pfm.len(), header.len() + 2 * 2 * 3 * 4);
+
+        let values: Vec<f32> = pfm[header.len()..]
+            .chunks(4)
+            .map(|b| f32::from_le_bytes(b.try_into().unwrap()))
+            .collect();
+
+        // the bottom row comes first
+        assert_eq!(&values[..6], &[0.0, 0.0, 0.0, 0.0, 0.0, -0.25]);
+        assert_eq!(&values[6..], &[3.5, 0.0, 0.0, 0.0, 0.0, 0.0]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn png_is_made_of_valid_chunks() {
+        let canvas = Canvas::new(3, 2);
+        let png = canvas.to_png();
+
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+
+        // walk every chunk and check its checksum
+        let mut offset = 8;
+        let mut kinds = vec![];
+        while offset < png.len() {
+            let len = u32::from_be_bytes(png[offset..offset + 4].try_into().unwrap()) as usize;
+            let body = &png[offset + 4..offset + 8 + len];
+            let crc =
+                u32::from_be_bytes(png[offset + 8 + len..offset + 12 + len].try_into().unwrap());
+
+            assert_eq!(crc32(body), crc);
+            kinds.push(String::from_utf8_lossy(&body[..4]).to_string());
+            offset += 12 + len;
+        }
+
+        assert_eq!(kinds, vec!["IHDR", "IDAT", "IEND"]);
+        assert_eq!(offset, png.len());
+    }
+
+    #[test]
+    fn png_stores_the_alpha_channel_when_there_is_one() -> Result<()> {
+        let mut canvas = Canvas::new(2, 1);
+        // the color type follows the width, height and bit depth in IHDR
+        let color_type = |png: &[u8]| png[8 + 8 + 9];
+        let idat_len = |png: &[u8]| u32::from_be_bytes(png[33..37].try_into().unwrap());
+
+        let rgb = canvas.to_png();
+        assert_eq!(color_type(&rgb), 2);
+
+        canvas.write_alpha(1, 0, 0.0)?;
+        let rgba = canvas.to_png();
+        assert_eq!(color_type(&rgba), 6);
+
+        // one extra byte for each of the two pixels
+        assert_eq!(idat_len(&rgba), idat_len(&rgb) + 2);
+
+        Ok(())
+    }
+}
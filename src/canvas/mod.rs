@@ -1,4 +1,14 @@
+mod anaglyph;
+mod ascii;
 #[allow(clippy::module_inception)]
 mod canvas;
+mod encoders;
+mod plot;
+mod sampling;
+mod tone_map;
 
 pub use canvas::Canvas;
+pub use encoders::BitDepth;
+pub use plot::{draw_line, plot_path, PlotBounds};
+pub use sampling::{FilterMode, WrapMode};
+pub use tone_map::ToneMap;
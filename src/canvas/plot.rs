@@ -0,0 +1,458 @@
+use super::Canvas;
+use crate::color::Color;
+use anyhow::{Error, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// The region of world space that is mapped onto a [Canvas] when
+/// plotting. `min_x` lands on the left edge of the canvas and `max_y`
+/// on the top edge, so y grows upwards like in a regular plot.
+pub struct PlotBounds {
+    pub min_x: f64,
+    pub max_x: f64,
+    pub min_y: f64,
+    pub max_y: f64,
+}
+
+impl PlotBounds {
+    /// Create new bounds, checking that each range is finite and
+    /// not empty
+    pub fn new(min_x: f64, max_x: f64, min_y: f64, max_y: f64) -> Result<Self> {
+        let valid = [min_x, max_x, min_y, max_y].iter().all(|v| v.is_finite());
+
+        if !valid || min_x >= max_x || min_y >= max_y {
+            return Err(Error::msg(
+                "Plot bounds must be finite, with each minimum below its maximum",
+            ));
+        }
+
+        Ok(Self {
+            min_x,
+            max_x,
+            min_y,
+            max_y,
+        })
+    }
+
+    /// Maps a point in world space to (fractional) pixel coordinates
+    fn to_canvas(self, canvas: &Canvas, (x, y): (f64, f64)) -> (f64, f64) {
+        let width = (canvas.width.max(1) - 1) as f64;
+        let height = (canvas.height.max(1) - 1) as f64;
+
+        (
+            (x - self.min_x) / (self.max_x - self.min_x) * width,
+            (self.max_y - y) / (self.max_y - self.min_y) * height,
+        )
+    }
+}
+
+/// Plots a path through the given world space points onto the canvas,
+/// connecting each point to the next one with a straight line.
+///
+/// Parts of the path outside of `bounds` are clipped. Non-finite
+/// points are skipped, and break the path into separate pieces.
+///
+/// ```
+/// use raytracer::{
+///     canvas::{plot_path, Canvas, PlotBounds},
+///     color::Color,
+/// };
+///
+/// let mut canvas = Canvas::new(10, 10);
+/// let bounds = PlotBounds::new(0.0, 9.0, 0.0, 9.0).unwrap();
+///
+/// plot_path(&mut canvas, [(0.0, 0.0), (9.0, 9.0)], &bounds, Color::red()).unwrap();
+///
+/// // the bottom left and the top right corners are connected
+/// assert_eq!(*canvas.pixel_at(0, 9).unwrap(), Color::red());
+/// assert_eq!(*canvas.pixel_at(5, 4).unwrap(), Color::red());
+/// assert_eq!(*canvas.pixel_at(9, 0).unwrap(), Color::red());
+/// ```
+pub fn plot_path(
+    canvas: &mut Canvas,
+    points: impl IntoIterator<Item = (f64, f64)>,
+    bounds: &PlotBounds,
+    color: Color,
+) -> Result<()> {
+    let mut previous: Option<(f64, f64)> = None;
+
+    for point in points {
+        if !point.0.is_finite() || !point.1.is_finite() {
+            previous = None;
+            continue;
+        }
+
+        let current = bounds.to_canvas(canvas, point);
+        draw_line(canvas, previous.unwrap_or(current), current, color)?;
+        previous = Some(current);
+    }
+
+    Ok(())
+}
+
+/// Draws a straight line between two points given in (fractional)
+/// pixel coordinates, using Bresenham's line algorithm. The line is
+/// clipped to the canvas first, so only the visible part is drawn.
+pub fn draw_line(
+    canvas: &mut Canvas,
+    from: (f64, f64),
+    to: (f64, f64),
+    color: Color,
+) -> Result<()> {
+    if canvas.width == 0 || canvas.height == 0 {
+        return Ok(());
+    }
+
+    let Some((from, to)) = clip_line(canvas, from, to) else {
+        return Ok(());
+    };
+
+    let (mut x, mut y) = (from.0.round() as i64, from.1.round() as i64);
+    let (x1, y1) = (to.0.round() as i64, to.1.round() as i64);
+
+    let dx = (x1 - x).abs();
+    let dy = -(y1 - y).abs();
+    let sx = if x < x1 { 1 } else { -1 };
+    let sy = if y < y1 { 1 } else { -1 };
+    let mut error = dx + dy;
+
+    loop {
+        canvas.write_pixel(x as usize, y as usize, color)?;
+
+        if x == x1 && y == y1 {
+            return Ok(());
+        }
+
+        let e2 = 2 * error;
+        if e2 >= dy {
+            error += dy;
+            x += sx;
+        }
+        if e2 <= dx {
+            error += dx;
+            y += sy;
+        }
+    }
+}
+
+impl Canvas {
+    /// Draws an anti-aliased line between two points given in
+    /// (fractional) pixel coordinates, using Xiaolin Wu's algorithm.
+    ///
+    /// Along the line, each step covers two neighbouring pixels, split
+    /// by how close the line passes to each of them. The color is
+    /// blended into what is already on the canvas by that coverage,
+    /// `dst * (1 - a) + color * a`. Like the hard version, the line
+    /// reaches half a pixel past each end point; an end pixel it only
+    /// partly reaches has its coverage scaled by how much of it is
+    /// crossed. End points on pixel centres light their pixels fully,
+    /// so lines that meet in a path don't leave a faint dot where they
+    /// join. Parts of the line off the canvas are clipped.
+    ///
+    /// ```
+    /// use raytracer::{canvas::Canvas, color::Color};
+    ///
+    /// let mut canvas = Canvas::new(10, 10);
+    /// canvas.draw_line_aa(0.0, 4.5, 9.0, 4.5, Color::new(1, 1, 1));
+    ///
+    /// // the line runs between two rows, and lights both halfway
+    /// assert_eq!(*canvas.pixel_at(3, 4).unwrap(), Color::new(0.5, 0.5, 0.5));
+    /// assert_eq!(*canvas.pixel_at(3, 5).unwrap(), Color::new(0.5, 0.5, 0.5));
+    /// ```
+    pub fn draw_line_aa(&mut self, x0: f64, y0: f64, x1: f64, y1: f64, color: Color) {
+        if self.width == 0 || self.height == 0 {
+            return;
+        }
+
+        let Some(((x0, y0), (x1, y1))) = clip_line(self, (x0, y0), (x1, y1)) else {
+            return;
+        };
+
+        // walk along the major axis, swapping x and y for steep lines
+        let steep = (y1 - y0).abs() > (x1 - x0).abs();
+        let (mut x0, mut y0, mut x1, mut y1) = if steep {
+            (y0, x0, y1, x1)
+        } else {
+            (x0, y0, x1, y1)
+        };
+        if x0 > x1 {
+            (x0, x1) = (x1, x0);
+            (y0, y1) = (y1, y0);
+        }
+
+        let gradient = if x1 == x0 { 0.0 } else { (y1 - y0) / (x1 - x0) };
+
+        let mut plot = |major: f64, minor: f64, coverage: f64| {
+            let (x, y) = if steep {
+                (minor, major)
+            } else {
+                (major, minor)
+            };
+            self.blend_pixel(x as isize, y as isize, color, coverage);
+        };
+
+        let start = x0.round();
+        let end = x1.round();
+        let mut minor = y0 + gradient * (start - x0);
+
+        let mut major = start;
+        while major <= end {
+            // how much of this column the line crosses, which is less
+            // than all of it only at the ends
+            let gap = (x1.min(major) - x0.max(major) + 1.0).clamp(0.0, 1.0);
+
+            let below = minor.floor();
+            let fraction = minor - below;
+            plot(major, below, (1.0 - fraction) * gap);
+            plot(major, below + 1.0, fraction * gap);
+
+            minor += gradient;
+            major += 1.0;
+        }
+    }
+
+    /// Blends `color` into the pixel at `(x, y)` by `coverage`, if the
+    /// pixel is on the canvas
+    fn blend_pixel(&mut self, x: isize, y: isize, color: Color, coverage: f64) {
+        if coverage <= 0.0 {
+            return;
+        }
+
+        if let (Ok(ux), Ok(uy)) = (usize::try_from(x), usize::try_from(y)) {
+            if let Ok(&existing) = self.pixel_at(ux, uy) {
+                let blended = existing * (1.0 - coverage) + color * coverage;
+                self.write_pixel_clipped(x, y, blended);
+            }
+        }
+    }
+}
+
+/// Clips the line between two points to the pixel area of the canvas
+/// using the Liang-Barsky algorithm. Returns `None` if no part of the
+/// line is visible.
+fn clip_line(
+    canvas: &Canvas,
+    (x0, y0): (f64, f64),
+    (x1, y1): (f64, f64),
+) -> Option<((f64, f64), (f64, f64))> {
+    let max_x = (canvas.width - 1) as f64;
+    let max_y = (canvas.height - 1) as f64;
+    let (dx, dy) = (x1 - x0, y1 - y0);
+
+    let mut t0 = 0.0_f64;
+    let mut t1 = 1.0_f64;
+
+    // each edge is described as p * t <= q
+    for (p, q) in [(-dx, x0), (dx, max_x - x0), (-dy, y0), (dy, max_y - y0)] {
+        if p == 0.0 {
+            if q < 0.0 {
+                return None;
+            }
+        } else {
+            let t = q / p;
+            if p < 0.0 {
+                t0 = t0.max(t);
+            } else {
+                t1 = t1.min(t);
+            }
+        }
+    }
+
+    if t0 > t1 {
+        return None;
+    }
+
+    Some(((x0 + t0 * dx, y0 + t0 * dy), (x0 + t1 * dx, y0 + t1 * dy)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{draw_line, plot_path, PlotBounds};
+    use crate::{canvas::Canvas, color::Color};
+    use anyhow::Result;
+
+    fn lit_pixels(canvas: &Canvas) -> Vec<(usize, usize)> {
+        let mut lit = vec![];
+        for y in 0..canvas.height {
+            for x in 0..canvas.width {
+                if *canvas.pixel_at(x, y).unwrap() != Color::black() {
+                    lit.push((x, y));
+                }
+            }
+        }
+        lit
+    }
+
+    #[test]
+    fn bounds_must_be_valid() {
+        assert!(PlotBounds::new(0.0, 1.0, 0.0, 1.0).is_ok());
+        assert!(PlotBounds::new(1.0, 1.0, 0.0, 1.0).is_err());
+        assert!(PlotBounds::new(0.0, 1.0, 2.0, 1.0).is_err());
+        assert!(PlotBounds::new(0.0, f64::NAN, 0.0, 1.0).is_err());
+    }
+
+    #[test]
+    fn a_parabola_is_plotted_as_connected_pixels() -> Result<()> {
+        let mut canvas = Canvas::new(21, 21);
+        let bounds = PlotBounds::new(-10.0, 10.0, 0.0, 20.0)?;
+
+        // y = x² / 5, sampled coarsely
+        let points = (-10..=10).step_by(5).map(|x| {
+            let x = x as f64;
+            (x, x * x / 5.0)
+        });
+        plot_path(&mut canvas, points, &bounds, Color::green())?;
+
+        let lit = lit_pixels(&canvas);
+        assert!(lit.len() > 6);
+
+        // every lit pixel touches another lit pixel
+        for &(x, y) in &lit {
+            let has_neighbour = lit
+                .iter()
+                .any(|&(nx, ny)| (nx, ny) != (x, y) && nx.abs_diff(x) <= 1 && ny.abs_diff(y) <= 1);
+            assert!(has_neighbour, "pixel ({}, {}) is isolated", x, y);
+        }
+
+        // the vertex of the parabola is at the bottom middle
+        assert_eq!(*canvas.pixel_at(10, 20)?, Color::green());
+
+        Ok(())
+    }
+
+    #[test]
+    fn points_outside_the_bounds_are_clipped() -> Result<()> {
+        let mut canvas = Canvas::new(10, 10);
+        let bounds = PlotBounds::new(0.0, 9.0, 0.0, 9.0)?;
+
+        // entirely outside, including a point very far away
+        plot_path(
+            &mut canvas,
+            [(20.0, 20.0), (1e12, -1e12), (-5.0, 30.0)],
+            &bounds,
+            Color::red(),
+        )?;
+        assert!(lit_pixels(&canvas).is_empty());
+
+        // a line crossing the canvas only draws the visible part
+        plot_path(
+            &mut canvas,
+            [(-100.0, 4.0), (100.0, 4.0)],
+            &bounds,
+            Color::red(),
+        )?;
+        let lit = lit_pixels(&canvas);
+        assert_eq!(lit.len(), 10);
+        assert!(lit.iter().all(|&(_, y)| y == 5));
+
+        Ok(())
+    }
+
+    #[test]
+    fn non_finite_points_break_the_path() -> Result<()> {
+        let mut canvas = Canvas::new(10, 10);
+        let bounds = PlotBounds::new(0.0, 9.0, 0.0, 9.0)?;
+
+        plot_path(
+            &mut canvas,
+            [(0.0, 0.0), (f64::NAN, 1.0), (9.0, 0.0)],
+            &bounds,
+            Color::red(),
+        )?;
+
+        assert_eq!(lit_pixels(&canvas), vec![(0, 9), (9, 9)]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn drawing_a_single_point() -> Result<()> {
+        let mut canvas = Canvas::new(3, 3);
+        draw_line(&mut canvas, (1.0, 1.0), (1.0, 1.0), Color::red())?;
+        assert_eq!(lit_pixels(&canvas), vec![(1, 1)]);
+        Ok(())
+    }
+
+    #[test]
+    fn a_diagonal_aa_line_lights_both_sides_evenly() {
+        let mut canvas = Canvas::new(10, 11);
+        let white = Color::new(1, 1, 1);
+        canvas.draw_line_aa(0.0, 0.5, 9.0, 9.5, white);
+
+        for x in 0..10 {
+            let above = *canvas.pixel_at(x, x).unwrap();
+            let below = *canvas.pixel_at(x, x + 1).unwrap();
+
+            assert_eq!(above, Color::new(0.5, 0.5, 0.5));
+            assert_eq!(below, above);
+        }
+        assert_eq!(lit_pixels(&canvas).len(), 20);
+
+        // a vertical line between two columns lights both halfway
+        let mut steep = Canvas::new(5, 10);
+        steep.draw_line_aa(2.5, 0.0, 2.5, 9.0, white);
+        for y in 0..10 {
+            assert_eq!(*steep.pixel_at(2, y).unwrap(), Color::new(0.5, 0.5, 0.5));
+            assert_eq!(*steep.pixel_at(3, y).unwrap(), Color::new(0.5, 0.5, 0.5));
+        }
+    }
+
+    #[test]
+    fn a_horizontal_aa_line_matches_the_hard_line() -> Result<()> {
+        let mut hard = Canvas::new(10, 5);
+        let mut smooth = Canvas::new(10, 5);
+
+        draw_line(&mut hard, (1.0, 2.0), (8.0, 2.0), Color::red())?;
+        smooth.draw_line_aa(8.0, 2.0, 1.0, 2.0, Color::red());
+
+        assert_eq!(smooth, hard);
+
+        Ok(())
+    }
+
+    #[test]
+    fn aa_line_ends_are_lit_by_how_far_the_line_reaches() -> Result<()> {
+        let white = Color::new(1, 1, 1);
+
+        // the line stops a quarter pixel short of both end pixels' far edges
+        let mut canvas = Canvas::new(8, 5);
+        canvas.draw_line_aa(2.25, 2.0, 5.75, 2.0, white);
+
+        assert_eq!(*canvas.pixel_at(2, 2)?, Color::new(0.75, 0.75, 0.75));
+        assert_eq!(*canvas.pixel_at(6, 2)?, Color::new(0.75, 0.75, 0.75));
+        for x in 3..=5 {
+            assert_eq!(*canvas.pixel_at(x, 2)?, white);
+        }
+        assert_eq!(*canvas.pixel_at(1, 2)?, Color::black());
+        assert_eq!(*canvas.pixel_at(7, 2)?, Color::black());
+
+        // between two rows, the end coverage is split across both
+        let mut canvas = Canvas::new(8, 5);
+        canvas.draw_line_aa(2.25, 2.5, 5.75, 2.5, white);
+
+        for (x, y) in [(2, 2), (2, 3), (6, 2), (6, 3)] {
+            assert_eq!(*canvas.pixel_at(x, y)?, Color::new(0.375, 0.375, 0.375));
+        }
+        assert_eq!(*canvas.pixel_at(4, 2)?, Color::new(0.5, 0.5, 0.5));
+
+        Ok(())
+    }
+
+    #[test]
+    fn aa_lines_blend_into_the_canvas_and_clip() -> Result<()> {
+        let mut canvas = Canvas::new(5, 5);
+        canvas.fill(Color::red());
+
+        canvas.draw_line_aa(-10.0, 1.25, 20.0, 1.25, Color::blue());
+
+        assert_eq!(*canvas.pixel_at(2, 1)?, Color::new(0.25, 0, 0.75));
+        assert_eq!(*canvas.pixel_at(2, 2)?, Color::new(0.75, 0, 0.25));
+        assert_eq!(*canvas.pixel_at(2, 0)?, Color::red());
+
+        // entirely off the canvas
+        canvas.draw_line_aa(-10.0, -1.0, -1.0, -10.0, Color::blue());
+        canvas.draw_line_aa(0.0, 1e12, 4.0, 1e12, Color::blue());
+        assert_eq!(*canvas.pixel_at(0, 0)?, Color::red());
+
+        Ok(())
+    }
+}
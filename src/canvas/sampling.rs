@@ -0,0 +1,226 @@
+use super::Canvas;
+use crate::color::Color;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// How a texture lookup between pixel centers is filtered
+pub enum FilterMode {
+    /// Uses the single closest pixel, which looks blocky when a small
+    /// image is stretched over a large surface
+    Nearest,
+    /// Blends the four closest pixels by their distance
+    #[default]
+    Bilinear,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// What a texture lookup does past the edges of the image
+pub enum WrapMode {
+    /// Repeats the edge pixels
+    #[default]
+    Clamp,
+    /// Tiles the image, so the far edge continues from the near one.
+    /// This is what closes the seam of a spherical map.
+    Repeat,
+}
+
+impl WrapMode {
+    /// Maps a pixel index, which may be out of range, onto the canvas
+    fn apply(&self, index: i64, size: usize) -> usize {
+        match self {
+            WrapMode::Clamp => index.clamp(0, size as i64 - 1) as usize,
+            WrapMode::Repeat => index.rem_euclid(size as i64) as usize,
+        }
+    }
+}
+
+impl Canvas {
+    /// Samples the canvas at the texture coordinates (u, v). `u` runs
+    /// from the left edge (0) to the right edge (1), and `v` from the
+    /// top edge (0) to the bottom edge (1). Coordinates past an edge
+    /// are handled by `wrap_u` and `wrap_v`.
+    ///
+    /// An empty canvas always samples as black.
+    ///
+    /// ```
+    /// use raytracer::{
+    ///     canvas::{Canvas, FilterMode, WrapMode},
+    ///     color::Color,
+    /// };
+    ///
+    /// let mut canvas = Canvas::new(2, 1);
+    /// canvas.write_pixel(1, 0, Color::new(1, 1, 1)).unwrap();
+    ///
+    /// let nearest = canvas.sample(0.6, 0.5, FilterMode::Nearest, WrapMode::Clamp, WrapMode::Clamp);
+    /// assert_eq!(nearest, Color::new(1, 1, 1));
+    /// ```
+    pub fn sample(
+        &self,
+        u: f64,
+        v: f64,
+        filter: FilterMode,
+        wrap_u: WrapMode,
+        wrap_v: WrapMode,
+    ) -> Color {
+        if self.width == 0 || self.height == 0 || !u.is_finite() || !v.is_finite() {
+            return Color::black();
+        }
+
+        let pixel = |x: f64, y: f64| {
+            let column = wrap_u.apply(x as i64, self.width);
+            let row = wrap_v.apply(y as i64, self.height);
+            self.pixels()[row * self.width + column]
+        };
+
+        let x = u * self.width as f64;
+        let y = v * self.height as f64;
+
+        match filter {
+            FilterMode::Nearest => pixel(x.floor(), y.floor()),
+            FilterMode::Bilinear => {
+                // position relative to the pixel centers
+                let (x, y) = (x - 0.5, y - 0.5);
+                let (x0, y0) = (x.floor(), y.floor());
+                let (fx, fy) = (x - x0, y - y0);
+
+                let top = pixel(x0, y0) * (1.0 - fx) + pixel(x0 + 1.0, y0) * fx;
+                let bottom = pixel(x0, y0 + 1.0) * (1.0 - fx) + pixel(x0 + 1.0, y0 + 1.0) * fx;
+
+                top * (1.0 - fy) + bottom * fy
+            }
+        }
+    }
+
+    /// Samples the canvas at the texture coordinates (u, v), blending
+    /// the four nearest pixels. `u` runs from the left edge (0) to the
+    /// right edge (1) and wraps around, while `v` runs from the top
+    /// edge (0) to the bottom edge (1) and is clamped. This is the
+    /// lookup that suits an equirectangular panorama.
+    ///
+    /// An empty canvas always samples as black.
+    ///
+    /// ```
+    /// use raytracer::{canvas::Canvas, color::Color};
+    ///
+    /// let mut canvas = Canvas::new(2, 1);
+    /// canvas.write_pixel(1, 0, Color::new(1, 1, 1)).unwrap();
+    ///
+    /// // halfway between the centers of the two pixels
+    /// assert_eq!(canvas.sample_bilinear(0.5, 0.5), Color::new(0.5, 0.5, 0.5));
+    /// ```
+    pub fn sample_bilinear(&self, u: f64, v: f64) -> Color {
+        self.sample(
+            u,
+            v,
+            FilterMode::Bilinear,
+            WrapMode::Repeat,
+            WrapMode::Clamp,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FilterMode, WrapMode};
+    use crate::{canvas::Canvas, color::Color};
+    use anyhow::Result;
+
+    #[test]
+    fn sampling_a_pixel_center_returns_its_color() -> Result<()> {
+        let mut canvas = Canvas::new(4, 2);
+        canvas.write_pixel(2, 1, Color::red())?;
+
+        assert_eq!(canvas.sample_bilinear(2.5 / 4.0, 1.5 / 2.0), Color::red());
+        assert_eq!(canvas.sample_bilinear(0.5 / 4.0, 0.5 / 2.0), Color::black());
+
+        Ok(())
+    }
+
+    #[test]
+    fn sampling_wraps_horizontally_and_clamps_vertically() -> Result<()> {
+        let mut canvas = Canvas::new(4, 2);
+        canvas.write_pixel(0, 0, Color::new(1, 1, 1))?;
+        canvas.write_pixel(3, 0, Color::new(1, 1, 1))?;
+
+        // the seam between the last and the first column blends them
+        assert_eq!(canvas.sample_bilinear(0.0, 0.0), Color::new(1, 1, 1));
+        assert_eq!(canvas.sample_bilinear(1.0, 0.0), Color::new(1, 1, 1));
+
+        // beyond the top and bottom edges the edge rows are used
+        assert_eq!(canvas.sample_bilinear(0.5 / 4.0, -1.0), Color::new(1, 1, 1));
+        assert_eq!(canvas.sample_bilinear(0.5 / 4.0, 2.0), Color::black());
+
+        Ok(())
+    }
+
+    #[test]
+    fn bilinear_matches_nearest_on_pixel_centers() -> Result<()> {
+        let mut canvas = Canvas::new(3, 3);
+        canvas.write_pixel(1, 1, Color::new(0.2, 0.4, 0.6))?;
+        canvas.write_pixel(2, 1, Color::new(1, 1, 1))?;
+
+        for (x, y) in [(0, 0), (1, 1), (2, 1), (2, 2)] {
+            let (u, v) = ((x as f64 + 0.5) / 3.0, (y as f64 + 0.5) / 3.0);
+            let nearest =
+                canvas.sample(u, v, FilterMode::Nearest, WrapMode::Clamp, WrapMode::Clamp);
+            let bilinear =
+                canvas.sample(u, v, FilterMode::Bilinear, WrapMode::Clamp, WrapMode::Clamp);
+
+            assert_eq!(nearest, bilinear);
+            assert_eq!(nearest, *canvas.pixel_at(x, y)?);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn nearest_sampling_is_blocky() -> Result<()> {
+        let mut canvas = Canvas::new(2, 1);
+        canvas.write_pixel(1, 0, Color::new(1, 1, 1))?;
+
+        let sample = |u| {
+            canvas.sample(
+                u,
+                0.5,
+                FilterMode::Nearest,
+                WrapMode::Clamp,
+                WrapMode::Clamp,
+            )
+        };
+
+        assert_eq!(sample(0.49), Color::black());
+        assert_eq!(sample(0.51), Color::new(1, 1, 1));
+
+        Ok(())
+    }
+
+    #[test]
+    fn repeating_closes_the_seam_and_clamping_does_not() -> Result<()> {
+        // a spherical map whose first column is white and last is black
+        let mut canvas = Canvas::new(4, 2);
+        for y in 0..2 {
+            canvas.write_pixel(0, y, Color::new(1, 1, 1))?;
+        }
+
+        let seam = |wrap| canvas.sample(0.0, 0.5, FilterMode::Bilinear, wrap, WrapMode::Clamp);
+
+        assert_eq!(seam(WrapMode::Repeat), Color::new(0.5, 0.5, 0.5));
+        assert_eq!(seam(WrapMode::Clamp), Color::new(1, 1, 1));
+        assert_eq!(
+            canvas.sample(
+                1.0,
+                0.5,
+                FilterMode::Bilinear,
+                WrapMode::Repeat,
+                WrapMode::Clamp
+            ),
+            Color::new(0.5, 0.5, 0.5)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn empty_canvases_sample_as_black() {
+        assert_eq!(Canvas::new(0, 0).sample_bilinear(0.5, 0.5), Color::black());
+    }
+}
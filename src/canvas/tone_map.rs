@@ -0,0 +1,143 @@
+use super::Canvas;
+use crate::color::Color;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+/// Operators that compress the unbounded brightness of a render into
+/// the \[0, 1\] range that image files can store. Each one is applied
+/// to the red, green and blue channels separately.
+pub enum ToneMap {
+    /// Leaves every value as it is
+    #[default]
+    None,
+    /// Clips every value to \[0, 1\], losing detail in highlights
+    Clamp,
+    /// `x / (1 + x)`, which never quite reaches 1
+    Reinhard,
+    /// `x * (1 + x / w²) / (1 + x)`, which reaches 1 at the white point
+    /// `w`. Anything brighter than the white point is clipped to 1.
+    ReinhardExtended { white_point: f64 },
+    /// A fitted approximation of the ACES filmic curve
+    AcesApprox,
+}
+
+impl ToneMap {
+    /// Applies the operator to a single channel value
+    pub fn map_value(&self, x: f64) -> f64 {
+        match *self {
+            ToneMap::None => x,
+            ToneMap::Clamp => x.clamp(0.0, 1.0),
+            ToneMap::Reinhard => {
+                let x = x.max(0.0);
+                x / (1.0 + x)
+            }
+            ToneMap::ReinhardExtended { white_point } => {
+                let x = x.max(0.0);
+                let mapped = x * (1.0 + x / (white_point * white_point)) / (1.0 + x);
+                mapped.min(1.0)
+            }
+            ToneMap::AcesApprox => {
+                let x = x.max(0.0);
+                let mapped = (x * (2.51 * x + 0.03)) / (x * (2.43 * x + 0.59) + 0.14);
+                mapped.clamp(0.0, 1.0)
+            }
+        }
+    }
+
+    /// Applies the operator to each channel of the color
+    pub fn map_color(&self, color: &Color) -> Color {
+        Color::new(
+            self.map_value(color.red),
+            self.map_value(color.green),
+            self.map_value(color.blue),
+        )
+    }
+}
+
+impl Canvas {
+    /// Returns a copy of the canvas with the tone mapping operator
+    /// applied to every pixel. The canvas itself keeps its linear
+    /// values, so it can be mapped again with a different operator.
+    ///
+    /// ```
+    /// use raytracer::{
+    ///     canvas::{Canvas, ToneMap},
+    ///     color::Color,
+    /// };
+    ///
+    /// let mut canvas = Canvas::new(1, 1);
+    /// canvas.write_pixel(0, 0, Color::new(3, 1, 0)).unwrap();
+    ///
+    /// let mapped = canvas.tone_mapped(ToneMap::Reinhard);
+    /// assert_eq!(*mapped.pixel_at(0, 0).unwrap(), Color::new(0.75, 0.5, 0));
+    /// assert_eq!(*canvas.pixel_at(0, 0).unwrap(), Color::new(3, 1, 0));
+    /// ```
+    pub fn tone_mapped(&self, op: ToneMap) -> Canvas {
+        let mut mapped = self.clone();
+        mapped.for_each_pixel_mut(|_, _, pixel| *pixel = op.map_color(pixel));
+        mapped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ToneMap;
+    use crate::{canvas::Canvas, color::Color, utils::float_equals};
+    use anyhow::Result;
+
+    #[test]
+    fn reinhard_follows_its_formula() {
+        let op = ToneMap::Reinhard;
+
+        assert_eq!(op.map_value(0.0), 0.0);
+        assert_eq!(op.map_value(1.0), 0.5);
+        assert_eq!(op.map_value(3.0), 0.75);
+        assert!(op.map_value(1e9) < 1.0);
+    }
+
+    #[test]
+    fn none_is_the_identity() -> Result<()> {
+        let mut canvas = Canvas::new(2, 1);
+        canvas.write_pixel(0, 0, Color::new(5, -1, 0.25))?;
+        canvas.write_pixel(1, 0, Color::new(0.5, 100, 2))?;
+
+        let mapped = canvas.tone_mapped(ToneMap::None);
+
+        for x in 0..2 {
+            assert_eq!(mapped.pixel_at(x, 0)?, canvas.pixel_at(x, 0)?);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn extended_reinhard_saturates_at_the_white_point() {
+        let op = ToneMap::ReinhardExtended { white_point: 4.0 };
+
+        assert!(float_equals(&op.map_value(4.0), &1.0));
+        assert!(op.map_value(2.0) < 1.0);
+        assert!(op.map_value(2.0) > ToneMap::Reinhard.map_value(2.0));
+
+        for x in [4.5, 8.0, 1000.0] {
+            assert!(op.map_value(x) <= 1.0);
+        }
+    }
+
+    #[test]
+    fn clamp_and_aces_stay_in_range() {
+        for x in [-1.0, 0.0, 0.18, 1.0, 10.0, 1e6] {
+            for op in [ToneMap::Clamp, ToneMap::AcesApprox] {
+                let mapped = op.map_value(x);
+                assert!(
+                    (0.0..=1.0).contains(&mapped),
+                    "{:?}({}) = {}",
+                    op,
+                    x,
+                    mapped
+                );
+            }
+        }
+
+        assert_eq!(ToneMap::Clamp.map_value(0.3), 0.3);
+        assert!(ToneMap::AcesApprox.map_value(0.5) < ToneMap::AcesApprox.map_value(1.0));
+    }
+}
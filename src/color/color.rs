@@ -3,6 +3,7 @@ use std::fmt::Display;
 use std::ops;
 
 #[derive(Debug, Clone, Copy, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// Representation of colors using RGB values
 pub struct Color {
     pub red: f64,
@@ -92,6 +93,59 @@ impl Color {
         Self::new(0, 0, 1)
     }
 
+    /// Quick shortcut method to create the color Magenta (1,0,1)
+    ///
+    /// ```
+    /// use raytracer::color::Color;
+    ///
+    /// let m = Color::magenta();
+    /// assert_eq!(m.red, 1.0);
+    /// assert_eq!(m.green, 0.0);
+    /// assert_eq!(m.blue, 1.0);
+    /// ```
+    pub fn magenta() -> Self {
+        Self::new(1, 0, 1)
+    }
+
+    /// The color of a blackbody at `temperature` kelvin, using Tanner
+    /// Helland's fit to the blackbody curve. Candle light is around
+    /// 1900K, a warm bulb 2700K, and daylight 6500K, which comes out
+    /// within 2% of white. Temperatures are clamped to the 1000K to
+    /// 40000K range that the fit covers.
+    ///
+    /// ```
+    /// use raytracer::color::Color;
+    ///
+    /// let warm = Color::from_kelvin(2700.0);
+    /// assert!(warm.red > warm.green && warm.green > warm.blue);
+    /// ```
+    pub fn from_kelvin(temperature: f64) -> Self {
+        // the fit works in hundreds of kelvin
+        let t = temperature.clamp(1000.0, 40000.0) / 100.0;
+
+        let red = if t <= 66.0 {
+            255.0
+        } else {
+            329.698727446 * (t - 60.0).powf(-0.1332047592)
+        };
+
+        let green = if t <= 66.0 {
+            99.4708025861 * t.ln() - 161.1195681661
+        } else {
+            288.1221695283 * (t - 60.0).powf(-0.0755148492)
+        };
+
+        let blue = if t >= 66.0 {
+            255.0
+        } else if t <= 19.0 {
+            0.0
+        } else {
+            138.5177312231 * (t - 10.0).ln() - 305.0447927307
+        };
+
+        Self::new(red, green, blue).clamp(0.0, 255.0) * (1.0 / 255.0)
+    }
+
     /// The `hadamard_product` is an operation that yields a
     /// new [Color] obtained by multiplying the individual
     /// elements of the two input [Color]s.
@@ -118,11 +172,43 @@ impl Color {
     /// assert_eq!(c1.get_255_scaled_tuple(), (255, 51, 102));
     /// ```
     pub fn get_255_scaled_tuple(&self) -> (usize, usize, usize) {
-        let r = ((self.red * 255.0) as usize).clamp(0, 255);
-        let g = ((self.green * 255.0) as usize).clamp(0, 255);
-        let b = ((self.blue * 255.0) as usize).clamp(0, 255);
-        (r, g, b)
+        self.get_scaled_tuple(255)
     }
+
+    /// Scales the color into integer values so the RGB components
+    /// fall in the range \[0, max\], rounding down, and returns them
+    /// as a simple tuple
+    ///
+    /// ```
+    /// use raytracer::color::Color;
+    ///
+    /// let c1 = Color::from((1, 0.5, 0));
+    /// assert_eq!(c1.get_scaled_tuple(100), (100, 50, 0));
+    /// ```
+    pub fn get_scaled_tuple(&self, max: u16) -> (usize, usize, usize) {
+        let scale = |v: f64| scale_channel(v, max) as usize;
+        (scale(self.red), scale(self.green), scale(self.blue))
+    }
+
+    /// Restricts each component of the color to the range
+    /// \[min, max\]. NaN components become `min`.
+    ///
+    /// ```
+    /// use raytracer::color::Color;
+    ///
+    /// let c = Color::new(1.5, -0.2, 0.4).clamp(0.0, 1.0);
+    /// assert_eq!(c, Color::new(1, 0, 0.4));
+    /// ```
+    pub fn clamp(&self, min: f64, max: f64) -> Self {
+        let clamp = |v: f64| if v.is_nan() { min } else { v.clamp(min, max) };
+        Self::new(clamp(self.red), clamp(self.green), clamp(self.blue))
+    }
+}
+
+/// Scales a single color channel to an integer in \[0, max\], rounding
+/// down. Values outside of \[0, 1\] are clamped.
+pub(crate) fn scale_channel(value: f64, max: u16) -> u16 {
+    ((value * max as f64) as usize).clamp(0, max as usize) as u16
 }
 
 impl PartialEq for Color {
@@ -335,4 +421,30 @@ mod tests {
         let c1 = Color::from((1, 0.2, 0.4));
         assert_eq!(c1.get_255_scaled_tuple(), (255, 51, 102));
     }
+
+    #[test]
+    fn blackbody_colors_follow_the_temperature() {
+        let warm = Color::from_kelvin(2700.0);
+        assert!(warm.red > warm.blue);
+
+        let cool = Color::from_kelvin(10000.0);
+        assert!(cool.blue > cool.red);
+
+        let daylight = Color::from_kelvin(6500.0);
+        for channel in [daylight.red, daylight.green, daylight.blue] {
+            assert!((channel - 1.0).abs() < 0.02);
+        }
+
+        // out of range temperatures use the nearest end of the fit
+        assert_eq!(Color::from_kelvin(0.0), Color::from_kelvin(1000.0));
+        assert_eq!(Color::from_kelvin(1e6), Color::from_kelvin(40000.0));
+    }
+
+    #[test]
+    fn get_scaled_tuple_uses_the_given_maximum() {
+        let c = Color::new(0.5, 1, 1.7);
+        assert_eq!(c.get_scaled_tuple(100), (50, 100, 100));
+        assert_eq!(c.get_scaled_tuple(1), (0, 1, 1));
+        assert_eq!(c.get_scaled_tuple(255), c.get_255_scaled_tuple());
+    }
 }
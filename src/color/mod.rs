@@ -1,4 +1,5 @@
 #[allow(clippy::module_inception)]
 mod color;
 
+pub(crate) use color::scale_channel;
 pub use color::Color;
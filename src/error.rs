@@ -0,0 +1,177 @@
+use crate::spatial::Tuple;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The errors returned by the core of the ray tracer.
+///
+/// Fallible functions still return an [anyhow::Result], so these
+/// errors carry through `?` like any other. Callers that need to tell
+/// failures apart can downcast to this type.
+///
+/// ```
+/// use raytracer::{canvas::Canvas, color::Color, error::RayTracerError};
+///
+/// let mut canvas = Canvas::new(2, 2);
+/// let err = canvas.write_pixel(5, 0, Color::red()).unwrap_err();
+///
+/// assert!(matches!(
+///     err.downcast_ref::<RayTracerError>(),
+///     Some(RayTracerError::CanvasOutOfBounds { x: 5, y: 0, .. })
+/// ));
+/// ```
+pub enum RayTracerError {
+    /// A pixel outside of a canvas was read or written
+    CanvasOutOfBounds {
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    },
+    /// A matrix that has no inverse was inverted
+    NonInvertibleMatrix,
+    /// An object was given a transform that has no inverse, such as a
+    /// scaling by 0. `object` names it, e.g. `Sphere(67e55044)`.
+    NonInvertibleTransform { object: String },
+    /// A tuple was a point where a vector was needed, or the other way
+    /// around
+    InvalidTuple {
+        expected: &'static str,
+        got: &'static str,
+    },
+    /// Two sizes that need to match don't. Images are sized as
+    /// (width, height) and matrices as (rows, columns).
+    InvalidDimensions {
+        expected: (usize, usize),
+        got: (usize, usize),
+    },
+}
+
+impl RayTracerError {
+    /// Builds an [RayTracerError::InvalidTuple] for a tuple that should
+    /// have been a `expected`
+    pub(crate) fn invalid_tuple(expected: &'static str, tuple: &Tuple) -> Self {
+        let got = if tuple.is_a_point() {
+            "point"
+        } else if tuple.is_a_vector() {
+            "vector"
+        } else {
+            "tuple"
+        };
+
+        Self::InvalidTuple { expected, got }
+    }
+}
+
+impl fmt::Display for RayTracerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CanvasOutOfBounds {
+                x,
+                y,
+                width,
+                height,
+            } => write!(
+                f,
+                "Index ({}, {}) is out-of-bounds for this canvas of size ({}, {})",
+                x, y, width, height
+            ),
+            Self::NonInvertibleMatrix => write!(f, "Matrix is not invertible"),
+            Self::NonInvertibleTransform { object } => {
+                write!(f, "The transform of {} is not invertible", object)
+            }
+            Self::InvalidTuple { expected, got } => {
+                write!(f, "Expected a {}, but got a {}", expected, got)
+            }
+            Self::InvalidDimensions { expected, got } => write!(
+                f,
+                "Expected dimensions {}x{}, but got {}x{}",
+                expected.0, expected.1, got.0, got.1
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RayTracerError {}
+
+#[cfg(test)]
+mod tests {
+    use super::RayTracerError;
+    use crate::{
+        canvas::Canvas,
+        color::Color,
+        intersections::Ray,
+        lights::PointLight,
+        matrix::{inverse_4x4, scaling, Matrix},
+        spatial::Tuple,
+    };
+
+    fn kind_of<T>(result: anyhow::Result<T>) -> Option<RayTracerError> {
+        result.err()?.downcast_ref::<RayTracerError>().cloned()
+    }
+
+    #[test]
+    fn canvas_reports_the_pixel_and_its_size() {
+        let mut canvas = Canvas::new(3, 2);
+
+        let expected = Some(RayTracerError::CanvasOutOfBounds {
+            x: 1,
+            y: 2,
+            width: 3,
+            height: 2,
+        });
+
+        assert_eq!(kind_of(canvas.pixel_at(1, 2)), expected);
+        assert_eq!(kind_of(canvas.write_pixel(1, 2, Color::red())), expected);
+    }
+
+    #[test]
+    fn singular_matrices_cannot_be_inverted() {
+        assert_eq!(
+            kind_of(inverse_4x4(&scaling(1, 0, 1))),
+            Some(RayTracerError::NonInvertibleMatrix)
+        );
+    }
+
+    #[test]
+    fn mismatched_matrices_report_their_dimensions() {
+        let a = Matrix::<2, 2>::default();
+        let b = Matrix::<2, 3>::default();
+
+        assert_eq!(
+            kind_of(a.multiply(&b)),
+            Some(RayTracerError::InvalidDimensions {
+                expected: (2, 2),
+                got: (2, 3),
+            })
+        );
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn rays_and_lights_check_their_tuples() {
+        let point = Tuple::point(0, 0, 0);
+        let vector = Tuple::vector(0, 0, 1);
+
+        assert_eq!(
+            kind_of(Ray::from_tuples(vector, vector)),
+            Some(RayTracerError::InvalidTuple {
+                expected: "point",
+                got: "vector"
+            })
+        );
+        assert_eq!(
+            kind_of(Ray::from_tuples(point, point)),
+            Some(RayTracerError::InvalidTuple {
+                expected: "vector",
+                got: "point"
+            })
+        );
+        assert_eq!(
+            kind_of(PointLight::from_tuple(vector, Color::red())),
+            Some(RayTracerError::InvalidTuple {
+                expected: "point",
+                got: "vector"
+            })
+        );
+    }
+}
@@ -0,0 +1,142 @@
+use crate::canvas::Canvas;
+use anyhow::Result;
+use std::{fmt, path::Path};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The image formats a [Canvas] can be written to
+pub enum ImageFormat {
+    /// Plain text PPM (P3)
+    PpmText,
+    /// Binary PPM (P6)
+    PpmBinary,
+    Png,
+    Bmp,
+    /// Portable float map, which keeps unclamped HDR values
+    Pfm,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The error returned when a file name doesn't end in the extension
+/// of a supported [ImageFormat]
+pub struct UnsupportedFormat {
+    pub extension: String,
+}
+
+impl fmt::Display for UnsupportedFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Unsupported image format '{}', expected one of: ppm, pbm, png, bmp, pfm",
+            self.extension
+        )
+    }
+}
+
+impl std::error::Error for UnsupportedFormat {}
+
+impl ImageFormat {
+    /// Picks the format from the extension of `path`, ignoring case.
+    /// `.ppm` files are written as text, and `.pbm` files as binary PPM.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self> {
+        let extension = path
+            .as_ref()
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+
+        match extension.as_str() {
+            "ppm" => Ok(Self::PpmText),
+            "pbm" => Ok(Self::PpmBinary),
+            "png" => Ok(Self::Png),
+            "bmp" => Ok(Self::Bmp),
+            "pfm" => Ok(Self::Pfm),
+            _ => Err(UnsupportedFormat { extension }.into()),
+        }
+    }
+
+    /// Encodes the canvas in this format
+    pub fn encode(&self, canvas: &Canvas) -> Result<Vec<u8>> {
+        Ok(match self {
+            Self::PpmText => canvas.to_ppm()?.into_bytes(),
+            Self::PpmBinary => canvas.to_ppm_binary(),
+            Self::Png => canvas.to_png(),
+            Self::Bmp => canvas.to_bmp(),
+            Self::Pfm => canvas.to_pfm(),
+        })
+    }
+}
+
+/// Writes the canvas to `path`, in the format that matches the
+/// extension of the file name.
+///
+/// ```no_run
+/// use raytracer::{canvas::Canvas, export::write_canvas};
+///
+/// let canvas = Canvas::new(10, 10);
+/// write_canvas("render.png", &canvas).unwrap();
+/// ```
+pub fn write_canvas(path: impl AsRef<Path>, canvas: &Canvas) -> Result<()> {
+    let format = ImageFormat::from_path(&path)?;
+    write_canvas_as(path, canvas, format)
+}
+
+/// Writes the canvas to `path` in the given format, whatever the
+/// extension of the file name is
+pub fn write_canvas_as(path: impl AsRef<Path>, canvas: &Canvas, format: ImageFormat) -> Result<()> {
+    std::fs::write(path, format.encode(canvas)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{write_canvas, ImageFormat, UnsupportedFormat};
+    use crate::canvas::Canvas;
+    use anyhow::Result;
+    use std::path::PathBuf;
+    use uuid::Uuid;
+
+    fn temp_file(extension: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("raytracer-{}.{}", Uuid::new_v4(), extension))
+    }
+
+    #[test]
+    fn each_extension_writes_the_right_magic_bytes() -> Result<()> {
+        let canvas = Canvas::new(4, 3);
+        let cases: [(&str, &[u8]); 6] = [
+            ("ppm", b"P3\n"),
+            ("pbm", b"P6\n"),
+            ("png", b"\x89PNG\r\n\x1a\n"),
+            ("bmp", b"BM"),
+            ("pfm", b"PF\n"),
+            ("PNG", b"\x89PNG\r\n\x1a\n"),
+        ];
+
+        for (extension, magic) in cases {
+            let path = temp_file(extension);
+            write_canvas(&path, &canvas)?;
+
+            let bytes = std::fs::read(&path)?;
+            std::fs::remove_file(&path)?;
+
+            assert!(bytes.starts_with(magic), "wrong magic for .{}", extension);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn unsupported_extensions_are_rejected() {
+        let path = temp_file("gif");
+        let err = write_canvas(&path, &Canvas::new(1, 1)).unwrap_err();
+
+        assert_eq!(
+            err.downcast_ref::<UnsupportedFormat>(),
+            Some(&UnsupportedFormat {
+                extension: "gif".to_string()
+            })
+        );
+        assert!(!path.exists());
+
+        assert!(ImageFormat::from_path("render").is_err());
+    }
+}
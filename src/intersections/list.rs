@@ -0,0 +1,239 @@
+use super::Intersection;
+use std::ops::Deref;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+/// A collection of [Intersection]s that is always kept sorted by
+/// ascending `t` value.
+///
+/// The collection dereferences to a slice, so it can be indexed,
+/// iterated, and passed to [super::hit] like any other list of
+/// intersections.
+///
+/// ```
+/// use raytracer::{
+///     intersections::{Intersection, Intersections},
+///     shapes::{Object, Sphere},
+/// };
+///
+/// let s = Object::Sphere(Sphere::default());
+///
+/// let mut xs = Intersections::new();
+/// xs.push(Intersection::new(5, s));
+/// xs.push(Intersection::new(-1, s));
+/// xs.push(Intersection::new(2, s));
+///
+/// assert_eq!(xs[0].t, -1.0);
+/// assert_eq!(xs.hit().unwrap().t, 2.0);
+/// ```
+pub struct Intersections {
+    inner: Vec<Intersection>,
+}
+
+impl Intersections {
+    /// Create a new empty collection of intersections
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new empty collection with room for at least
+    /// `capacity` intersections before it needs to grow
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            inner: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Inserts an intersection, keeping the collection sorted by `t`.
+    ///
+    /// Intersections with equal `t` values are ordered by object id,
+    /// so the result doesn't depend on the order of insertion.
+    pub fn push(&mut self, intersection: Intersection) {
+        let index = self.inner.partition_point(|i| *i <= intersection);
+        self.inner.insert(index, intersection);
+    }
+
+    /// Moves all the intersections from `other` into this collection,
+    /// keeping it sorted. Since both collections are already sorted,
+    /// this is a single linear merge.
+    pub fn merge(&mut self, other: Intersections) {
+        debug_assert!(is_sorted(&self.inner) && is_sorted(&other.inner));
+
+        if other.inner.is_empty() {
+            return;
+        }
+
+        if self.inner.is_empty() {
+            self.inner = other.inner;
+            return;
+        }
+
+        let mut merged = Vec::with_capacity(self.inner.len() + other.inner.len());
+        let mut left = std::mem::take(&mut self.inner).into_iter().peekable();
+        let mut right = other.inner.into_iter().peekable();
+
+        while let (Some(l), Some(r)) = (left.peek(), right.peek()) {
+            if r < l {
+                merged.push(right.next().unwrap());
+            } else {
+                merged.push(left.next().unwrap());
+            }
+        }
+
+        merged.extend(left);
+        merged.extend(right);
+
+        self.inner = merged;
+    }
+
+    /// Returns the intersection that hits an object, which is the
+    /// one with the lowest non-negative, finite `t` value. This
+    /// gives the same result as [super::hit], but can stop early
+    /// since the intersections are sorted.
+    pub fn hit(&self) -> Option<&Intersection> {
+        self.inner.iter().find(|i| i.t > 0.0 && i.t.is_finite())
+    }
+
+    /// Removes every intersection that doesn't satisfy
+    /// `t_min < t < t_max`, keeping the rest in order
+    pub fn retain_range(&mut self, t_min: f64, t_max: f64) {
+        self.inner.retain(|i| t_min < i.t && i.t < t_max);
+    }
+}
+
+/// Checks the invariant every [Intersections] keeps
+fn is_sorted(xs: &[Intersection]) -> bool {
+    xs.windows(2).all(|pair| pair[0] <= pair[1])
+}
+
+impl Deref for Intersections {
+    type Target = [Intersection];
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl From<Vec<Intersection>> for Intersections {
+    fn from(mut value: Vec<Intersection>) -> Self {
+        value.sort();
+        Self { inner: value }
+    }
+}
+
+impl From<Intersections> for Vec<Intersection> {
+    fn from(value: Intersections) -> Self {
+        value.inner
+    }
+}
+
+impl FromIterator<Intersection> for Intersections {
+    fn from_iter<T: IntoIterator<Item = Intersection>>(iter: T) -> Self {
+        Self::from(iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+impl IntoIterator for Intersections {
+    type Item = Intersection;
+    type IntoIter = std::vec::IntoIter<Intersection>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.inner.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Intersections {
+    type Item = &'a Intersection;
+    type IntoIter = std::slice::Iter<'a, Intersection>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.inner.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Intersections;
+    use crate::{
+        intersections::{hit, Computations, Intersection, Ray},
+        matrix::translation,
+        shapes::{Object, Sphere},
+        spatial::{Point, Tuple, Vector},
+    };
+    use anyhow::Result;
+
+    fn ts(xs: &Intersections) -> Vec<f64> {
+        xs.iter().map(|i| i.t).collect()
+    }
+
+    #[test]
+    fn pushing_keeps_intersections_sorted_regardless_of_order() {
+        let s = Object::Sphere(Sphere::default());
+        let values = [5.0, -3.0, 7.0, 2.0];
+
+        let mut forwards = Intersections::new();
+        values
+            .iter()
+            .for_each(|t| forwards.push(Intersection::new(*t, s)));
+
+        let mut backwards = Intersections::new();
+        values
+            .iter()
+            .rev()
+            .for_each(|t| backwards.push(Intersection::new(*t, s)));
+
+        assert_eq!(ts(&forwards), vec![-3.0, 2.0, 5.0, 7.0]);
+        assert_eq!(forwards, backwards);
+        assert_eq!(
+            forwards,
+            Intersections::from(values.map(|t| Intersection::new(t, s)).to_vec())
+        );
+    }
+
+    #[test]
+    fn hit_selects_lowest_non_negative_intersection() {
+        let s = Object::Sphere(Sphere::default());
+        let xs: Intersections = [5.0, 7.0, -3.0, 2.0]
+            .into_iter()
+            .map(|t| Intersection::new(t, s))
+            .collect();
+
+        assert_eq!(xs.hit().map(|i| i.t), Some(2.0));
+        assert_eq!(xs.hit(), hit(&xs));
+
+        let misses: Intersections = [-2.0, -1.0]
+            .into_iter()
+            .map(|t| Intersection::new(t, s))
+            .collect();
+        assert_eq!(misses.hit(), None);
+    }
+
+    #[test]
+    fn merging_intersections_of_two_shapes() -> Result<()> {
+        let ray = Ray::new(Point::new(0, 0, -5), Vector::new(0, 0, 1));
+        let s1 = Sphere::default();
+        let mut s2 = Sphere::default();
+        s2.set_transform(translation(0, 0, 1))?;
+
+        let mut xs = s1.intersect(&ray)?;
+        xs.merge(s2.intersect(&ray)?);
+
+        assert_eq!(ts(&xs), vec![4.0, 5.0, 6.0, 7.0]);
+        assert_eq!(xs[0].object, Object::Sphere(s1));
+        assert_eq!(xs[1].object, Object::Sphere(s2));
+        assert_eq!(xs[2].object, Object::Sphere(s1));
+        assert_eq!(xs[3].object, Object::Sphere(s2));
+
+        Ok(())
+    }
+
+    #[test]
+    fn prepare_computations_works_with_the_collection() -> Result<()> {
+        let ray = Ray::new(Point::new(0, 0, -5), Vector::new(0, 0, 1));
+        let xs = Sphere::default().intersect(&ray)?;
+
+        let comps = Computations::prepare_computations(xs.hit().unwrap(), &ray)?;
+        assert_eq!(comps.get_point(), &Tuple::point(0, 0, -1));
+
+        Ok(())
+    }
+}
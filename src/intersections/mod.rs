@@ -1,17 +1,37 @@
-mod objects;
+mod list;
 mod operations;
 mod ray;
 
-pub use objects::{Intersect, Object, Sphere, SurfaceNormal};
-pub use operations::{hit, reflect, transform_ray};
+pub use list::Intersections;
+#[allow(deprecated)]
+pub use operations::transform_ray;
+pub use operations::{hit, hit_for_shadow, hit_owned, ray_box_intersect, reflect, ShadowResult};
 pub use ray::Ray;
 
-use crate::{spatial::Tuple, utils::EPSILON};
+use crate::{
+    lights::{LightingContext, PointLight},
+    shapes::{Object, SurfaceNormal},
+    spatial::Tuple,
+    utils::RENDER_EPSILON,
+};
 use anyhow::Result;
+use std::{cmp::Ordering, fmt};
 
-#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+#[derive(Debug, Copy, Clone)]
 /// Data structure to keep track of intersections
 /// for a given object
+///
+/// Intersections are totally ordered by their `t` value using
+/// [f64::total_cmp], so NaN values sort to the ends instead of
+/// making comparisons fail. Intersections with the same `t` value
+/// are ordered by the id of their object, which keeps sorting
+/// deterministic when several objects are hit at the same distance.
+///
+/// Equality agrees with that order. `0.0` and `-0.0` are the same
+/// distance, as with `==`, but unlike `==` a NaN `t` equals itself.
+/// Objects are compared the way [Object] compares them, by id, so an
+/// intersection with an older copy of a shape (one whose material or
+/// transform has since changed) still equals one with the new copy.
 pub struct Intersection {
     pub t: f64,
     pub object: Object,
@@ -28,6 +48,38 @@ impl Intersection {
     }
 }
 
+/// Formats an [Intersection] as its `t` value and the object it hit,
+/// e.g. `t = 4.00000 on Sphere(67e55044)`
+impl fmt::Display for Intersection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "t = {:.5} on {}", self.t, self.object)
+    }
+}
+
+impl Ord for Intersection {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // adding 0.0 turns -0.0 into 0.0, which total_cmp would
+        // otherwise place just before it
+        (self.t + 0.0)
+            .total_cmp(&(other.t + 0.0))
+            .then_with(|| self.object.id().cmp(&other.object.id()))
+    }
+}
+
+impl PartialOrd for Intersection {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Intersection {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Intersection {}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 /// Struct containing pre-computed values using rays and intersections
 pub struct Computations {
@@ -70,10 +122,40 @@ impl Computations {
         &self.over_point
     }
 
+    /// Gathers what [crate::lights::lighting] needs to shade this hit
+    /// with `light`, of which the fraction `light_intensity` reaches
+    /// the point
+    pub fn lighting_context<'a>(
+        &'a self,
+        light: &'a PointLight,
+        light_intensity: f64,
+    ) -> LightingContext<'a> {
+        LightingContext {
+            material: self.object.get_material(),
+            light,
+            point: self.point,
+            eyev: self.eyev,
+            normalv: self.normalv,
+            light_intensity,
+        }
+    }
+
     /// Builds a state of the world based on the given intersection and ray
     /// values. This computation is performed to make some commonly accessed
     /// state values easily accessible in other computations.
     pub fn prepare_computations(i: &Intersection, r: &Ray) -> Result<Self> {
+        Self::prepare_computations_with_bias(i, r, RENDER_EPSILON)
+    }
+
+    /// Same as [Computations::prepare_computations], but with control over
+    /// how far the over point is pushed off the surface along the normal.
+    ///
+    /// The push is `bias` for points within one unit of the origin along
+    /// every axis, and grows with the largest coordinate of the point
+    /// beyond that, so that huge scenes stay free of shadow acne without
+    /// any tuning. Very small scenes may still need a smaller `bias` so
+    /// that shadows don't visibly detach from the surfaces casting them.
+    pub fn prepare_computations_with_bias(i: &Intersection, r: &Ray, bias: f64) -> Result<Self> {
         // Copy intersection's properties for convenience
         let t = i.t;
         let object = i.object;
@@ -89,7 +171,14 @@ impl Computations {
             normalv = -normalv;
         }
 
-        let over_point = point + (&normalv * EPSILON);
+        // rounding errors in the hit point grow with the size of its
+        // coordinates, so far away points need a bigger push
+        let magnitude = point
+            .get_x()
+            .abs()
+            .max(point.get_y().abs())
+            .max(point.get_z().abs());
+        let over_point = point + (normalv * (bias * magnitude.max(1.0)));
 
         Ok(Self {
             t,
@@ -105,14 +194,119 @@ impl Computations {
 
 #[cfg(test)]
 mod tests {
-    use crate::spatial::Tuple;
-
-    use super::{Computations, Intersection, Object, Ray, Sphere};
+    use super::{Computations, Intersection, Ray};
+    use crate::{
+        matrix::{scaling, translation},
+        shapes::{Object, Sphere},
+        spatial::{Point, Tuple, Vector},
+        utils::RENDER_EPSILON,
+    };
     use anyhow::Result;
+    use std::cmp::Ordering;
+
+    #[test]
+    fn creating_intersection_works() {
+        let s = Sphere::default();
+        let t = 3.5;
+        let i = Intersection::new(t, Object::Sphere(s));
+        assert_eq!(i.t, t);
+        assert_eq!(i.object, Object::Sphere(s));
+    }
+
+    #[test]
+    fn the_hit_should_offset_the_point() -> Result<()> {
+        let r = Ray::new(Point::new(0, 0, -5), Vector::new(0, 0, 1));
+        let mut shape = Sphere::default();
+        shape.set_transform(translation(0, 0, 1))?;
+
+        let i = Intersection::new(5, Object::Sphere(shape));
+        let comps = Computations::prepare_computations(&i, &r)?;
+
+        assert!(comps.get_over_point().get_z() < -RENDER_EPSILON / 2.0);
+        assert!(comps.get_point().get_z() > comps.get_over_point().get_z());
+
+        Ok(())
+    }
+    #[test]
+    fn sorting_intersections_with_equal_t_is_deterministic() {
+        let a = Object::Sphere(Sphere::default());
+        let b = Object::Sphere(Sphere::default());
+        let (first, second) = if a.id() < b.id() { (a, b) } else { (b, a) };
+
+        let mut xs = vec![
+            Intersection::new(2, second),
+            Intersection::new(1, first),
+            Intersection::new(2, first),
+        ];
+        let mut reversed: Vec<_> = xs.iter().rev().copied().collect();
+
+        xs.sort();
+        reversed.sort();
+
+        assert_eq!(xs, reversed);
+        assert_eq!(xs[0], Intersection::new(1, first));
+        assert_eq!(xs[1], Intersection::new(2, first));
+        assert_eq!(xs[2], Intersection::new(2, second));
+    }
+
+    #[test]
+    fn display_shows_t_and_the_object() {
+        let s = Object::Sphere(Sphere::default());
+        let i = Intersection::new(4, s);
+
+        let id = s.id().simple().to_string();
+        assert_eq!(
+            i.to_string(),
+            format!("t = 4.00000 on Sphere({})", &id[..8])
+        );
+    }
+
+    #[test]
+    fn nan_intersections_sort_to_the_end() {
+        let s = Object::Sphere(Sphere::default());
+        let mut xs = [
+            Intersection::new(f64::NAN, s),
+            Intersection::new(3, s),
+            Intersection::new(-1, s),
+        ];
+
+        xs.sort();
+
+        assert_eq!(xs[0].t, -1.0);
+        assert_eq!(xs[1].t, 3.0);
+        assert!(xs[2].t.is_nan());
+        assert_eq!(xs[2], xs[2]);
+    }
+
+    #[test]
+    fn equality_of_intersections_follows_the_distance_and_object() -> Result<()> {
+        let mut sphere = Sphere::default();
+        let s = Object::Sphere(sphere);
+
+        assert_eq!(Intersection::new(0.0, s), Intersection::new(-0.0, s));
+        assert_eq!(
+            Intersection::new(0.0, s).cmp(&Intersection::new(-0.0, s)),
+            Ordering::Equal
+        );
+        assert_ne!(Intersection::new(1, s), Intersection::new(1.5, s));
+        assert_ne!(
+            Intersection::new(1, s),
+            Intersection::new(1, Object::Sphere(Sphere::default()))
+        );
+
+        // a copy taken before the sphere changed is still the same object
+        sphere.set_transform(scaling(2, 2, 2))?;
+        assert_eq!(
+            Intersection::new(1, s),
+            Intersection::new(1, Object::Sphere(sphere))
+        );
+
+        Ok(())
+    }
 
     #[test]
     fn precomputing_state_of_intersection_when_it_is_outside() -> Result<()> {
-        let ray = Ray::new(Tuple::point(0, 0, -5), Tuple::vector(0, 0, 1))?;
+        let ray = Ray::new(Point::new(0, 0, -5), Vector::new(0, 0, 1));
         let sphere = Sphere::default();
         let intersection = Intersection::new(4, Object::Sphere(sphere));
 
@@ -130,7 +324,7 @@ mod tests {
 
     #[test]
     fn precomputing_state_of_intersection_when_it_is_inside() -> Result<()> {
-        let ray = Ray::new(Tuple::point(0, 0, 0), Tuple::vector(0, 0, 1))?;
+        let ray = Ray::new(Point::new(0, 0, 0), Vector::new(0, 0, 1));
         let sphere = Sphere::default();
         let intersection = Intersection::new(1, Object::Sphere(sphere));
 
@@ -144,4 +338,35 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn over_point_is_offset_by_the_bias() -> Result<()> {
+        let ray = Ray::new(Point::new(0, 0, -5), Vector::new(0, 0, 1));
+        let intersection = Intersection::new(4, Object::Sphere(Sphere::default()));
+
+        let comps = Computations::prepare_computations(&intersection, &ray)?;
+        let default_bias =
+            Computations::prepare_computations_with_bias(&intersection, &ray, RENDER_EPSILON)?;
+        assert_eq!(comps, default_bias);
+
+        let comps = Computations::prepare_computations_with_bias(&intersection, &ray, 0.5)?;
+        assert_eq!(comps.over_point, Tuple::point(0, 0, -1.5));
+        assert_eq!(comps.point, Tuple::point(0, 0, -1));
+
+        Ok(())
+    }
+
+    #[test]
+    fn over_point_offset_grows_with_distance_from_the_origin() -> Result<()> {
+        let mut shape = Sphere::default();
+        shape.set_transform(translation(0, 0, 1000))?;
+
+        let ray = Ray::new(Point::new(0, 0, 0), Vector::new(0, 0, 1));
+        let intersection = Intersection::new(999, Object::Sphere(shape));
+
+        let comps = Computations::prepare_computations_with_bias(&intersection, &ray, 0.001)?;
+        assert_eq!(comps.over_point, Tuple::point(0, 0, 998.001));
+
+        Ok(())
+    }
 }
@@ -1,5 +1,5 @@
 use super::{Intersection, Ray};
-use crate::{matrix::Matrix, spatial::Tuple};
+use crate::{matrix::Matrix, shapes::Object, spatial::Tuple};
 use anyhow::Result;
 use core::f64;
 
@@ -7,16 +7,19 @@ use core::f64;
 /// that has the lowest non negative t-value. This corresponds to
 /// the intersection that hits an object.
 ///
+/// Intersections with a non-finite t-value (NaN or infinite) are
+/// never considered a hit.
+///
 /// In the event that the ray misses the object entirely,
 /// this would return a `None` value.
-pub fn hit(xs: Vec<Intersection>) -> Option<Intersection> {
-    let mut result: Option<Intersection> = None;
+pub fn hit(xs: &[Intersection]) -> Option<&Intersection> {
+    let mut result: Option<&Intersection> = None;
     let mut current_min = f64::MAX;
 
     for i in xs {
         // find the lowest non-negative t value to find
         // the ray that hit the object
-        if i.t < current_min && i.t > 0.0 {
+        if i.t.is_finite() && i.t < current_min && i.t > 0.0 {
             current_min = i.t;
             result = Some(i);
         }
@@ -25,28 +28,121 @@ pub fn hit(xs: Vec<Intersection>) -> Option<Intersection> {
     result
 }
 
+/// Convenience wrapper around [hit] for when you own the intersections,
+/// returning a copy of the hit instead of a reference into the list.
+pub fn hit_owned(xs: Vec<Intersection>) -> Option<Intersection> {
+    hit(&xs).copied()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// The outcome of casting a shadow ray from a point towards a light
+pub enum ShadowResult<'a> {
+    /// Nothing blocks the light
+    Unshadowed,
+    /// The light is completely blocked by this object
+    Fully(&'a Object),
+}
+
+/// Given the intersections of a shadow ray, this function finds out
+/// whether anything blocks the light that is `max_distance` away
+/// along the ray. Only intersections with `0 < t < max_distance`
+/// can block the light, and the nearest one is reported.
+///
+/// Every solid shape is opaque and blocks the light completely. The
+/// boundaries of volumes are skipped, since a medium only dims the
+/// light passing through it (see [crate::world::World::is_shadowed]).
+pub fn hit_for_shadow(xs: &[Intersection], max_distance: f64) -> ShadowResult<'_> {
+    xs.iter()
+        .filter(|i| i.t.is_finite() && 0.0 < i.t && i.t < max_distance)
+        .filter(|i| i.object.get_material().get_volume().is_none())
+        .min_by(|a, b| a.t.total_cmp(&b.t))
+        .map_or(ShadowResult::Unshadowed, |h| ShadowResult::Fully(&h.object))
+}
+
 /// Transforms a ray by performing a matrix multiplication
 /// of the ray and the given input matrix. This is useful
 /// to transform rays instead of transforming objects themselves.
+#[deprecated(note = "use `Ray::transform`, which cannot fail")]
 pub fn transform_ray(ray: &Ray, matrix: &Matrix<4, 4>) -> Result<Ray> {
-    Ray::new(matrix * &ray.origin, matrix * &ray.direction)
+    Ok(Ray::new(
+        (matrix * ray.origin).try_into()?,
+        (matrix * ray.direction).try_into()?,
+    ))
+}
+
+/// Finds where the line along `ray` enters and leaves the axis-aligned
+/// box spanning `min` to `max`, as the pair (tmin, tmax), or `None`
+/// if it misses the box.
+///
+/// This is the slab test: the ray is clipped against the pair of
+/// planes on each axis in turn. Both values are returned even when
+/// they are negative, so tmin < 0 < tmax means the ray starts inside
+/// the box, and a box entirely behind the ray has tmax < 0. Rays that
+/// only graze an edge or a face, and flat boxes where `min` and `max`
+/// share a coordinate, count as hits.
+///
+/// ```
+/// use raytracer::{
+///     intersections::{ray_box_intersect, Ray},
+///     spatial::{Point, Tuple, Vector},
+/// };
+///
+/// let ray = Ray::new(Point::new(5, 0.5, 0), Vector::new(-1, 0, 0));
+/// let (min, max) = (Tuple::point(-1, -1, -1), Tuple::point(1, 1, 1));
+///
+/// assert_eq!(ray_box_intersect(&ray, &min, &max), Some((4.0, 6.0)));
+/// ```
+pub fn ray_box_intersect(ray: &Ray, min: &Tuple, max: &Tuple) -> Option<(f64, f64)> {
+    let mut tmin = f64::NEG_INFINITY;
+    let mut tmax = f64::INFINITY;
+
+    for axis in 0..3 {
+        let (origin, direction) = (ray.origin[axis], ray.direction[axis]);
+
+        if direction == 0.0 {
+            // parallel to the slab, so it is either always or never
+            // between its planes
+            if origin < min[axis] || origin > max[axis] {
+                return None;
+            }
+            continue;
+        }
+
+        let t1 = (min[axis] - origin) / direction;
+        let t2 = (max[axis] - origin) / direction;
+        tmin = tmin.max(t1.min(t2));
+        tmax = tmax.min(t1.max(t2));
+
+        if tmin > tmax {
+            return None;
+        }
+    }
+
+    Some((tmin, tmax))
 }
 
 /// Calculates the reflection of an inbound vector for a
 /// surface given the normal vector for that point.
+///
+/// This is the same as [Tuple::reflect].
 pub fn reflect(inbound: &Tuple, normal: &Tuple) -> Tuple {
-    inbound - &(normal * (2.0 * normal.dot(inbound)))
+    inbound.reflect(normal)
 }
 
 #[cfg(test)]
 mod tests {
     use std::f64::consts::SQRT_2;
 
-    use super::{hit, reflect, transform_ray, Intersection};
+    use super::{
+        hit, hit_for_shadow, hit_owned, ray_box_intersect, reflect, Intersection, ShadowResult,
+    };
     use crate::{
-        intersections::{Object, Ray, Sphere},
+        color::Color,
+        intersections::Ray,
+        lights::Material,
         matrix::{scaling, translation},
-        spatial::Tuple,
+        shapes::{Object, Sphere},
+        spatial::{Point, Tuple, Vector},
     };
     use anyhow::Result;
 
@@ -56,7 +152,7 @@ mod tests {
         let i1 = Intersection::new(1, Object::Sphere(s));
         let i2 = Intersection::new(2, Object::Sphere(s));
 
-        assert_eq!(hit(vec![i2, i1]), Some(i1));
+        assert_eq!(hit(&[i2, i1]), Some(&i1));
         Ok(())
     }
 
@@ -66,7 +162,7 @@ mod tests {
         let i1 = Intersection::new(-1, Object::Sphere(s));
         let i2 = Intersection::new(1, Object::Sphere(s));
 
-        assert_eq!(hit(vec![i2, i1]), Some(i2));
+        assert_eq!(hit(&[i2, i1]), Some(&i2));
 
         Ok(())
     }
@@ -77,7 +173,7 @@ mod tests {
         let i1 = Intersection::new(-2, Object::Sphere(s));
         let i2 = Intersection::new(-1, Object::Sphere(s));
 
-        assert_eq!(hit(vec![i2, i1]), None);
+        assert_eq!(hit(&[i2, i1]), None);
         Ok(())
     }
 
@@ -89,28 +185,83 @@ mod tests {
         let i3 = Intersection::new(-3, Object::Sphere(s));
         let i4 = Intersection::new(2, Object::Sphere(s));
 
-        assert_eq!(hit(vec![i1, i2, i3, i4]), Some(i4));
+        assert_eq!(hit(&[i1, i2, i3, i4]), Some(&i4));
 
         Ok(())
     }
 
     #[test]
-    fn translating_a_ray() -> Result<()> {
-        let r = Ray::new(Tuple::point(1, 2, 3), Tuple::vector(0, 1, 0))?;
-        let m = translation(3, 4, 5);
-        let r2 = transform_ray(&r, &m)?;
-        assert_eq!(r2.origin, Tuple::point(4, 6, 8));
-        assert_eq!(r2.direction, Tuple::vector(0, 1, 0));
+    fn hit_of_no_intersections_is_none() {
+        assert_eq!(hit(&[]), None);
+        assert_eq!(hit_owned(vec![]), None);
+    }
+
+    #[test]
+    fn hit_skips_non_finite_intersections() {
+        let s = Sphere::default();
+        let i1 = Intersection::new(f64::NAN, Object::Sphere(s));
+        let i2 = Intersection::new(3, Object::Sphere(s));
+        let i3 = Intersection::new(f64::INFINITY, Object::Sphere(s));
+
+        assert_eq!(hit(&[i1, i2, i3]), Some(&i2));
+        assert_eq!(hit(&[i1, i3]), None);
+    }
+
+    #[test]
+    fn hit_owned_returns_a_copy_of_the_hit() {
+        let s = Sphere::default();
+        let i1 = Intersection::new(5, Object::Sphere(s));
+        let i2 = Intersection::new(-3, Object::Sphere(s));
+        let i3 = Intersection::new(2, Object::Sphere(s));
+
+        assert_eq!(hit_owned(vec![i1, i2, i3]), Some(i3));
+    }
+
+    #[test]
+    fn shadow_hits_only_count_between_the_point_and_the_light() -> Result<()> {
+        let near = Object::Sphere(Sphere::default());
+        let far = Object::Sphere(Sphere::default());
+
+        assert_eq!(hit_for_shadow(&[], 10.0), ShadowResult::Unshadowed);
+
+        // Objects behind the point or past the light don't block it
+        let xs = [Intersection::new(-2, near), Intersection::new(12, far)];
+        assert_eq!(hit_for_shadow(&xs, 10.0), ShadowResult::Unshadowed);
+
+        // The nearest object in between is the one blocking the light
+        let xs = [
+            Intersection::new(7, far),
+            Intersection::new(-1, near),
+            Intersection::new(3, near),
+        ];
+        assert_eq!(hit_for_shadow(&xs, 10.0), ShadowResult::Fully(&near));
+
+        // media let the light through
+        let smoke = Object::Sphere(Sphere::new(
+            translation(0, 0, 5),
+            Material::volume(1.0, Color::new(1, 1, 1)),
+        )?);
+        let xs = [Intersection::new(4, smoke), Intersection::new(6, smoke)];
+        assert_eq!(hit_for_shadow(&xs, 10.0), ShadowResult::Unshadowed);
+
+        let xs = [xs[0], Intersection::new(7, far), xs[1]];
+        assert_eq!(hit_for_shadow(&xs, 10.0), ShadowResult::Fully(&far));
+
         Ok(())
     }
 
     #[test]
-    fn scaling_a_ray() -> Result<()> {
-        let r = Ray::new(Tuple::point(1, 2, 3), Tuple::vector(0, 1, 0))?;
-        let m = scaling(2, 3, 4);
-        let r2 = transform_ray(&r, &m)?;
-        assert_eq!(r2.origin, Tuple::point(2, 6, 12));
-        assert_eq!(r2.direction, Tuple::vector(0, 3, 0));
+    #[allow(deprecated)]
+    fn transform_ray_matches_ray_transform() -> Result<()> {
+        let r = Ray::new(Point::new(1, 2, 3), Vector::new(0, 1, 0));
+
+        for m in [translation(3, 4, 5), scaling(2, 3, 4)] {
+            let r2 = super::transform_ray(&r, &m)?;
+            let expected = r.transform(&m);
+            assert_eq!(r2.origin, expected.origin);
+            assert_eq!(r2.direction, expected.direction);
+        }
+
         Ok(())
     }
 
@@ -126,4 +277,86 @@ mod tests {
         let n = Tuple::vector(SQRT_2 / 2.0, SQRT_2 / 2.0, 0);
         assert_eq!(reflect(&v, &n), Tuple::vector(1, 0, 0));
     }
+
+    fn unit_box() -> (Tuple, Tuple) {
+        (Tuple::point(-1, -1, -1), Tuple::point(1, 1, 1))
+    }
+
+    #[test]
+    fn rays_hit_a_box_from_every_side() -> Result<()> {
+        let (min, max) = unit_box();
+
+        for (origin, direction, expected) in [
+            (Point::new(5, 0.5, 0), Vector::new(-1, 0, 0), (4.0, 6.0)),
+            (Point::new(-5, 0.5, 0), Vector::new(1, 0, 0), (4.0, 6.0)),
+            (Point::new(0.5, 5, 0), Vector::new(0, -1, 0), (4.0, 6.0)),
+            (Point::new(0.5, -5, 0), Vector::new(0, 1, 0), (4.0, 6.0)),
+            (Point::new(0.5, 0, 5), Vector::new(0, 0, -1), (4.0, 6.0)),
+            (Point::new(0.5, 0, -5), Vector::new(0, 0, 1), (4.0, 6.0)),
+            (Point::new(0, 0.5, 0), Vector::new(0, 0, 1), (-1.0, 1.0)),
+        ] {
+            let ray = Ray::new(origin, direction);
+            assert_eq!(ray_box_intersect(&ray, &min, &max), Some(expected));
+        }
+
+        // diagonal rays through opposite corners
+        let ray = Ray::new(Point::new(-2, -2, -2), Vector::new(1, 1, 1));
+        assert_eq!(ray_box_intersect(&ray, &min, &max), Some((1.0, 3.0)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn rays_that_miss_a_box() -> Result<()> {
+        let (min, max) = unit_box();
+
+        for (origin, direction) in [
+            (Point::new(-2, 0, 0), Vector::new(0.2673, 0.5345, 0.8018)),
+            (Point::new(0, -2, 0), Vector::new(0.8018, 0.2673, 0.5345)),
+            (Point::new(0, 0, -2), Vector::new(0.5345, 0.8018, 0.2673)),
+            (Point::new(2, 0, 2), Vector::new(0, 0, -1)),
+            (Point::new(0, 2, 2), Vector::new(0, -1, 0)),
+            (Point::new(2, 2, 0), Vector::new(-1, 0, 0)),
+        ] {
+            let ray = Ray::new(origin, direction);
+            assert_eq!(ray_box_intersect(&ray, &min, &max), None);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn parallel_rays_graze_the_faces_of_a_box() -> Result<()> {
+        let (min, max) = unit_box();
+
+        // sliding along the top face, and just above it
+        let along = Ray::new(Point::new(-5, 1, 0), Vector::new(1, 0, 0));
+        assert_eq!(ray_box_intersect(&along, &min, &max), Some((4.0, 6.0)));
+
+        let above = Ray::new(Point::new(-5, 1.0001, 0), Vector::new(1, 0, 0));
+        assert_eq!(ray_box_intersect(&above, &min, &max), None);
+
+        // touching a single edge
+        let edge = Ray::new(Point::new(0, 2, -1), Vector::new(1, -1, 0).normalize());
+        let (tmin, tmax) = ray_box_intersect(&edge, &min, &max).unwrap();
+        assert!((tmin - SQRT_2).abs() < 1e-9 && (tmax - SQRT_2).abs() < 1e-9);
+
+        Ok(())
+    }
+
+    #[test]
+    fn flat_boxes_can_still_be_hit() -> Result<()> {
+        let (min, max) = (Tuple::point(-1, 0, -1), Tuple::point(1, 0, 1));
+
+        let through = Ray::new(Point::new(0, 5, 0), Vector::new(0, -1, 0));
+        assert_eq!(ray_box_intersect(&through, &min, &max), Some((5.0, 5.0)));
+
+        let within = Ray::new(Point::new(-5, 0, 0), Vector::new(1, 0, 0));
+        assert_eq!(ray_box_intersect(&within, &min, &max), Some((4.0, 6.0)));
+
+        let beside = Ray::new(Point::new(-5, 0.5, 0), Vector::new(1, 0, 0));
+        assert_eq!(ray_box_intersect(&beside, &min, &max), None);
+
+        Ok(())
+    }
 }
@@ -1,61 +1,221 @@
-use crate::spatial::Tuple;
-use anyhow::{Error, Result};
+use crate::{
+    matrix::Matrix,
+    spatial::{Point, Tuple, Vector},
+};
+use anyhow::Result;
+use std::fmt;
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "RayData", into = "RayData")
+)]
 /// A data structure representing the origin and direction of a ray
+///
+/// A ray also carries the range of `t` values that queries made with
+/// it care about. Rays are unbounded unless narrowed with [Ray::clamped].
 pub struct Ray {
     /// The origin point of this [Ray]
     pub origin: Tuple,
     /// The direction of this [Ray]
     pub direction: Tuple,
+    t_min: f64,
+    t_max: f64,
 }
 
 impl Ray {
-    /// We need to ensure that the users pass in valid origin
-    /// and direction values to create new [Rays]. We want to maintain
-    /// the invariant that the origin is always a point, and that a
-    /// direction is always a vector.
-    fn validate(origin: &Tuple, direction: &Tuple) -> bool {
-        origin.is_a_point() && direction.is_a_vector()
+    /// Creates a new [Ray] from its origin [Point] and direction
+    /// [Vector]. The types already guarantee a valid ray, so unlike the
+    /// [Tuple] based [Ray::from_tuples], this can't fail.
+    ///
+    /// ```
+    /// use raytracer::{
+    ///     intersections::Ray,
+    ///     spatial::{Point, Tuple, Vector},
+    /// };
+    ///
+    /// let r = Ray::new(Point::new(1, 2, 3), Vector::new(0, 0, 1));
+    /// assert_eq!(r.origin, Tuple::point(1, 2, 3));
+    /// ```
+    pub fn new(origin: Point, direction: Vector) -> Self {
+        Self {
+            origin: origin.into(),
+            direction: direction.into(),
+            t_min: f64::NEG_INFINITY,
+            t_max: f64::INFINITY,
+        }
     }
 
     /// Given a starting origin point, and a direction vector,
-    /// we can create a new [Ray] using this constructor
-    pub fn new(origin: Tuple, direction: Tuple) -> Result<Self> {
-        if !Self::validate(&origin, &direction) {
-            return Err(Error::msg(
-                "The origin tuple must be a point, and the direction tuple must be a vector",
-            ));
+    /// we can create a new [Ray] using this constructor. This fails
+    /// unless the origin is a point and the direction is a vector.
+    #[deprecated(note = "use `Ray::new`, which takes a typed `Point` and `Vector`")]
+    pub fn from_tuples(origin: Tuple, direction: Tuple) -> Result<Self> {
+        Ok(Self::new(origin.try_into()?, direction.try_into()?))
+    }
+
+    /// Returns a copy of this [Ray] that only considers intersections
+    /// with `t_min < t < t_max`. Intersection queries in the world leave
+    /// out everything outside this range, so a hit is only ever found
+    /// inside it.
+    ///
+    /// ```
+    /// use raytracer::{
+    ///     intersections::Ray,
+    ///     spatial::{Point, Vector},
+    /// };
+    ///
+    /// let r = Ray::new(Point::new(0, 0, 0), Vector::new(0, 0, 1));
+    /// let r = r.clamped(0.0, 10.0);
+    ///
+    /// assert!(r.contains(5.0));
+    /// assert!(!r.contains(10.0));
+    /// ```
+    pub fn clamped(&self, t_min: impl Into<f64>, t_max: impl Into<f64>) -> Self {
+        Self {
+            t_min: t_min.into(),
+            t_max: t_max.into(),
+            ..*self
         }
-        Ok(Self { origin, direction })
+    }
+
+    /// The lower (exclusive) bound of `t` values this [Ray] considers
+    pub fn t_min(&self) -> f64 {
+        self.t_min
+    }
+
+    /// The upper (exclusive) bound of `t` values this [Ray] considers
+    pub fn t_max(&self) -> f64 {
+        self.t_max
+    }
+
+    /// Returns true if `t` lies inside the range of this [Ray]
+    pub fn contains(&self, t: f64) -> bool {
+        self.t_min < t && t < self.t_max
     }
 
     /// Finds the point `t` units away in the direction of this
     /// [Ray] from the origin of this [Ray]
     pub fn position(&self, t: impl Into<f64>) -> Tuple {
-        self.origin + (&self.direction * t.into())
+        self.origin + (self.direction * t.into())
+    }
+
+    /// Transforms this [Ray] by multiplying its origin and direction
+    /// by the given matrix. This is useful to transform rays instead
+    /// of transforming objects themselves.
+    ///
+    /// Multiplying by a transformation matrix keeps points as points
+    /// and vectors as vectors, so the result doesn't need to go back
+    /// through the validation in [Ray::new]. The `t` range is kept as
+    /// is, since `t` values don't change when both the origin and the
+    /// direction are transformed.
+    ///
+    /// ```
+    /// use raytracer::{
+    ///     intersections::Ray,
+    ///     matrix::translation,
+    ///     spatial::{Point, Tuple, Vector},
+    /// };
+    ///
+    /// let r = Ray::new(Point::new(1, 2, 3), Vector::new(0, 1, 0));
+    /// let r2 = r.transform(&translation(3, 4, 5));
+    ///
+    /// assert_eq!(r2.origin, Tuple::point(4, 6, 8));
+    /// assert_eq!(r2.direction, Tuple::vector(0, 1, 0));
+    /// ```
+    pub fn transform(&self, m: &Matrix<4, 4>) -> Ray {
+        Self {
+            origin: m * self.origin,
+            direction: m * self.direction,
+            ..*self
+        }
+    }
+}
+
+/// Formats a [Ray] as `origin -> direction`
+impl fmt::Display for Ray {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} -> {}", self.origin, self.direction)
+    }
+}
+
+/// The serialized form of a [Ray]. Only the origin and direction are
+/// kept, so a deserialized [Ray] is always unbounded.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct RayData {
+    origin: Tuple,
+    direction: Tuple,
+}
+
+#[cfg(feature = "serde")]
+impl From<Ray> for RayData {
+    fn from(value: Ray) -> Self {
+        Self {
+            origin: value.origin,
+            direction: value.direction,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RayData> for Ray {
+    type Error = anyhow::Error;
+
+    fn try_from(value: RayData) -> Result<Self> {
+        Ok(Ray::new(
+            value.origin.try_into()?,
+            value.direction.try_into()?,
+        ))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::Ray;
-    use crate::spatial::Tuple;
+    use crate::{
+        matrix::{rotation_y, scaling, translation},
+        spatial::{Point, Tuple, Vector},
+    };
     use anyhow::Result;
 
     #[test]
     fn creation_and_querying() -> Result<()> {
-        let origin = Tuple::point(1, 2, 3);
-        let direction = Tuple::vector(4, 5, 6);
-        let ray = Ray::new(origin, direction)?;
-        assert_eq!(ray.origin, origin);
-        assert_eq!(ray.direction, direction);
+        let origin = Point::new(1, 2, 3);
+        let direction = Vector::new(4, 5, 6);
+        let ray = Ray::new(origin, direction);
+        assert_eq!(ray.origin, origin.into());
+        assert_eq!(ray.direction, direction.into());
+        Ok(())
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn the_tuple_shim_checks_the_kinds_of_its_tuples() -> Result<()> {
+        let typed = Ray::new(Point::new(1, 2, 3), Vector::new(4, 5, 6));
+        let untyped = Ray::from_tuples(Tuple::point(1, 2, 3), Tuple::vector(4, 5, 6))?;
+        assert_eq!(typed, untyped);
+
+        assert!(Ray::from_tuples(Tuple::vector(1, 2, 3), Tuple::vector(4, 5, 6)).is_err());
+        assert!(Ray::from_tuples(Tuple::point(1, 2, 3), Tuple::point(4, 5, 6)).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn display_shows_origin_and_direction() -> Result<()> {
+        let r = Ray::new(Point::new(1, 2, 3), Vector::new(0, -0.0, 1));
+        assert_eq!(
+            r.to_string(),
+            "P(1.00000, 2.00000, 3.00000) -> V(0.00000, 0.00000, 1.00000)"
+        );
         Ok(())
     }
 
     #[test]
     fn calculate_position() -> Result<()> {
-        let ray = Ray::new(Tuple::point(2, 3, 4), Tuple::vector(1, 0, 0))?;
+        let ray = Ray::new(Point::new(2, 3, 4), Vector::new(1, 0, 0));
 
         assert_eq!(ray.position(0), ray.origin);
         assert_eq!(ray.position(1), Tuple::point(3, 3, 4));
@@ -63,4 +223,72 @@ mod tests {
         assert_eq!(ray.position(2.5), Tuple::point(4.5, 3, 4));
         Ok(())
     }
+
+    #[test]
+    fn rays_are_unbounded_until_clamped() -> Result<()> {
+        let r = Ray::new(Point::new(0, 0, 0), Vector::new(0, 0, 1));
+        assert!(r.contains(-1e9));
+        assert!(r.contains(1e9));
+
+        let clamped = r.clamped(1, 4.5);
+        assert_eq!((clamped.t_min(), clamped.t_max()), (1.0, 4.5));
+        assert!(clamped.contains(2.0));
+        assert!(!clamped.contains(1.0));
+        assert!(!clamped.contains(5.0));
+
+        let moved = clamped.transform(&translation(1, 1, 1));
+        assert_eq!((moved.t_min(), moved.t_max()), (1.0, 4.5));
+
+        Ok(())
+    }
+
+    #[test]
+    fn translating_a_ray() -> Result<()> {
+        let r = Ray::new(Point::new(1, 2, 3), Vector::new(0, 1, 0));
+        let r2 = r.transform(&translation(3, 4, 5));
+        assert_eq!(r2.origin, Tuple::point(4, 6, 8));
+        assert_eq!(r2.direction, Tuple::vector(0, 1, 0));
+        Ok(())
+    }
+
+    #[test]
+    fn scaling_a_ray() -> Result<()> {
+        let r = Ray::new(Point::new(1, 2, 3), Vector::new(0, 1, 0));
+        let r2 = r.transform(&scaling(2, 3, 4));
+        assert_eq!(r2.origin, Tuple::point(2, 6, 12));
+        assert_eq!(r2.direction, Tuple::vector(0, 3, 0));
+        Ok(())
+    }
+
+    #[test]
+    fn transformed_rays_keep_their_identifiers() -> Result<()> {
+        let r = Ray::new(Point::new(1, -2, 3), Vector::new(0.5, 1, -1));
+        let m = (translation(5, -3, 2) * scaling(2, 0.5, 4))?;
+        let m = (rotation_y(1.2) * m)?;
+
+        let r2 = r.transform(&m);
+        assert!(r2.origin.is_a_point());
+        assert!(r2.direction.is_a_vector());
+
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn json_round_trip_of_a_ray() -> Result<()> {
+        let r = Ray::new(Point::new(1, 2, 3), Vector::new(0, 0, 1));
+        let back: Ray = serde_json::from_str(&serde_json::to_string(&r)?)?;
+        assert_eq!(back, r);
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn ray_with_a_vector_origin_fails_to_deserialize() {
+        let json = r#"{
+            "origin": {"x": 1.0, "y": 2.0, "z": 3.0, "w": 0.0},
+            "direction": {"x": 0.0, "y": 0.0, "z": 1.0, "w": 0.0}
+        }"#;
+        assert!(serde_json::from_str::<Ray>(json).is_err());
+    }
 }
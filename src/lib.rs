@@ -1,3 +1,7 @@
+// shape ids are random by default, which browsers can't provide
+#[cfg(all(target_arch = "wasm32", target_os = "unknown", not(feature = "wasm")))]
+compile_error!("enable the `wasm` feature to build for wasm32-unknown-unknown");
+
 /// Contains the implementation of our camera that captures scenes from
 /// the world
 pub mod camera;
@@ -7,10 +11,17 @@ pub mod camera;
 /// familiar image format (PPM)
 pub mod canvas;
 
+/// Writes canvases to image files, picking the format from the
+/// file's extension
+pub mod export;
+
 /// This module contains our representation of RGB Color values
 /// and implementations of various color operations
 pub mod color;
 
+/// The typed errors returned by the core modules of the ray tracer
+pub mod error;
+
 /// Contains the representation for matrices
 /// and methods to operate on them
 pub mod matrix;
@@ -19,6 +30,10 @@ pub mod matrix;
 /// with various types of objects
 pub mod intersections;
 
+/// Contains the shapes that can be placed in the world, along with
+/// the traits used to intersect them and find their surface normals
+pub mod shapes;
+
 /// Contains the implementation of point lights, materials, and the
 /// Phong reflection model to simulate the interaction of light with
 /// objects
@@ -31,5 +46,27 @@ pub mod world;
 /// The `spatial` module contains the representation for key
 /// three-dimensional spatial properties like Points and Vectors
 pub mod spatial;
+
+/// Contains a simple projectile simulation (from chapter 2) built on
+/// top of the [spatial::Tuple] type
+#[cfg(feature = "examples-scenes")]
 pub mod tick;
-mod utils;
+
+/// A registry of ready made scenes that can be looked up by name
+#[cfg(feature = "examples-scenes")]
+pub mod scenes;
+
+/// Helpers for reporting the progress of long running renders
+pub mod progress;
+
+/// Standard scenes and a harness for timing renders of them, to compare
+/// the speed of the renderer over time
+#[cfg(feature = "examples-scenes")]
+pub mod bench;
+
+/// Re-exports the most commonly used types, transforms and traits
+pub mod prelude;
+
+/// Small utilities shared by the rest of the crate, like the seedable
+/// random number generator used for stochastic rendering
+pub mod utils;
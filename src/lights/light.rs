@@ -1,9 +1,19 @@
-use crate::{color::Color, intersections::reflect, spatial::Tuple};
-use anyhow::{Error, Result};
+use crate::{
+    color::Color,
+    error::RayTracerError,
+    spatial::{Point, Tuple},
+};
+use anyhow::Result;
+use std::fmt;
 
 use super::Material;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "PointLightData", into = "PointLightData")
+)]
 /// Data structure representing a light source. A light source
 /// has a position in space, and a specific color
 pub struct PointLight {
@@ -12,21 +22,128 @@ pub struct PointLight {
 }
 
 impl PointLight {
+    /// Create a new light source at `position` of color `intensity`
+    pub fn new(position: Point, intensity: Color) -> Self {
+        Self {
+            intensity,
+            position: position.into(),
+        }
+    }
+
     /// Create a new light source at `position` of color `intensity`
     ///
     /// Note: This returns a result because it validates the type
     /// of the input tuple to ensure we are passing in a point and
     /// not a vector.
-    pub fn new(position: Tuple, intensity: Color) -> Result<Self> {
+    #[deprecated(note = "use `PointLight::new`, which takes a typed `Point`")]
+    pub fn from_tuple(position: Tuple, intensity: Color) -> Result<Self> {
+        Ok(Self::new(position.try_into()?, intensity))
+    }
+
+    /// Create a new light source at `position` with the color of a
+    /// blackbody at `kelvin` (see [Color::from_kelvin]), scaled by
+    /// `brightness`. This fails if `position` is a vector.
+    ///
+    /// ```
+    /// use raytracer::{lights::PointLight, spatial::Tuple};
+    ///
+    /// let bulb = PointLight::with_temperature(Tuple::point(0, 5, 0), 2700.0, 0.8).unwrap();
+    /// assert!(bulb.get_intensity().red > bulb.get_intensity().blue);
+    /// ```
+    pub fn with_temperature(position: Tuple, kelvin: f64, brightness: f64) -> Result<Self> {
+        Ok(Self::new(
+            position.try_into()?,
+            Color::from_kelvin(kelvin) * brightness,
+        ))
+    }
+
+    /// Get the position of the light
+    pub fn get_position(&self) -> Tuple {
+        self.position
+    }
+
+    /// Move the light to `position`. This fails if `position` is a
+    /// vector, leaving the light where it was.
+    pub fn set_position(&mut self, position: Tuple) -> Result<()> {
         if position.is_a_vector() {
-            Err(Error::msg("position must be a Point not a Vector"))
-        } else {
-            Ok(Self {
-                intensity,
-                position,
-            })
+            return Err(RayTracerError::invalid_tuple("point", &position).into());
         }
+
+        self.position = position;
+        Ok(())
     }
+
+    /// Get the color and brightness of the light
+    pub fn get_intensity(&self) -> Color {
+        self.intensity
+    }
+
+    /// Set the color and brightness of the light
+    pub fn set_intensity(&mut self, intensity: Color) {
+        self.intensity = intensity;
+    }
+}
+
+/// Formats a light as its position and intensity, e.g.
+/// `PointLight at P(0.00000, 10.00000, -10.00000) intensity (1, 1, 1)`
+impl fmt::Display for PointLight {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let i = self.intensity;
+        write!(
+            f,
+            "PointLight at {} intensity ({}, {}, {})",
+            self.position, i.red, i.green, i.blue
+        )
+    }
+}
+
+/// The serialized form of a [PointLight]
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct PointLightData {
+    position: Tuple,
+    intensity: Color,
+}
+
+#[cfg(feature = "serde")]
+impl From<PointLight> for PointLightData {
+    fn from(value: PointLight) -> Self {
+        Self {
+            position: value.position,
+            intensity: value.intensity,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<PointLightData> for PointLight {
+    type Error = anyhow::Error;
+
+    fn try_from(value: PointLightData) -> Result<Self> {
+        Ok(PointLight::new(value.position.try_into()?, value.intensity))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// Everything [lighting] needs to know to shade a point on a surface.
+///
+/// Naming the fields keeps the point and the two vectors from being
+/// swapped by accident, and lets new inputs be added without changing
+/// every call site.
+pub struct LightingContext<'a> {
+    /// The material of the surface being shaded
+    pub material: &'a Material,
+    /// The light illuminating the surface
+    pub light: &'a PointLight,
+    /// The point being shaded
+    pub point: Tuple,
+    /// The vector from the point towards the eye
+    pub eyev: Tuple,
+    /// The surface normal at the point
+    pub normalv: Tuple,
+    /// How much of the light reaches the point, from 0 (fully
+    /// shadowed) to 1 (fully lit)
+    pub light_intensity: f64,
 }
 
 /// Calculates the color value for a light source hitting a material
@@ -34,14 +151,9 @@ impl PointLight {
 ///
 /// The algorithm supporting this function is based on the
 /// Phong reflection model
-pub fn lighting(
-    material: &Material,
-    point_light: &PointLight,
-    position: &Tuple,
-    eyev: &Tuple,
-    normalv: &Tuple,
-    in_shadow: bool,
-) -> Color {
+pub fn lighting(ctx: &LightingContext) -> Color {
+    let (material, point_light) = (ctx.material, ctx.light);
+
     // combine surface color with the light's intensity/color
     let effective_color = material.get_color() * point_light.intensity;
 
@@ -49,17 +161,17 @@ pub fn lighting(
     let ambient = effective_color * material.get_ambient();
 
     // if we're in a shadow, we can ignore the diffuse and specular components
-    if in_shadow {
+    if ctx.light_intensity <= 0.0 {
         return ambient;
     }
 
     // find the direction to the light source
-    let lightv = (&point_light.position - position).normalize();
+    let lightv = (point_light.position - ctx.point).normalize();
 
     // light_dot_normal represents the cosine of the angle between the​
     // light vector and the normal vector. A negative number means the​
     // light is on the other side of the surface.
-    let light_dot_normal = lightv.dot(normalv);
+    let light_dot_normal = lightv.dot(&ctx.normalv);
     let mut diffuse = Color::black();
     let mut specular = Color::black();
 
@@ -70,8 +182,8 @@ pub fn lighting(
         // reflect_dot_eye represents the cosine angle between the
         // reflection vector and the eye vector. Negative number
         // means the light reflects away from the eye
-        let reflectv = reflect(&(&lightv * -1.0), normalv);
-        let reflect_dot_eye = reflectv.dot(eyev);
+        let reflectv = (-lightv).reflect(&ctx.normalv);
+        let reflect_dot_eye = reflectv.dot(&ctx.eyev);
 
         if reflect_dot_eye >= 0.0 {
             // compute the specular contribution
@@ -80,28 +192,69 @@ pub fn lighting(
         }
     }
 
-    ambient + diffuse + specular
+    ambient + (diffuse + specular) * ctx.light_intensity
+}
+
+/// Same as [lighting], with the inputs passed one by one. A point in
+/// shadow gets none of the light, and any other point gets all of it.
+#[deprecated(note = "use `lighting` with a `LightingContext`")]
+pub fn lighting_positional(
+    material: &Material,
+    point_light: &PointLight,
+    position: &Tuple,
+    eyev: &Tuple,
+    normalv: &Tuple,
+    in_shadow: bool,
+) -> Color {
+    lighting(&LightingContext {
+        material,
+        light: point_light,
+        point: *position,
+        eyev: *eyev,
+        normalv: *normalv,
+        light_intensity: if in_shadow { 0.0 } else { 1.0 },
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use std::f64::consts::SQRT_2;
 
-    use super::{lighting, Material, PointLight};
-    use crate::{color::Color, spatial::Tuple};
+    use super::{lighting, LightingContext, Material, PointLight};
+    use crate::{
+        color::Color,
+        spatial::{Point, Tuple},
+    };
     use anyhow::Result;
 
+    #[test]
+    #[allow(deprecated)]
+    fn the_tuple_shim_checks_the_kind_of_its_tuple() -> Result<()> {
+        let typed = PointLight::new(Point::new(0, 10, -10), Color::new(1, 1, 1));
+        let untyped = PointLight::from_tuple(Tuple::point(0, 10, -10), Color::new(1, 1, 1))?;
+        assert_eq!(typed, untyped);
+
+        assert!(PointLight::from_tuple(Tuple::vector(0, 10, -10), Color::new(1, 1, 1)).is_err());
+
+        Ok(())
+    }
+
     #[test]
     fn lighting_with_eye_between_light_and_surface() -> Result<()> {
         let m = Material::default();
-        let position = Tuple::point(0, 0, 0);
+        let position = Tuple::origin();
 
         let eyev = Tuple::vector(0, 0, -1);
         let normal = Tuple::vector(0, 0, -1);
-        let point_light = PointLight::new(Tuple::point(0, 0, -10), Color::new(1, 1, 1))?;
-        let in_shadow = false;
-
-        let result = lighting(&m, &point_light, &position, &eyev, &normal, in_shadow);
+        let point_light = PointLight::new(Point::new(0, 0, -10), Color::new(1, 1, 1));
+        let result = lighting(&LightingContext {
+            material: &m,
+            light: &point_light,
+            point: position,
+            eyev,
+            normalv: normal,
+            light_intensity: 1.0,
+        });
         let expected = Color::new(1.9, 1.9, 1.9);
 
         assert_eq!(result, expected);
@@ -112,14 +265,19 @@ mod tests {
     #[test]
     fn light_between_source_and_surface_offset_45_degrees() -> Result<()> {
         let m = Material::default();
-        let position = Tuple::point(0, 0, 0);
+        let position = Tuple::origin();
 
         let eyev = Tuple::vector(0, SQRT_2 / 2.0, -SQRT_2 / 2.0);
         let normal = Tuple::vector(0, 0, -1);
-        let point_light = PointLight::new(Tuple::point(0, 0, -10), Color::new(1, 1, 1))?;
-        let in_shadow = false;
-
-        let result = lighting(&m, &point_light, &position, &eyev, &normal, in_shadow);
+        let point_light = PointLight::new(Point::new(0, 0, -10), Color::new(1, 1, 1));
+        let result = lighting(&LightingContext {
+            material: &m,
+            light: &point_light,
+            point: position,
+            eyev,
+            normalv: normal,
+            light_intensity: 1.0,
+        });
         let expected = Color::new(1, 1, 1);
 
         assert_eq!(result, expected);
@@ -130,14 +288,19 @@ mod tests {
     #[test]
     fn eye_opposite_surface_light_source_offset_45_degrees() -> Result<()> {
         let m = Material::default();
-        let position = Tuple::point(0, 0, 0);
+        let position = Tuple::origin();
 
         let eyev = Tuple::vector(0, 0, -1);
         let normal = Tuple::vector(0, 0, -1);
-        let point_light = PointLight::new(Tuple::point(0, 10, -10), Color::new(1, 1, 1))?;
-        let in_shadow = false;
-
-        let result = lighting(&m, &point_light, &position, &eyev, &normal, in_shadow);
+        let point_light = PointLight::new(Point::new(0, 10, -10), Color::new(1, 1, 1));
+        let result = lighting(&LightingContext {
+            material: &m,
+            light: &point_light,
+            point: position,
+            eyev,
+            normalv: normal,
+            light_intensity: 1.0,
+        });
         let expected = Color::new(0.7364, 0.7364, 0.7364);
 
         assert_eq!(result, expected);
@@ -148,14 +311,19 @@ mod tests {
     #[test]
     fn light_with_eye_in_path_of_reflection_vector() -> Result<()> {
         let m = Material::default();
-        let position = Tuple::point(0, 0, 0);
+        let position = Tuple::origin();
 
         let eyev = Tuple::vector(0, -SQRT_2 / 2.0, -SQRT_2 / 2.0);
         let normal = Tuple::vector(0, 0, -1);
-        let point_light = PointLight::new(Tuple::point(0, 10, -10), Color::new(1, 1, 1))?;
-        let in_shadow = false;
-
-        let result = lighting(&m, &point_light, &position, &eyev, &normal, in_shadow);
+        let point_light = PointLight::new(Point::new(0, 10, -10), Color::new(1, 1, 1));
+        let result = lighting(&LightingContext {
+            material: &m,
+            light: &point_light,
+            point: position,
+            eyev,
+            normalv: normal,
+            light_intensity: 1.0,
+        });
         let expected = Color::new(1.6364, 1.6364, 1.6364);
 
         assert_eq!(result, expected);
@@ -166,14 +334,19 @@ mod tests {
     #[test]
     fn light_behind_surface() -> Result<()> {
         let m = Material::default();
-        let position = Tuple::point(0, 0, 0);
+        let position = Tuple::origin();
 
         let eyev = Tuple::vector(0, 0, -1);
         let normal = Tuple::vector(0, 0, -1);
-        let point_light = PointLight::new(Tuple::point(0, 0, 10), Color::new(1, 1, 1))?;
-        let in_shadow = false;
-
-        let result = lighting(&m, &point_light, &position, &eyev, &normal, in_shadow);
+        let point_light = PointLight::new(Point::new(0, 0, 10), Color::new(1, 1, 1));
+        let result = lighting(&LightingContext {
+            material: &m,
+            light: &point_light,
+            point: position,
+            eyev,
+            normalv: normal,
+            light_intensity: 1.0,
+        });
         let expected = Color::new(0.1, 0.1, 0.1);
 
         assert_eq!(result, expected);
@@ -184,18 +357,141 @@ mod tests {
     #[test]
     fn lighting_with_surface_in_shadow() -> Result<()> {
         let m = Material::default();
-        let position = Tuple::point(0, 0, 0);
+        let position = Tuple::origin();
 
         let eyev = Tuple::vector(0, 0, -1);
         let normal = Tuple::vector(0, 0, -1);
-        let point_light = PointLight::new(Tuple::point(0, 0, -10), Color::new(1, 1, 1))?;
-        let in_shadow = true;
-
-        let result = lighting(&m, &point_light, &position, &eyev, &normal, in_shadow);
+        let point_light = PointLight::new(Point::new(0, 0, -10), Color::new(1, 1, 1));
+        let result = lighting(&LightingContext {
+            material: &m,
+            light: &point_light,
+            point: position,
+            eyev,
+            normalv: normal,
+            light_intensity: 0.0,
+        });
         let expected = Color::new(0.1, 0.1, 0.1);
 
         assert_eq!(result, expected);
 
         Ok(())
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn json_round_trip_of_a_light() -> Result<()> {
+        let light = PointLight::new(Point::new(-10, 10, -10), Color::new(1, 0.5, 0.25));
+        let back: PointLight = serde_json::from_str(&serde_json::to_string(&light)?)?;
+        assert_eq!(back, light);
+
+        let json = r#"{
+            "position": {"x": 0.0, "y": 0.0, "z": 0.0, "w": 0.0},
+            "intensity": {"red": 1.0, "green": 1.0, "blue": 1.0}
+        }"#;
+        assert!(serde_json::from_str::<PointLight>(json).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn partly_shadowed_points_get_part_of_the_light() -> Result<()> {
+        let m = Material::default();
+        let point_light = PointLight::new(Point::new(0, 0, -10), Color::new(1, 1, 1));
+
+        let result = lighting(&LightingContext {
+            material: &m,
+            light: &point_light,
+            point: Tuple::origin(),
+            eyev: Tuple::vector(0, 0, -1),
+            normalv: Tuple::vector(0, 0, -1),
+            light_intensity: 0.5,
+        });
+
+        // the ambient 0.1 plus half of the diffuse 0.9 and specular 0.9
+        assert_eq!(result, Color::new(1.0, 1.0, 1.0));
+
+        Ok(())
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn positional_lighting_matches_the_context_form() -> Result<()> {
+        let m = Material::default();
+        let point_light = PointLight::new(Point::new(0, 10, -10), Color::new(1, 1, 1));
+        let (position, eyev, normal) = (
+            Tuple::origin(),
+            Tuple::vector(0, 0, -1),
+            Tuple::vector(0, 0, -1),
+        );
+
+        for (in_shadow, light_intensity) in [(false, 1.0), (true, 0.0)] {
+            let positional =
+                super::lighting_positional(&m, &point_light, &position, &eyev, &normal, in_shadow);
+            let ctx = LightingContext {
+                material: &m,
+                light: &point_light,
+                point: position,
+                eyev,
+                normalv: normal,
+                light_intensity,
+            };
+
+            assert_eq!(positional, lighting(&ctx));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn accessors_reflect_the_constructor_values() -> Result<()> {
+        let mut light = PointLight::new(Point::new(0, 10, -10), Color::new(1, 0.5, 0));
+
+        assert_eq!(light.get_position(), Tuple::point(0, 10, -10));
+        assert_eq!(light.get_intensity(), Color::new(1, 0.5, 0));
+
+        light.set_position(Tuple::point(1, 2, 3))?;
+        light.set_intensity(Color::red());
+
+        assert_eq!(light.get_position(), Tuple::point(1, 2, 3));
+        assert_eq!(light.get_intensity(), Color::red());
+
+        Ok(())
+    }
+
+    #[test]
+    fn light_temperature_sets_the_color_and_brightness_scales_it() -> Result<()> {
+        let position = Tuple::point(0, 10, -10);
+        let dim = PointLight::with_temperature(position, 4000.0, 0.5)?;
+        let bright = PointLight::with_temperature(position, 4000.0, 1.5)?;
+
+        assert_eq!(dim.get_intensity(), Color::from_kelvin(4000.0) * 0.5);
+        assert_eq!(bright.get_intensity(), dim.get_intensity() * 3);
+        assert_eq!(bright.get_position(), position);
+
+        assert!(PointLight::with_temperature(Tuple::vector(0, 1, 0), 4000.0, 1.0).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn lights_cannot_be_moved_to_a_vector() -> Result<()> {
+        let mut light = PointLight::new(Point::new(0, 10, -10), Color::new(1, 1, 1));
+
+        assert!(light.set_position(Tuple::vector(1, 2, 3)).is_err());
+        assert_eq!(light.get_position(), Tuple::point(0, 10, -10));
+
+        Ok(())
+    }
+
+    #[test]
+    fn displaying_a_light() -> Result<()> {
+        let light = PointLight::new(Point::new(0, 10, -10), Color::new(1, 0.5, 0));
+
+        assert_eq!(
+            light.to_string(),
+            "PointLight at P(0.00000, 10.00000, -10.00000) intensity (1, 0.5, 0)"
+        );
+
+        Ok(())
+    }
 }
@@ -12,6 +12,44 @@ pub struct Material {
     diffuse: Positive,
     specular: Positive,
     shininess: Positive,
+    emissive: Color,
+    volume: Option<Volume>,
+}
+
+#[derive(Debug, Clone, Copy, PartialOrd, PartialEq)]
+/// A homogeneous participating medium, such as smoke or milk, that
+/// fills the inside of a shape
+pub struct Volume {
+    density: Positive,
+    color: Color,
+}
+
+impl Volume {
+    /// Create a medium with the given density, the chance per unit
+    /// of length for light to be scattered or absorbed, that scatters
+    /// light of the given color toward the eye
+    pub fn new(density: f64, color: Color) -> Self {
+        Self {
+            density: Positive::new(density).unwrap(),
+            color,
+        }
+    }
+
+    /// Get the density of the medium
+    pub fn get_density(&self) -> f64 {
+        self.density.into()
+    }
+
+    /// Get the color the medium scatters toward the eye
+    pub fn get_color(&self) -> Color {
+        self.color
+    }
+
+    /// The fraction of light that makes it through `distance` units
+    /// of the medium, following the Beer-Lambert law
+    pub fn transmittance(&self, distance: f64) -> f64 {
+        (-self.get_density() * distance).exp()
+    }
 }
 
 impl Material {
@@ -23,6 +61,18 @@ impl Material {
             diffuse: Positive::new(diffuse).unwrap(),
             specular: Positive::new(specular).unwrap(),
             shininess: Positive::new(shininess).unwrap(),
+            emissive: Color::black(),
+            volume: None,
+        }
+    }
+
+    /// Create a material that turns its shape into the boundary of a
+    /// homogeneous medium, see [Volume::new]. The surface itself isn't
+    /// shaded, rays pass through it and are attenuated on the way.
+    pub fn volume(density: f64, color: Color) -> Self {
+        Self {
+            volume: Some(Volume::new(density, color)),
+            ..Self::default()
         }
     }
 
@@ -75,6 +125,22 @@ impl Material {
     pub fn set_shininess(&mut self, shininess: f64) {
         self.shininess = Positive::new(shininess).unwrap();
     }
+
+    /// Get the light given off by the material
+    pub fn get_emissive(&self) -> Color {
+        self.emissive
+    }
+
+    /// Set the light given off by the material. Only the path tracing
+    /// integrator uses it, the Phong model ignores it.
+    pub fn set_emissive(&mut self, emissive: Color) {
+        self.emissive = emissive;
+    }
+
+    /// Get the medium filling shapes with this material, if any
+    pub fn get_volume(&self) -> Option<&Volume> {
+        self.volume.as_ref()
+    }
 }
 
 impl Default for Material {
@@ -85,6 +151,8 @@ impl Default for Material {
             diffuse: Positive::new(0.9).unwrap(),
             specular: Positive::new(0.9).unwrap(),
             shininess: Positive::new(200.0).unwrap(),
+            emissive: Color::black(),
+            volume: None,
         }
     }
 }
@@ -96,13 +164,15 @@ impl PartialEq for Material {
             && float_equals(&(self.diffuse.into()), &(other.diffuse).into())
             && float_equals(&(self.specular.into()), &(other.specular).into())
             && float_equals(&(self.shininess.into()), &(other.shininess).into())
+            && self.emissive == other.emissive
+            && self.volume == other.volume
     }
 }
 
 #[cfg(test)]
 mod test {
-    use super::Material;
-    use crate::color::Color;
+    use super::{Material, Volume};
+    use crate::{color::Color, utils::float_equals};
 
     #[test]
     fn create_default_material() {
@@ -113,5 +183,21 @@ mod test {
         assert_eq!(m.get_diffuse(), 0.9);
         assert_eq!(m.get_specular(), 0.9);
         assert_eq!(m.get_shininess(), 200.0);
+        assert_eq!(m.get_emissive(), Color::black());
+        assert_eq!(m.get_volume(), None);
+    }
+
+    #[test]
+    fn doubling_the_distance_squares_the_transmittance() {
+        let medium = Volume::new(0.7, Color::new(1, 1, 1));
+
+        for distance in [0.1, 1.0, 2.5] {
+            let once = medium.transmittance(distance);
+            let twice = medium.transmittance(2.0 * distance);
+            assert!(float_equals(&twice, &(once * once)));
+        }
+
+        assert_eq!(medium.transmittance(0.0), 1.0);
+        assert_eq!(Volume::new(0.0, Color::black()).transmittance(100.0), 1.0);
     }
 }
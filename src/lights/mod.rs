@@ -1,5 +1,7 @@
 mod light;
 mod material;
 
-pub use light::{lighting, PointLight};
-pub use material::Material;
+#[allow(deprecated)]
+pub use light::lighting_positional;
+pub use light::{lighting, LightingContext, PointLight};
+pub use material::{Material, Volume};
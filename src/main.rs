@@ -1,257 +1,186 @@
-use anyhow::Result;
-use raytracer::camera::Camera;
-use raytracer::canvas::Canvas;
-use raytracer::color::Color;
-use raytracer::intersections::{hit, Object, Ray, Sphere};
-use raytracer::lights::{lighting, Material, PointLight};
-use raytracer::matrix::{rotation_x, rotation_y, rotation_z, scaling, translation, view_transform};
-use raytracer::spatial::Tuple;
-use raytracer::tick::{tick, Environment, Projectile};
-use raytracer::world::World;
-use std::f64::consts::PI;
-
-#[allow(dead_code)]
-/// Chapter 2 tick example
-fn projectile_example() -> Result<()> {
-    println!("It's tick tick time");
-
-    let p = Tuple::point(0, 1, 0);
-    let v = &Tuple::vector(1, 1.8, 0).normalize() * 11.25;
-    let mut projectile = Projectile::new(p, v);
-
-    let g = Tuple::vector(0, -0.1, 0);
-    let w = Tuple::vector(-0.01, 0, 0);
-    let environment = Environment::new(g, w);
-
-    let width = 900;
-    let height = 550;
-    let mut canvas = Canvas::new(width, height);
-
-    while projectile.position.get_y() >= 0.0 {
-        let x = projectile.position.get_x();
-        let y = projectile.position.get_y();
-
-        if !(0.0..900.0).contains(&x) || !(0.0..500.0).contains(&(y)) {
-            continue;
-        }
+use anyhow::{Error, Result};
+use raytracer::bench;
+use raytracer::export::{write_canvas_as, ImageFormat};
+use raytracer::prelude::*;
+use raytracer::progress::ProgressBar;
+use raytracer::scenes;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// Options for the binary, read from the command line
+struct Options {
+    /// --scene <name>: which registered scene to render
+    scene: String,
+    /// --output <file>: where to write the image, `<scene>.ppm` by default
+    output: Option<String>,
+    /// --quiet: hide the progress bar while rendering
+    quiet: bool,
+    /// --binary: write .ppm files as binary (P6) instead of text
+    binary: bool,
+    /// --verbose: print a summary of the scene and the render
+    verbose: bool,
+    /// --preview: save a quarter resolution preview before the full render
+    preview: bool,
+    /// --benchmark: time the render of the scene instead of saving it
+    benchmark: bool,
+    /// --runs <n>: how many times to render the scene when benchmarking
+    runs: usize,
+    /// --size <n>: the width and height of the image when benchmarking
+    size: usize,
+}
 
-        canvas.write_pixel(x as usize, height - y as usize, Color::red())?;
-        projectile = tick(&environment, projectile);
+impl Options {
+    fn from_args() -> Result<Self> {
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        let value_of = |flag: &str| {
+            args.iter()
+                .position(|arg| arg == flag)
+                .and_then(|i| args.get(i + 1))
+                .cloned()
+        };
+
+        let runs = match value_of("--runs") {
+            Some(runs) => runs
+                .parse()
+                .map_err(|_| Error::msg(format!("Invalid number of runs '{}'", runs)))?,
+            None => 5,
+        };
+
+        let size = match value_of("--size") {
+            Some(size) => size
+                .parse()
+                .map_err(|_| Error::msg(format!("Invalid image size '{}'", size)))?,
+            None => 128,
+        };
+
+        Ok(Self {
+            scene: value_of("--scene").unwrap_or_else(|| "chapter7".to_string()),
+            output: value_of("--output"),
+            quiet: args.iter().any(|arg| arg == "--quiet"),
+            binary: args.iter().any(|arg| arg == "--binary"),
+            verbose: args.iter().any(|arg| arg == "--verbose"),
+            preview: args.iter().any(|arg| arg == "--preview"),
+            benchmark: args.iter().any(|arg| arg == "--benchmark"),
+            runs,
+            size,
+        })
     }
-
-    write_canvas_to_file("./projectile.ppm", &canvas);
-
-    Ok(())
 }
 
-#[allow(dead_code)]
-/// Chapter 4 analock clock example
-fn analog_clock() -> Result<()> {
-    let height = 500;
-    let width = 500;
-    let mut canvas = Canvas::new(width, height);
-    let p = Tuple::point(0, 200, 0);
-
-    let mut angle = 0_f64;
-
-    while angle < 2.0 * PI {
-        let transform = (&translation(250, 250, 0) * &rotation_z(angle))?;
-        let current_pos = &transform * &p;
-        let cur_y = height - current_pos.get_y() as usize;
-        let cur_x = current_pos.get_x() as usize;
-
-        if (0..height).contains(&cur_y) && (0..width).contains(&cur_x) {
-            canvas.write_pixel(cur_x, cur_y, Color::new(1, 1, 1))?;
-        }
+/// Looks up a registered scene, listing the known ones if it's missing
+fn find_scene(name: &str) -> Result<scenes::SceneFn> {
+    scenes::by_name(name).ok_or_else(|| {
+        let names: Vec<_> = scenes::registry().iter().map(|(n, _)| *n).collect();
+        Error::msg(format!(
+            "Unknown scene '{}', expected one of: {}",
+            name,
+            names.join(", ")
+        ))
+    })
+}
 
-        angle += PI / 6.0;
+/// Renders the scene picked in the options several times at the
+/// benchmark size, and prints how long each render and each phase of
+/// the render took
+fn benchmark_scene(options: &Options) -> Result<()> {
+    let scene = find_scene(&options.scene)?;
+    if options.runs == 0 {
+        return Err(Error::msg("A benchmark needs at least one run"));
     }
 
-    write_canvas_to_file("./analog_clock.ppm", &canvas);
+    println!("Benchmarking '{}'", options.scene);
+
+    print!("{}", bench::run_many(scene, options.size, options.runs)?);
 
     Ok(())
 }
 
-#[allow(dead_code)]
-fn cast_rays_on_sphere_2d() -> Result<()> {
-    let canvas_pixels = 100;
-    let height = canvas_pixels;
-    let width = canvas_pixels;
-    let ray_origin = Tuple::point(0, 0, -5);
-
-    let wall_z = 10.0;
-    let wall_size = 7.0; // max y
-    let half = wall_size / 2.0;
-    let pixel_size = wall_size / canvas_pixels as f64;
-
-    let mut canvas = Canvas::new(height, width);
+/// Renders the scene picked in the options, and writes it to the
+/// output file in the format matching its extension
+fn render_scene(options: &Options) -> Result<()> {
+    let scene = find_scene(&options.scene)?;
 
-    let mut s = Sphere::default();
-    s.set_transform((&rotation_z(PI / 4.0) * &scaling(0.5, 1, 1))?);
+    let output = options
+        .output
+        .clone()
+        .unwrap_or_else(|| format!("./{}.ppm", options.scene.to_lowercase()));
 
-    for y in 0..(height - 1) {
-        let world_y = half - (y as f64 * pixel_size);
-
-        for x in 0..(width - 1) {
-            let world_x = -half + (x as f64 * pixel_size);
+    // check the format before spending time on the render
+    let mut format = ImageFormat::from_path(&output)?;
+    if options.binary && format == ImageFormat::PpmText {
+        format = ImageFormat::PpmBinary;
+    }
 
-            let position_on_canvas = Tuple::point(world_x, world_y, wall_z);
-            let direction = (&position_on_canvas - &ray_origin).normalize();
+    let (world, camera) = scene()?;
 
-            let ray = Ray::new(ray_origin, direction)?;
+    if options.preview {
+        let preview = preview_path(&output);
+        write_canvas_as(
+            &preview,
+            &render(&camera.scaled(2), &world, options)?,
+            format,
+        )?;
 
-            if hit(s.intersect(&ray)?).is_some() {
-                canvas.write_pixel(x, y, Color::red())?;
-            }
+        if !options.quiet {
+            eprintln!("Wrote preview to {}", preview.display());
         }
     }
 
-    write_canvas_to_file("./cast_rays.ppm", &canvas);
+    let start = Instant::now();
+    let canvas = render(&camera, &world, options)?;
+    let elapsed = start.elapsed();
+
+    write_canvas_as(&output, &canvas, format)?;
+
+    if options.verbose {
+        let pixels = camera.get_hsize() * camera.get_vsize();
+
+        println!("{}", world.summary());
+        println!("resolution: {}x{}", camera.get_hsize(), camera.get_vsize());
+        println!("samples per pixel: {}", camera.get_samples_per_pixel());
+        println!("primary rays: {}", pixels * camera.get_samples_per_pixel());
+        println!("render time: {:.3}s", elapsed.as_secs_f64());
+        println!(
+            "output: {} ({} bytes)",
+            output,
+            std::fs::metadata(&output)?.len()
+        );
+    }
 
     Ok(())
 }
 
-#[allow(dead_code)]
-fn cast_rays_on_sphere_3d() -> Result<()> {
-    println!("It's time to render your first 3D sphere!");
-
-    let canvas_pixels = 100;
-    let height = canvas_pixels;
-    let width = canvas_pixels;
-    let ray_origin = Tuple::point(0, 0, -5);
-
-    let wall_z = 10.0;
-    let wall_size = 7.0; // max y
-    let half = wall_size / 2.0;
-    let pixel_size = wall_size / canvas_pixels as f64;
-
-    let mut canvas = Canvas::new(height, width);
-
-    let mut s = Sphere::default();
-    s.material.set_color(Color::new(1, 1, 1));
-
-    let light_position = Tuple::point(-10, 10, -10);
-    let light_color = Color::new(1, 0, 0);
-    let light = PointLight::new(light_position, light_color)?;
-
-    for y in 0..(width - 1) {
-        let world_y = half - (y as f64 * pixel_size);
-
-        for x in 0..(height - 1) {
-            let world_x = -half + (x as f64 * pixel_size);
-
-            let position_on_canvas = Tuple::point(world_x, world_y, wall_z);
-            let direction = (&position_on_canvas - &ray_origin).normalize();
-
-            let ray = Ray::new(ray_origin, direction)?;
-
-            let cur_hit = hit(s.intersect(&ray)?);
-
-            if cur_hit.is_some() {
-                let point = ray.position(cur_hit.unwrap().t);
-                let normal = s.normal_at(point)?;
-                let eye = -ray.direction;
-                let color = lighting(&s.material, &light, &point, &eye, &normal, false); // placeholder until shadows are accounted for
-
-                canvas.write_pixel(x, y, color)?;
-            }
-        }
+/// Renders the world, showing a progress bar unless asked to be quiet
+fn render(camera: &Camera, world: &World, options: &Options) -> Result<Canvas> {
+    if options.quiet {
+        camera.render(world)
+    } else {
+        let mut progress = ProgressBar::new();
+        camera.render_with_progress(world, |done, total| progress.update(done, total))
     }
-
-    write_canvas_to_file("./cast_rays3d.ppm", &canvas);
-
-    Ok(())
 }
 
-#[allow(dead_code)]
-fn render_a_world_chapter_7(vsize: usize, hsize: usize) -> Result<()> {
-    let mut floor_material = Material::default();
-    floor_material.set_color(Color::new(1, 0.9, 0.9));
-    floor_material.set_specular(0.0);
-    let floor = Sphere::new(scaling(10, 0.01, 10), floor_material);
-
-    let mut left_wall_transform = (&translation(0, 0, 5) * &rotation_y(-PI / 4.0))?;
-    left_wall_transform = (&left_wall_transform * &rotation_x(PI / 2.0))?;
-    left_wall_transform = (&left_wall_transform * &scaling(10, 0.01, 10))?;
-    let left_wall = Sphere::new(left_wall_transform, floor_material);
-
-    let mut right_wall_transform = (&translation(0, 0, 5) * &rotation_y(PI / 4.0))?;
-    right_wall_transform = (&right_wall_transform * &rotation_x(PI / 2.0))?;
-    right_wall_transform = (&right_wall_transform * &scaling(10, 0.01, 10))?;
-    let right_wall = Sphere::new(right_wall_transform, floor_material);
-
-    let mut middle_material = Material::default();
-    middle_material.set_color(Color::new(0.1, 1, 0.5));
-    middle_material.set_diffuse(0.7);
-    middle_material.set_specular(0.3);
-    let middle = Sphere::new(translation(-0.5, 1, 0.5), middle_material);
-
-    let mut right_material = Material::default();
-    right_material.set_color(Color::new(0.5, 1, 0.1));
-    right_material.set_diffuse(0.7);
-    right_material.set_specular(0.3);
-    let right = Sphere::new(
-        (&translation(1.5, 0.5, -0.5) * &scaling(0.5, 0.5, 0.5))?,
-        right_material,
-    );
-
-    let mut left_material = Material::default();
-    left_material.set_color(Color::new(1, 0.8, 0.1));
-    left_material.set_diffuse(0.7);
-    left_material.set_specular(0.3);
-    let left = Sphere::new(
-        (&translation(-1.5, 0.33, -0.75) * &scaling(0.33, 0.33, 0.33))?,
-        left_material,
-    );
-
-    let light_source = PointLight::new(Tuple::point(-10, 10, -10), Color::new(1, 1, 1))?;
+/// The path of the preview image for `output`: `render.png` is
+/// previewed in `render.preview.png`
+fn preview_path(output: &str) -> PathBuf {
+    let path = Path::new(output);
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
 
-    let mut world = World::empty();
-    world.set_light(Some(light_source));
-    world.add_object(Object::Sphere(floor));
-    world.add_object(Object::Sphere(left_wall));
-    world.add_object(Object::Sphere(right_wall));
-    world.add_object(Object::Sphere(middle));
-    world.add_object(Object::Sphere(left));
-    world.add_object(Object::Sphere(right));
-
-    let mut camera = Camera::new(hsize, vsize, PI / 3.0);
-    camera.set_transform(view_transform(
-        &Tuple::point(0, 1.5, -5),
-        &Tuple::point(0, 1, 0),
-        &Tuple::point(0, 1, 0),
-    ));
-
-    let canvas = camera.render(&world)?;
-
-    write_canvas_to_file("./chapter7render.ppm", &canvas);
-
-    Ok(())
-}
-
-fn write_canvas_to_file(filename: &str, canvas: &Canvas) {
-    std::fs::write(
-        filename,
-        canvas.to_ppm().expect("could not convert canvas to PPM"),
-    )
-    .expect("Cannot write to file");
+    match path.extension() {
+        Some(extension) => {
+            path.with_file_name(format!("{}.preview.{}", stem, extension.to_string_lossy()))
+        }
+        None => path.with_file_name(format!("{}.preview", stem)),
+    }
 }
 
 fn main() -> Result<()> {
-    // Projectile example from chapter 2
-    // projectile_example()?;
-
-    // analog clock example from chapter 4
-    // analog_clock()?;
-
-    // cast rays on sphere example from chapter 5
-    // cast_rays_on_sphere_2d()?;
+    let options = Options::from_args()?;
 
-    // cast rays on a sphere example from chapter 6
-    // cast_rays_on_sphere_3d()?;
-
-    // render a world from chapter 7
-    render_a_world_chapter_7(500, 500)?;
+    if options.benchmark {
+        return benchmark_scene(&options);
+    }
 
-    Ok(())
+    // the chapters before 7 draw without a camera, and live in examples/
+    render_scene(&options)
 }
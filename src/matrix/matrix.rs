@@ -1,10 +1,10 @@
-use anyhow::{Error, Result};
+use anyhow::Result;
 use std::{
     fmt::{Debug, Display},
     ops,
 };
 
-use crate::{spatial::Tuple, utils::float_equals};
+use crate::{error::RayTracerError, spatial::Tuple, utils::float_equals};
 
 #[derive(Debug, Clone, Copy, PartialOrd)]
 /// Representation of a Matrix of dimension `M x N`
@@ -80,7 +80,11 @@ impl<const M: usize, const N: usize> Matrix<M, N> {
         left_matrix: &Matrix<M2, N2>,
     ) -> Result<Matrix<M2, N>> {
         if M != N2 {
-            Err(Error::msg("Invalid indices for multiplication"))
+            Err(RayTracerError::InvalidDimensions {
+                expected: (M2, M),
+                got: (M2, N2),
+            }
+            .into())
         } else {
             let mut matrix = Matrix::new();
 
@@ -96,6 +100,22 @@ impl<const M: usize, const N: usize> Matrix<M, N> {
         }
     }
 
+    /// Copies the entries into a matrix of size `P x Q`, dropping any
+    /// that don't fit and filling the rest with zeros. This moves
+    /// between `Matrix<M, N>` and `Matrix<4, 4>` when the sizes are
+    /// known to match but the compiler can't see it.
+    fn resized<const P: usize, const Q: usize>(&self) -> Matrix<P, Q> {
+        let mut matrix = Matrix::<P, Q>::new();
+
+        for i in 0..M.min(P) {
+            for j in 0..N.min(Q) {
+                matrix[i][j] = self[i][j];
+            }
+        }
+
+        matrix
+    }
+
     /// When you transpose a matrix, its rows turn into columns
     /// and its columns into rows
     ///
@@ -155,11 +175,13 @@ impl<const M: usize, const N: usize> Matrix<M, N> {
         col: usize,
     ) -> Result<Matrix<P, Q>> {
         if P != M - 1 || Q != N - 1 {
-            return Err(Error::msg(
-                "Wrong submatrix dimensions. \
-                sorry, I am asking you to enter this,\
-             rust generic constants are dumb",
-            ));
+            // sorry, I am asking you to enter this, rust generic
+            // constants are dumb
+            return Err(RayTracerError::InvalidDimensions {
+                expected: (M - 1, N - 1),
+                got: (P, Q),
+            }
+            .into());
         }
 
         let mut mat = Matrix::<P, Q>::default();
@@ -190,6 +212,44 @@ impl<const M: usize, const N: usize> Matrix<M, N> {
     }
 }
 
+impl Matrix<4, 4> {
+    /// Calculates the inverse of the matrix, or `None` if it has no
+    /// inverse (its determinant is zero).
+    ///
+    /// This is the allocation-free form of [inverse_4x4](static_operations::inverse_4x4),
+    /// for callers that check invertibility once up front and then need
+    /// the inverse on every ray. The determinant is only evaluated once
+    /// and reused to divide each of the cofactors.
+    ///
+    /// ```
+    /// use raytracer::matrix::{scaling, Matrix};
+    ///
+    /// let inverse = scaling(2, 4, 8).try_inverse().unwrap();
+    /// assert_eq!(inverse, scaling(0.5, 0.25, 0.125));
+    ///
+    /// assert!(scaling(1, 0, 1).try_inverse().is_none());
+    /// ```
+    pub fn try_inverse(&self) -> Option<Matrix<4, 4>> {
+        use static_operations::{cofactor_4x4, determinant_4x4};
+
+        let determinant = determinant_4x4(self).ok()?;
+
+        if determinant == 0.0 {
+            return None;
+        }
+
+        let mut inverse = Matrix::<4, 4>::new();
+
+        for row in 0..4 {
+            for col in 0..4 {
+                inverse[col][row] = cofactor_4x4(self, row, col).ok()? / determinant
+            }
+        }
+
+        Some(inverse)
+    }
+}
+
 impl<const M: usize, const N: usize> From<[[f64; N]; M]> for Matrix<M, N> {
     fn from(value: [[f64; N]; M]) -> Self {
         Self { matrix: value }
@@ -222,13 +282,75 @@ impl ops::Mul<&Tuple> for &Matrix<4, 4> {
     type Output = Tuple;
 
     fn mul(self, rhs: &Tuple) -> Self::Output {
-        let tuple_as_matrix = Matrix::from(rhs);
-        let multiplication_result = tuple_as_matrix.multiply(self);
+        let (x, y, z, w) = (rhs.get_x(), rhs.get_y(), rhs.get_z(), rhs.get_w());
+        let row = |i: usize| self[i][0] * x + self[i][1] * y + self[i][2] * z + self[i][3] * w;
 
-        match multiplication_result {
-            Ok(m) => Tuple::from(m),
-            Err(_e) => Tuple::vector(0, 0, 0),
-        }
+        Tuple::from((row(0), row(1), row(2), row(3)))
+    }
+}
+
+impl ops::Mul<Tuple> for Matrix<4, 4> {
+    type Output = Tuple;
+
+    fn mul(self, rhs: Tuple) -> Self::Output {
+        ops::Mul::mul(&self, &rhs)
+    }
+}
+
+impl ops::Mul<&Tuple> for Matrix<4, 4> {
+    type Output = Tuple;
+
+    fn mul(self, rhs: &Tuple) -> Self::Output {
+        ops::Mul::mul(&self, rhs)
+    }
+}
+
+impl ops::Mul<Tuple> for &Matrix<4, 4> {
+    type Output = Tuple;
+
+    fn mul(self, rhs: Tuple) -> Self::Output {
+        ops::Mul::mul(self, &rhs)
+    }
+}
+
+/// Multiplying a 3x3 matrix with a [Tuple] only transforms its x, y, and z
+/// components, so the result is always a vector (w = 0). This is used for
+/// transforming directions like surface normals.
+impl ops::Mul<&Tuple> for &Matrix<3, 3> {
+    type Output = Tuple;
+
+    fn mul(self, rhs: &Tuple) -> Self::Output {
+        let (x, y, z) = (rhs.get_x(), rhs.get_y(), rhs.get_z());
+
+        Tuple::vector(
+            self[0][0] * x + self[0][1] * y + self[0][2] * z,
+            self[1][0] * x + self[1][1] * y + self[1][2] * z,
+            self[2][0] * x + self[2][1] * y + self[2][2] * z,
+        )
+    }
+}
+
+impl ops::Mul<Tuple> for Matrix<3, 3> {
+    type Output = Tuple;
+
+    fn mul(self, rhs: Tuple) -> Self::Output {
+        ops::Mul::mul(&self, &rhs)
+    }
+}
+
+impl ops::Mul<&Tuple> for Matrix<3, 3> {
+    type Output = Tuple;
+
+    fn mul(self, rhs: &Tuple) -> Self::Output {
+        ops::Mul::mul(&self, rhs)
+    }
+}
+
+impl ops::Mul<Tuple> for &Matrix<3, 3> {
+    type Output = Tuple;
+
+    fn mul(self, rhs: Tuple) -> Self::Output {
+        ops::Mul::mul(self, &rhs)
     }
 }
 
@@ -238,10 +360,48 @@ impl<const M: usize, const N: usize, const P: usize, const Q: usize> ops::Mul<&M
     type Output = Result<Matrix<M, Q>>;
 
     fn mul(self, rhs: &Matrix<P, Q>) -> Self::Output {
+        // almost every product is of two 4x4 transforms, so those take
+        // the written out path. The sizes are constants, so the compiler
+        // resolves this branch for each size.
+        if M == 4 && N == 4 && P == 4 && Q == 4 {
+            let product = static_operations::mul_4x4(&self.resized(), &rhs.resized());
+            return Ok(product.resized());
+        }
+
         rhs.multiply(self)
     }
 }
 
+impl<const M: usize, const N: usize, const P: usize, const Q: usize> ops::Mul<Matrix<P, Q>>
+    for Matrix<M, N>
+{
+    type Output = Result<Matrix<M, Q>>;
+
+    fn mul(self, rhs: Matrix<P, Q>) -> Self::Output {
+        ops::Mul::mul(&self, &rhs)
+    }
+}
+
+impl<const M: usize, const N: usize, const P: usize, const Q: usize> ops::Mul<&Matrix<P, Q>>
+    for Matrix<M, N>
+{
+    type Output = Result<Matrix<M, Q>>;
+
+    fn mul(self, rhs: &Matrix<P, Q>) -> Self::Output {
+        ops::Mul::mul(&self, rhs)
+    }
+}
+
+impl<const M: usize, const N: usize, const P: usize, const Q: usize> ops::Mul<Matrix<P, Q>>
+    for &Matrix<M, N>
+{
+    type Output = Result<Matrix<M, Q>>;
+
+    fn mul(self, rhs: Matrix<P, Q>) -> Self::Output {
+        ops::Mul::mul(self, &rhs)
+    }
+}
+
 impl From<Matrix<4, 1>> for Tuple {
     fn from(value: Matrix<4, 1>) -> Self {
         Self::from((value[0][0], value[1][0], value[2][0], value[3][0]))
@@ -318,7 +478,16 @@ impl<const M: usize, const N: usize> Default for Matrix<M, N> {
 /// implementations for all of these specific sizes.
 pub mod static_operations {
     use super::Matrix;
-    use anyhow::{Error, Result};
+    use crate::error::RayTracerError;
+    use anyhow::Result;
+
+    #[cfg(test)]
+    thread_local! {
+        /// Counts calls to [determinant_4x4] on the current thread, so tests
+        /// can check how often the determinant gets evaluated
+        pub(crate) static DETERMINANT_4X4_CALLS: std::cell::Cell<usize> =
+            const { std::cell::Cell::new(0) };
+    }
 
     /// Calculates the determinant of a 2x2 matrix
     pub(super) fn determinant_2x2(matrix: &Matrix<2, 2>) -> f64 {
@@ -335,7 +504,7 @@ pub mod static_operations {
     pub(super) fn cofactor_3x3(matrix: &Matrix<3, 3>, row: usize, col: usize) -> Result<f64> {
         let minor = minor_3x3(matrix, row, col)?;
 
-        if (row + col) % 2 == 0 {
+        if (row + col).is_multiple_of(2) {
             Ok(minor)
         } else {
             Ok(-minor)
@@ -353,6 +522,28 @@ pub mod static_operations {
         Ok(det)
     }
 
+    /// Calculates the inverse of a 3x3 matrix.
+    ///
+    /// Just like [inverse_4x4], this returns an error if the
+    /// matrix is not invertible (its determinant is zero).
+    pub fn inverse_3x3(matrix: &Matrix<3, 3>) -> Result<Matrix<3, 3>> {
+        let determinant = determinant_3x3(matrix)?;
+
+        if determinant == 0.0 {
+            return Err(RayTracerError::NonInvertibleMatrix.into());
+        }
+
+        let mut inverse = Matrix::<3, 3>::new();
+
+        for row in 0..3 {
+            for col in 0..3 {
+                inverse[col][row] = cofactor_3x3(matrix, row, col)? / determinant;
+            }
+        }
+
+        Ok(inverse)
+    }
+
     /// Calculates the minor of a 4x4 matrix
     pub(super) fn minor_4x4(matrix: &Matrix<4, 4>, row: usize, col: usize) -> Result<f64> {
         let sub_matrix = matrix.submatrix::<3, 3>(row, col)?;
@@ -363,7 +554,7 @@ pub mod static_operations {
     pub(super) fn cofactor_4x4(matrix: &Matrix<4, 4>, row: usize, col: usize) -> Result<f64> {
         let minor = minor_4x4(matrix, row, col)?;
 
-        if (row + col) % 2 == 0 {
+        if (row + col).is_multiple_of(2) {
             Ok(minor)
         } else {
             Ok(-minor)
@@ -372,6 +563,9 @@ pub mod static_operations {
 
     /// Calculates the determinant of a 4x4 matrix
     pub(super) fn determinant_4x4(matrix: &Matrix<4, 4>) -> Result<f64> {
+        #[cfg(test)]
+        DETERMINANT_4X4_CALLS.with(|calls| calls.set(calls.get() + 1));
+
         let mut det = 0_f64;
 
         for col in 0..4 {
@@ -382,7 +576,7 @@ pub mod static_operations {
     }
 
     /// Checks if a 4x4 matrix is invertible
-    pub(super) fn is_invertible_4x4(matrix: &Matrix<4, 4>) -> Result<bool> {
+    pub fn is_invertible_4x4(matrix: &Matrix<4, 4>) -> Result<bool> {
         Ok(determinant_4x4(matrix)? != 0.0)
     }
 
@@ -393,28 +587,50 @@ pub mod static_operations {
     /// first check if the matrix is invertible (the determinant of the matrix
     /// is non-zero for invertible matrices). If a matrix is not invertible, we
     /// will return an error to the caller.
+    ///
+    /// See [Matrix::try_inverse] for a version that returns an [Option].
     pub fn inverse_4x4(matrix: &Matrix<4, 4>) -> Result<Matrix<4, 4>> {
-        if !is_invertible_4x4(matrix)? {
-            return Err(Error::msg("Matrix is not invertible"));
-        }
-
-        let mut inverse = Matrix::<4, 4>::new();
+        matrix
+            .try_inverse()
+            .ok_or_else(|| RayTracerError::NonInvertibleMatrix.into())
+    }
 
-        for row in 0..4 {
-            for col in 0..4 {
-                inverse[col][row] = cofactor_4x4(matrix, row, col)? / determinant_4x4(matrix)?
+    /// Multiplies two 4x4 matrices, `a x b`.
+    ///
+    /// This is a specialized version of [Matrix::multiply] for the 4x4
+    /// transformation matrices we use everywhere. Since the dimensions are
+    /// known, it cannot fail and the inner products are written out in full.
+    ///
+    /// ```
+    /// use raytracer::matrix::{mul_4x4, scaling, translation};
+    ///
+    /// let a = translation(1, 2, 3);
+    /// let b = scaling(2, 2, 2);
+    ///
+    /// assert_eq!(mul_4x4(&a, &b), (a * b).unwrap());
+    /// ```
+    pub fn mul_4x4(a: &Matrix<4, 4>, b: &Matrix<4, 4>) -> Matrix<4, 4> {
+        let mut matrix = Matrix::<4, 4>::new();
+
+        for i in 0..4 {
+            let row = &a[i];
+            for j in 0..4 {
+                matrix[i][j] =
+                    row[0] * b[0][j] + row[1] * b[1][j] + row[2] * b[2][j] + row[3] * b[3][j];
             }
         }
 
-        Ok(inverse)
+        matrix
     }
 }
 
 #[cfg(test)]
+// the tests spell out the borrowed forms of the operators on purpose
+#[allow(clippy::op_ref)]
 mod tests {
     use super::static_operations::{
-        cofactor_3x3, determinant_2x2, determinant_3x3, determinant_4x4, inverse_4x4,
-        is_invertible_4x4, minor_3x3,
+        cofactor_3x3, determinant_2x2, determinant_3x3, determinant_4x4, inverse_3x3, inverse_4x4,
+        is_invertible_4x4, minor_3x3, mul_4x4, DETERMINANT_4X4_CALLS,
     };
     use super::Matrix;
     use crate::spatial::Tuple;
@@ -574,6 +790,53 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn owned_matrix_multiplication_matches_reference_forms() -> Result<()> {
+        let m_a = Matrix::from([
+            [1.0, 2.0, 3.0, 4.0],
+            [5.0, 6.0, 7.0, 8.0],
+            [9.0, 8.0, 7.0, 6.0],
+            [5.0, 4.0, 3.0, 2.0],
+        ]);
+
+        let m_b = Matrix::from([
+            [-2.0, 1.0, 2.0, 3.0],
+            [3.0, 2.0, 1.0, -1.0],
+            [4.0, 3.0, 6.0, 5.0],
+            [1.0, 2.0, 7.0, 8.0],
+        ]);
+
+        let expected = (&m_a * &m_b)?;
+
+        assert_eq!((m_a * m_b)?, expected);
+        assert_eq!((m_a * &m_b)?, expected);
+        assert_eq!((&m_a * m_b)?, expected);
+
+        // Invalid dimensions still fail with the owned forms
+        let matrix_2x2 = Matrix::from([[-3.0, 5.0], [1.0, -2.0]]);
+        assert!((m_a * matrix_2x2).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn owned_matrix_and_tuple_multiplication_matches_reference_forms() {
+        let matrix = Matrix::from([
+            [1.0, 2.0, 3.0, 4.0],
+            [2.0, 4.0, 4.0, 2.0],
+            [8.0, 6.0, 4.0, 1.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ]);
+
+        let tuple = Tuple::point(1, 2, 3);
+        let expected = Tuple::point(18, 24, 33);
+
+        assert_eq!(&matrix * &tuple, expected);
+        assert_eq!(matrix * tuple, expected);
+        assert_eq!(matrix * &tuple, expected);
+        assert_eq!(&matrix * tuple, expected);
+    }
+
     #[test]
     fn construct_and_use_identity_matrix() -> Result<()> {
         let identity_2x2 = Matrix::<2, 2>::identity();
@@ -792,4 +1055,153 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn inverse_of_3x3_matrices_works() -> Result<()> {
+        let m = Matrix::from([[1.0, 2.0, 6.0], [-5.0, 8.0, -4.0], [2.0, 6.0, 4.0]]);
+        let inverse = inverse_3x3(&m)?;
+
+        assert_eq!((m * inverse)?, Matrix::<3, 3>::identity());
+
+        let singular = Matrix::from([[1.0, 2.0, 3.0], [2.0, 4.0, 6.0], [0.0, 1.0, 1.0]]);
+        assert!(inverse_3x3(&singular).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn multiplying_a_3x3_matrix_and_tuple_yields_a_vector() {
+        let m = Matrix::from([[1.0, 2.0, 3.0], [0.0, 1.0, 0.0], [2.0, 0.0, 1.0]]);
+
+        // The w component is ignored, so points and vectors transform alike
+        let from_point = &m * &Tuple::point(1, 2, 3);
+        let from_vector = m * Tuple::vector(1, 2, 3);
+
+        assert_eq!(from_point, Tuple::vector(14, 2, 5));
+        assert_eq!(from_vector, Tuple::vector(14, 2, 5));
+        assert_eq!(from_point.get_w(), 0.0);
+    }
+
+    /// Tiny deterministic generator so the property tests below
+    /// always run against the same "random" matrices
+    fn random_matrices(count: usize) -> Vec<Matrix<4, 4>> {
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % 20_000) as f64 / 1000.0 - 10.0
+        };
+
+        (0..count)
+            .map(|_| {
+                let mut m = Matrix::<4, 4>::default();
+                for i in 0..4 {
+                    for j in 0..4 {
+                        m[i][j] = next();
+                    }
+                }
+                m
+            })
+            .collect()
+    }
+
+    #[test]
+    fn mul_4x4_agrees_with_generic_multiply() -> Result<()> {
+        let matrices = random_matrices(40);
+
+        for pair in matrices.chunks(2) {
+            let (a, b) = (&pair[0], &pair[1]);
+            assert_eq!(mul_4x4(a, b), b.multiply(a)?);
+            assert_eq!((a * b)?, mul_4x4(a, b));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn matrix_tuple_product_agrees_with_generic_multiply() -> Result<()> {
+        for m in random_matrices(20) {
+            for t in [Tuple::point(1.5, -2, 3), Tuple::vector(-4, 0.25, 7)] {
+                let generic = Tuple::from(Matrix::from(t).multiply(&m)?);
+                assert_eq!(m * t, generic);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn inverse_4x4_agrees_with_identity_on_random_matrices() -> Result<()> {
+        for m in random_matrices(20) {
+            if determinant_4x4(&m)?.abs() < 1e-3 {
+                continue;
+            }
+
+            let inverse = inverse_4x4(&m)?;
+            assert_eq!(mul_4x4(&m, &inverse), Matrix::<4, 4>::identity());
+            assert_eq!(mul_4x4(&inverse, &m), Matrix::<4, 4>::identity());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn inverse_4x4_evaluates_the_determinant_once() -> Result<()> {
+        let m = Matrix::from([
+            [-5.0, 2.0, 6.0, -8.0],
+            [1.0, -5.0, 1.0, 8.0],
+            [7.0, 7.0, -6.0, -7.0],
+            [1.0, -3.0, 7.0, 4.0],
+        ]);
+
+        DETERMINANT_4X4_CALLS.with(|calls| calls.set(0));
+        inverse_4x4(&m)?;
+        assert_eq!(DETERMINANT_4X4_CALLS.with(|calls| calls.get()), 1);
+
+        DETERMINANT_4X4_CALLS.with(|calls| calls.set(0));
+        m.try_inverse();
+        assert_eq!(DETERMINANT_4X4_CALLS.with(|calls| calls.get()), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn try_inverse_agrees_with_inverse_4x4() -> Result<()> {
+        let fixtures = [
+            Matrix::from([
+                [-5.0, 2.0, 6.0, -8.0],
+                [1.0, -5.0, 1.0, 8.0],
+                [7.0, 7.0, -6.0, -7.0],
+                [1.0, -3.0, 7.0, 4.0],
+            ]),
+            Matrix::from([
+                [9.0, 3.0, 0.0, 9.0],
+                [-5.0, -2.0, -6.0, -3.0],
+                [-4.0, 9.0, 6.0, 4.0],
+                [-7.0, 6.0, 6.0, 2.0],
+            ]),
+            Matrix::from([
+                [8.0, -5.0, 9.0, 2.0],
+                [7.0, 5.0, 6.0, 1.0],
+                [-6.0, 0.0, 9.0, 6.0],
+                [-3.0, 0.0, -9.0, -4.0],
+            ]),
+        ];
+
+        for m in fixtures {
+            assert_eq!(m.try_inverse(), Some(inverse_4x4(&m)?));
+        }
+
+        let singular = Matrix::from([
+            [-2.0, -8.0, 1.0, 1.0],
+            [-2.0, -8.0, 1.0, 1.0],
+            [1.0, 4.0, 1.0, 1.0],
+            [1.0, 4.0, 1.0, 1.0],
+        ]);
+        assert_eq!(singular.try_inverse(), None);
+        assert!(inverse_4x4(&singular).is_err());
+
+        Ok(())
+    }
 }
@@ -1,9 +1,17 @@
 #[allow(clippy::module_inception)]
 mod matrix;
+#[cfg(feature = "serde")]
+mod serialization;
+mod transform_spec;
 mod transformations;
 
-pub use matrix::static_operations::inverse_4x4;
+#[cfg(test)]
+pub(crate) use matrix::static_operations::DETERMINANT_4X4_CALLS;
+pub use matrix::static_operations::{inverse_3x3, inverse_4x4, is_invertible_4x4, mul_4x4};
 pub use matrix::Matrix;
+pub use transform_spec::TransformSpec;
+#[allow(deprecated)]
+pub use transformations::view_transform_tuples;
 pub use transformations::{
     rotation_x, rotation_y, rotation_z, scaling, shearing, translation, view_transform,
 };
@@ -0,0 +1,122 @@
+use super::Matrix;
+use serde::{
+    de::{self, SeqAccess, Visitor},
+    ser::SerializeSeq,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use std::{fmt, marker::PhantomData};
+
+/// A [Matrix] is serialized as a list of its rows, where each row is a
+/// list of [f64] values. A 2x2 identity matrix becomes `[[1.0, 0.0], [0.0, 1.0]]`.
+impl<const M: usize, const N: usize> Serialize for Matrix<M, N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut rows = serializer.serialize_seq(Some(M))?;
+        for i in 0..M {
+            rows.serialize_element(&self[i][..])?;
+        }
+        rows.end()
+    }
+}
+
+/// Deserializing expects exactly `M` rows of exactly `N` values each,
+/// and reports an invalid length error otherwise.
+impl<'de, const M: usize, const N: usize> Deserialize<'de> for Matrix<M, N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_seq(MatrixVisitor::<M, N>)
+    }
+}
+
+struct MatrixVisitor<const M: usize, const N: usize>;
+
+impl<'de, const M: usize, const N: usize> Visitor<'de> for MatrixVisitor<M, N> {
+    type Value = Matrix<M, N>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a list of {} rows containing {} numbers each", M, N)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut matrix = Matrix::<M, N>::default();
+
+        for i in 0..M {
+            matrix[i] = seq
+                .next_element_seed(RowSeed::<N>(PhantomData))?
+                .ok_or_else(|| de::Error::invalid_length(i, &self))?;
+        }
+
+        if seq.next_element::<de::IgnoredAny>()?.is_some() {
+            return Err(de::Error::invalid_length(M + 1, &self));
+        }
+
+        Ok(matrix)
+    }
+}
+
+/// Helper used to deserialize a single row of `N` values
+struct RowSeed<const N: usize>(PhantomData<[f64; N]>);
+
+impl<'de, const N: usize> de::DeserializeSeed<'de> for RowSeed<N> {
+    type Value = [f64; N];
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, const N: usize> Visitor<'de> for RowSeed<N> {
+    type Value = [f64; N];
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a row of {} numbers", N)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut row = [0.0; N];
+
+        for (j, cell) in row.iter_mut().enumerate() {
+            *cell = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(j, &self))?;
+        }
+
+        if seq.next_element::<de::IgnoredAny>()?.is_some() {
+            return Err(de::Error::invalid_length(N + 1, &self));
+        }
+
+        Ok(row)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Matrix;
+    use crate::matrix::{rotation_x, translation};
+    use anyhow::Result;
+
+    #[test]
+    fn json_round_trip_of_a_4x4_matrix() -> Result<()> {
+        let m = (translation(1, 2, 3) * rotation_x(0.5))?;
+
+        let json = serde_json::to_string(&m)?;
+        let back: Matrix<4, 4> = serde_json::from_str(&json)?;
+
+        assert_eq!(back, m);
+
+        Ok(())
+    }
+
+    #[test]
+    fn matrices_serialize_as_nested_arrays() -> Result<()> {
+        let m = Matrix::from([[1.0, 2.0], [3.0, 4.0]]);
+        assert_eq!(serde_json::to_string(&m)?, "[[1.0,2.0],[3.0,4.0]]");
+        Ok(())
+    }
+
+    #[test]
+    fn deserializing_wrong_dimensions_fails() {
+        assert!(serde_json::from_str::<Matrix<2, 2>>("[[1.0, 2.0]]").is_err());
+        assert!(serde_json::from_str::<Matrix<2, 2>>("[[1.0, 2.0], [3.0]]").is_err());
+        assert!(serde_json::from_str::<Matrix<2, 2>>("[[1.0, 2.0], [3.0, 4.0, 5.0]]").is_err());
+        assert!(serde_json::from_str::<Matrix<1, 1>>("[[1.0], [2.0]]").is_err());
+    }
+}
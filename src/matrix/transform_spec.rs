@@ -0,0 +1,320 @@
+use super::{mul_4x4, rotation_x, rotation_y, rotation_z, scaling, shearing, translation, Matrix};
+use anyhow::Result;
+
+#[derive(Debug, Clone, PartialEq)]
+/// A description of a transformation that can be turned into a
+/// 4x4 transformation [Matrix]. This is the form transforms take
+/// in scene files.
+///
+/// With the `serde` feature enabled, each transform is written as a
+/// compact list of its name followed by its arguments, and a list of
+/// such lists is a composition of transforms:
+///
+/// ```text
+/// ["scale", 2, 2, 2]
+/// [["rotate-y", 0.5], ["translate", 1, 0, 0]]
+/// ```
+pub enum TransformSpec {
+    /// Moves points by `(x, y, z)`. See [translation].
+    Translate(f64, f64, f64),
+    /// Scales by `(x, y, z)` along each axis. See [scaling].
+    Scale(f64, f64, f64),
+    /// Rotates by the given radians around the x axis. See [rotation_x].
+    RotateX(f64),
+    /// Rotates by the given radians around the y axis. See [rotation_y].
+    RotateY(f64),
+    /// Rotates by the given radians around the z axis. See [rotation_z].
+    RotateZ(f64),
+    /// Shears each component in proportion to the other two, with the
+    /// arguments in the order `(x_y, x_z, y_x, y_z, z_x, z_y)`, where
+    /// `x_y` is how much x moves in proportion to y. See [shearing].
+    Shear(f64, f64, f64, f64, f64, f64),
+    /// A sequence of transforms, applied in the order they are listed
+    Compose(Vec<TransformSpec>),
+}
+
+impl TransformSpec {
+    /// Builds the transformation matrix described by this spec.
+    ///
+    /// For a [TransformSpec::Compose], the first transform in the list
+    /// is applied first, so `[A, B, C]` produces the matrix `C x B x A`.
+    ///
+    /// ```
+    /// use raytracer::matrix::{scaling, translation, TransformSpec};
+    ///
+    /// let spec = TransformSpec::Compose(vec![
+    ///     TransformSpec::Scale(2.0, 2.0, 2.0),
+    ///     TransformSpec::Translate(1.0, 0.0, 0.0),
+    /// ]);
+    ///
+    /// let expected = (translation(1, 0, 0) * scaling(2, 2, 2)).unwrap();
+    /// assert_eq!(spec.to_matrix().unwrap(), expected);
+    /// ```
+    pub fn to_matrix(&self) -> Result<Matrix<4, 4>> {
+        Ok(match *self {
+            Self::Translate(x, y, z) => translation(x, y, z),
+            Self::Scale(x, y, z) => scaling(x, y, z),
+            Self::RotateX(r) => rotation_x(r),
+            Self::RotateY(r) => rotation_y(r),
+            Self::RotateZ(r) => rotation_z(r),
+            Self::Shear(x_y, x_z, y_x, y_z, z_x, z_y) => shearing(x_y, x_z, y_x, y_z, z_x, z_y),
+            Self::Compose(ref specs) => {
+                let mut result = Matrix::<4, 4>::identity();
+                for spec in specs {
+                    result = mul_4x4(&spec.to_matrix()?, &result);
+                }
+                result
+            }
+        })
+    }
+
+    /// The name used for this transform in the compact list form
+    #[cfg(feature = "serde")]
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Translate(..) => "translate",
+            Self::Scale(..) => "scale",
+            Self::RotateX(_) => "rotate-x",
+            Self::RotateY(_) => "rotate-y",
+            Self::RotateZ(_) => "rotate-z",
+            Self::Shear(..) => "shear",
+            Self::Compose(_) => "compose",
+        }
+    }
+
+    /// The numeric arguments of this transform in the compact list form
+    #[cfg(feature = "serde")]
+    fn arguments(&self) -> Vec<f64> {
+        match *self {
+            Self::Translate(x, y, z) | Self::Scale(x, y, z) => vec![x, y, z],
+            Self::RotateX(r) | Self::RotateY(r) | Self::RotateZ(r) => vec![r],
+            Self::Shear(a, b, c, d, e, f) => vec![a, b, c, d, e, f],
+            Self::Compose(_) => vec![],
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serialization {
+    use super::TransformSpec;
+    use serde::{
+        de::{self, SeqAccess, Visitor},
+        ser::SerializeSeq,
+        Deserialize, Deserializer, Serialize, Serializer,
+    };
+    use std::fmt;
+
+    impl Serialize for TransformSpec {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut seq = serializer.serialize_seq(None)?;
+            match self {
+                TransformSpec::Compose(specs) => {
+                    for spec in specs {
+                        seq.serialize_element(spec)?;
+                    }
+                }
+                _ => {
+                    seq.serialize_element(self.name())?;
+                    for arg in self.arguments() {
+                        seq.serialize_element(&arg)?;
+                    }
+                }
+            }
+            seq.end()
+        }
+    }
+
+    impl<'de> Deserialize<'de> for TransformSpec {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            deserializer.deserialize_seq(TransformSpecVisitor)
+        }
+    }
+
+    /// The first element of a compact list decides what the list is:
+    /// a transform name, or the first transform of a composition
+    enum Head {
+        Name(String),
+        Spec(TransformSpec),
+    }
+
+    impl<'de> Deserialize<'de> for Head {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            deserializer.deserialize_any(HeadVisitor)
+        }
+    }
+
+    struct HeadVisitor;
+
+    impl<'de> Visitor<'de> for HeadVisitor {
+        type Value = Head;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "a transform name or a transform list")
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+            Ok(Head::Name(v.to_string()))
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+            TransformSpecVisitor.visit_seq(seq).map(Head::Spec)
+        }
+    }
+
+    struct TransformSpecVisitor;
+
+    impl<'de> Visitor<'de> for TransformSpecVisitor {
+        type Value = TransformSpec;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(
+                f,
+                "a transform such as [\"scale\", 2, 2, 2] or a list of transforms"
+            )
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let name = match seq.next_element::<Head>()? {
+                None => return Ok(TransformSpec::Compose(vec![])),
+                Some(Head::Spec(first)) => {
+                    let mut specs = vec![first];
+                    while let Some(spec) = seq.next_element()? {
+                        specs.push(spec);
+                    }
+                    return Ok(TransformSpec::Compose(specs));
+                }
+                Some(Head::Name(name)) => name,
+            };
+
+            let mut args: Vec<f64> = vec![];
+            while let Some(arg) = seq.next_element()? {
+                args.push(arg);
+            }
+
+            let expected_args = match name.as_str() {
+                "translate" | "scale" => 3,
+                "rotate-x" | "rotate-y" | "rotate-z" => 1,
+                "shear" => 6,
+                _ => {
+                    return Err(de::Error::unknown_variant(
+                        &name,
+                        &[
+                            "translate",
+                            "scale",
+                            "rotate-x",
+                            "rotate-y",
+                            "rotate-z",
+                            "shear",
+                        ],
+                    ))
+                }
+            };
+
+            if args.len() != expected_args {
+                return Err(de::Error::custom(format!(
+                    "'{}' expects {} arguments but got {}",
+                    name,
+                    expected_args,
+                    args.len()
+                )));
+            }
+
+            Ok(match name.as_str() {
+                "translate" => TransformSpec::Translate(args[0], args[1], args[2]),
+                "scale" => TransformSpec::Scale(args[0], args[1], args[2]),
+                "rotate-x" => TransformSpec::RotateX(args[0]),
+                "rotate-y" => TransformSpec::RotateY(args[0]),
+                "rotate-z" => TransformSpec::RotateZ(args[0]),
+                _ => TransformSpec::Shear(args[0], args[1], args[2], args[3], args[4], args[5]),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TransformSpec;
+    use crate::matrix::{rotation_x, scaling, shearing, translation, Matrix};
+    use anyhow::Result;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn single_specs_build_their_matrices() -> Result<()> {
+        assert_eq!(
+            TransformSpec::Translate(1.0, 2.0, 3.0).to_matrix()?,
+            translation(1, 2, 3)
+        );
+        assert_eq!(
+            TransformSpec::Scale(2.0, 2.0, 2.0).to_matrix()?,
+            scaling(2, 2, 2)
+        );
+        assert_eq!(TransformSpec::RotateX(PI).to_matrix()?, rotation_x(PI));
+        assert_eq!(
+            TransformSpec::Shear(1.0, 0.0, 0.0, 0.0, 0.0, 1.0).to_matrix()?,
+            shearing(1, 0, 0, 0, 0, 1)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn composed_specs_apply_in_listed_order() -> Result<()> {
+        let spec = TransformSpec::Compose(vec![
+            TransformSpec::RotateX(PI / 2.0),
+            TransformSpec::Scale(5.0, 5.0, 5.0),
+            TransformSpec::Translate(10.0, 5.0, 7.0),
+        ]);
+
+        let expected = (translation(10, 5, 7) * scaling(5, 5, 5))?;
+        let expected = (expected * rotation_x(PI / 2.0))?;
+
+        assert_eq!(spec.to_matrix()?, expected);
+        assert_eq!(
+            TransformSpec::Compose(vec![]).to_matrix()?,
+            Matrix::<4, 4>::identity()
+        );
+
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn compact_list_form_deserializes_and_composes() -> Result<()> {
+        let spec: TransformSpec = serde_json::from_str(r#"["scale", 2, 2, 2]"#)?;
+        assert_eq!(spec, TransformSpec::Scale(2.0, 2.0, 2.0));
+
+        let spec: TransformSpec =
+            serde_json::from_str(r#"[["scale", 2, 2, 2], ["translate", 1, 0, 0]]"#)?;
+        assert_eq!(
+            spec.to_matrix()?,
+            (translation(1, 0, 0) * scaling(2, 2, 2))?
+        );
+
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn compact_list_form_round_trips() -> Result<()> {
+        let spec = TransformSpec::Compose(vec![
+            TransformSpec::RotateY(0.5),
+            TransformSpec::Shear(1.0, 0.0, 0.0, 0.0, 0.0, 1.0),
+        ]);
+
+        let json = serde_json::to_string(&spec)?;
+        assert_eq!(
+            json,
+            r#"[["rotate-y",0.5],["shear",1.0,0.0,0.0,0.0,0.0,1.0]]"#
+        );
+        assert_eq!(serde_json::from_str::<TransformSpec>(&json)?, spec);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn malformed_specs_fail_to_deserialize() {
+        assert!(serde_json::from_str::<TransformSpec>(r#"["scale", 2, 2]"#).is_err());
+        assert!(serde_json::from_str::<TransformSpec>(r#"["squash", 2, 2, 2]"#).is_err());
+        assert!(serde_json::from_str::<TransformSpec>(r#"[1, 2, 3]"#).is_err());
+    }
+}
@@ -1,4 +1,7 @@
-use crate::{matrix::Matrix, spatial::Tuple};
+use crate::{
+    matrix::{mul_4x4, Matrix},
+    spatial::{Point, Tuple, Vector},
+};
 
 /// Gets a 4x4 transformation matrix that can be used to translate tuples in 3D space
 ///
@@ -91,9 +94,32 @@ pub fn shearing(
     ])
 }
 
+/// Gets a view transform to for the eye vector based on the provided
+/// from and to points and up vector for the world
+pub fn view_transform(from: &Point, to: &Point, up: &Vector) -> Matrix<4, 4> {
+    orientation(from.as_tuple(), to.as_tuple(), up.as_tuple())
+}
+
 /// Gets a view transform to for the eye vector based on the provided
 /// from, to, and up Tuples for the world
-pub fn view_transform(from: &Tuple, to: &Tuple, up: &Tuple) -> Matrix<4, 4> {
+///
+/// `from` and `to` must be points and `up` must be a vector. Debug
+/// builds panic otherwise, since crossing a point with the forward
+/// vector silently produces a wrong orientation.
+#[deprecated(note = "use `view_transform`, which takes a typed `Point` and `Vector`")]
+pub fn view_transform_tuples(from: &Tuple, to: &Tuple, up: &Tuple) -> Matrix<4, 4> {
+    debug_assert!(
+        from.is_a_point() && to.is_a_point(),
+        "the view transform needs to look from a point to a point"
+    );
+    debug_assert!(up.is_a_vector(), "the up direction must be a vector");
+
+    orientation(from, to, up)
+}
+
+/// The view transform looking from `from` to `to`, with `up` roughly
+/// pointing up
+fn orientation(from: &Tuple, to: &Tuple, up: &Tuple) -> Matrix<4, 4> {
     let forward = (to - from).normalize();
     let upn = up.normalize();
     let left = forward.cross(&upn);
@@ -108,23 +134,19 @@ pub fn view_transform(from: &Tuple, to: &Tuple, up: &Tuple) -> Matrix<4, 4> {
 
     let translation_transform = translation(-from.get_x(), -from.get_y(), -from.get_z());
 
-    let view_transform_result = &orientation * &translation_transform;
-
-    // Just returning a identity matrix if multiplication went wrong (it _probably_ won't)
-    match view_transform_result {
-        Ok(vt) => vt,
-        Err(_) => Matrix::<4, 4>::identity(),
-    }
+    mul_4x4(&orientation, &translation_transform)
 }
 
 #[cfg(test)]
+// the tests spell out the borrowed forms of the operators on purpose
+#[allow(clippy::op_ref)]
 mod tests {
     use std::f64::consts::PI;
 
     use super::{rotation_x, rotation_y, rotation_z, scaling, shearing, translation};
     use crate::matrix::transformations::view_transform;
     use crate::matrix::{inverse_4x4, Matrix};
-    use crate::spatial::Tuple;
+    use crate::spatial::{Point, Tuple, Vector};
     use anyhow::Result;
 
     #[test]
@@ -292,36 +314,36 @@ mod tests {
 
     #[test]
     fn default_orientation_view_transform() {
-        let from = Tuple::point(0, 0, 0);
-        let to = Tuple::point(0, 0, -1);
-        let up = Tuple::vector(0, 1, 0);
+        let from = Point::new(0, 0, 0);
+        let to = Point::new(0, 0, -1);
+        let up = Vector::new(0, 1, 0);
 
         assert_eq!(view_transform(&from, &to, &up), Matrix::<4, 4>::identity());
     }
 
     #[test]
     fn view_transformation_looking_in_positive_z_direction() {
-        let from = Tuple::point(0, 0, 0);
-        let to = Tuple::point(0, 0, 1);
-        let up = Tuple::vector(0, 1, 0);
+        let from = Point::new(0, 0, 0);
+        let to = Point::new(0, 0, 1);
+        let up = Vector::new(0, 1, 0);
 
         assert_eq!(view_transform(&from, &to, &up), scaling(-1, 1, -1));
     }
 
     #[test]
     fn view_transformation_moves_the_world() {
-        let from = Tuple::point(0, 0, 8);
-        let to = Tuple::point(0, 0, 0);
-        let up = Tuple::vector(0, 1, 0);
+        let from = Point::new(0, 0, 8);
+        let to = Point::new(0, 0, 0);
+        let up = Vector::new(0, 1, 0);
 
         assert_eq!(view_transform(&from, &to, &up), translation(0, 0, -8));
     }
 
     #[test]
     fn arbitrary_view_transformation() {
-        let from = Tuple::point(1, 3, 2);
-        let to = Tuple::point(4, -2, 8);
-        let up = Tuple::vector(1, 1, 0);
+        let from = Point::new(1, 3, 2);
+        let to = Point::new(4, -2, 8);
+        let up = Vector::new(1, 1, 0);
 
         let expected = Matrix::from([
             [-0.50709, 0.50709, 0.67612, -2.36643],
@@ -334,4 +356,31 @@ mod tests {
 
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn view_transformation_looks_down_negative_z_at_the_target() {
+        // the camera setup used by the chapter 7 scene in main.rs
+        let from = Point::new(0, 1.5, -5);
+        let to = Point::new(0, 1, 0);
+        let up = Vector::new(0, 1, 0);
+
+        let t = view_transform(&from, &to, &up);
+        let distance = (to - from).magnitude();
+
+        assert_eq!(t * Tuple::from(from), Tuple::point(0, 0, 0));
+        assert_eq!(t * Tuple::from(to), Tuple::point(0, 0, -distance));
+        // the world's up direction stays pointing up in view space
+        assert!((t * Tuple::from(up)).get_y() > 0.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "up direction must be a vector")]
+    #[allow(deprecated)]
+    fn view_transformation_rejects_a_point_for_up() {
+        let _ = super::view_transform_tuples(
+            &Tuple::point(0, 1.5, -5),
+            &Tuple::point(0, 1, 0),
+            &Tuple::point(0, 1, 0),
+        );
+    }
 }
@@ -0,0 +1,81 @@
+//! Re-exports the types, transforms and traits needed to build and
+//! render a scene, so a single glob import is enough:
+//!
+//! ```
+//! use raytracer::prelude::*;
+//! use std::f64::consts::PI;
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let mut world = World::empty();
+//! world.set_light(Some(PointLight::new(
+//!     Point::new(-10, 10, -10),
+//!     Color::new(1, 1, 1),
+//! )));
+//!
+//! let mut material = Material::default();
+//! material.set_color(Color::new(1, 0.2, 1));
+//! world.add_object(Sphere::new(translation(0, 1, 0), material)?);
+//!
+//! let mut camera = Camera::new(20, 10, PI / 3.0)?;
+//! camera.set_transform(view_transform(
+//!     &Point::new(0, 1.5, -5),
+//!     &Point::new(0, 1, 0),
+//!     &Vector::new(0, 1, 0),
+//! ));
+//!
+//! let image: Canvas = camera.render(&world)?;
+//! assert_ne!(*image.pixel_at(10, 5)?, Color::black());
+//!
+//! let ray = Ray::new(Point::new(0, 1, -5), Vector::new(0, 0, 1));
+//! let xs = world[0].intersect(&ray)?;
+//! let first: &Intersection = &xs[0];
+//! let normal = first.object.normal_at(ray.position(first.t))?;
+//! assert_eq!(normal, Tuple::vector(0, 0, -1));
+//! # Ok(())
+//! # }
+//! ```
+
+pub use crate::{
+    camera::Camera,
+    canvas::Canvas,
+    color::Color,
+    intersections::{Intersection, Intersections, Ray},
+    lights::{Material, PointLight},
+    matrix::{
+        rotation_x, rotation_y, rotation_z, scaling, shearing, translation, view_transform, Matrix,
+    },
+    shapes::{Intersect, Object, Sphere, SurfaceNormal},
+    spatial::{Point, Tuple, Vector},
+    world::World,
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+
+    /// The core of the library has to build and render without any of
+    /// the optional features, as checked by running the tests with
+    /// `--no-default-features`
+    #[test]
+    fn core_types_need_no_features() -> Result<()> {
+        let mut world = World::empty();
+        world.set_light(Some(PointLight::new(
+            Point::new(-10, 10, -10),
+            Color::new(1, 1, 1),
+        )));
+        world.add_object(Object::sphere());
+
+        let mut camera = Camera::new(4, 4, 1.0)?;
+        camera.set_transform(view_transform(
+            &Point::new(0, 0, -5),
+            &Point::new(0, 0, 0),
+            &Vector::new(0, 1, 0),
+        ));
+
+        let image = camera.render(&world)?;
+        assert_ne!(*image.pixel_at(2, 2)?, Color::black());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,185 @@
+use std::{
+    io::Write,
+    time::{Duration, Instant},
+};
+
+/// The widest line [format_progress] will produce, so the progress
+/// bar fits on a standard 80 column terminal without wrapping
+pub const MAX_LINE_WIDTH: usize = 79;
+
+/// Number of characters used for the bar itself
+const BAR_WIDTH: usize = 30;
+
+/// Formats a single line describing the progress of a render that has
+/// finished `done` out of `total` rows after running for `elapsed`.
+///
+/// ```
+/// use raytracer::progress::format_progress;
+/// use std::time::Duration;
+///
+/// let line = format_progress(50, 100, Duration::from_secs(10));
+/// assert_eq!(
+///     line,
+///     "[###############...............]  50.0%  5.0 rows/s  ETA 10s"
+/// );
+/// ```
+pub fn format_progress(done: usize, total: usize, elapsed: Duration) -> String {
+    let done = done.min(total);
+    let fraction = if total == 0 {
+        1.0
+    } else {
+        done as f64 / total as f64
+    };
+
+    let filled = (fraction * BAR_WIDTH as f64).floor() as usize;
+    let bar = format!("{}{}", "#".repeat(filled), ".".repeat(BAR_WIDTH - filled));
+
+    let seconds = elapsed.as_secs_f64();
+    let rate = if seconds > 0.0 {
+        done as f64 / seconds
+    } else {
+        0.0
+    };
+
+    let eta = if done == total {
+        format_duration(0)
+    } else if rate > 0.0 {
+        format_duration(((total - done) as f64 / rate).ceil() as u64)
+    } else {
+        "--".to_string()
+    };
+
+    let mut line = format!(
+        "[{}] {:5.1}%  {:.1} rows/s  ETA {}",
+        bar,
+        fraction * 100.0,
+        rate,
+        eta
+    );
+    line.truncate(MAX_LINE_WIDTH);
+    line
+}
+
+/// Formats a number of seconds like `1h02m03s`, `2m05s` or `12s`
+fn format_duration(seconds: u64) -> String {
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+
+    if hours > 0 {
+        format!("{}h{:02}m{:02}s", hours, minutes, seconds)
+    } else if minutes > 0 {
+        format!("{}m{:02}s", minutes, seconds)
+    } else {
+        format!("{}s", seconds)
+    }
+}
+
+#[derive(Debug)]
+/// Draws a progress bar on stderr while a render is running, redrawing
+/// it at most a few times per second
+pub struct ProgressBar {
+    start: Instant,
+    last_draw: Option<Instant>,
+}
+
+impl ProgressBar {
+    /// The shortest time between two redraws of the bar
+    const REDRAW_INTERVAL: Duration = Duration::from_millis(250);
+
+    /// Create a new progress bar, starting the clock for the ETA
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            last_draw: None,
+        }
+    }
+
+    /// Updates the bar to show `done` out of `total` rows. The final
+    /// update is always drawn and ends the line.
+    pub fn update(&mut self, done: usize, total: usize) {
+        let now = Instant::now();
+        let finished = done >= total;
+
+        let due = self
+            .last_draw
+            .is_none_or(|last| now - last >= Self::REDRAW_INTERVAL);
+
+        if !due && !finished {
+            return;
+        }
+
+        self.last_draw = Some(now);
+
+        let line = format_progress(done, total, now - self.start);
+        let mut stderr = std::io::stderr();
+        let _ = write!(stderr, "\r{:<width$}", line, width = MAX_LINE_WIDTH);
+        if finished {
+            let _ = writeln!(stderr);
+        }
+        let _ = stderr.flush();
+    }
+}
+
+impl Default for ProgressBar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_duration, format_progress, MAX_LINE_WIDTH};
+    use std::time::Duration;
+
+    #[test]
+    fn formatting_several_progress_points() {
+        assert_eq!(
+            format_progress(0, 200, Duration::ZERO),
+            "[..............................]   0.0%  0.0 rows/s  ETA --"
+        );
+        assert_eq!(
+            format_progress(50, 200, Duration::from_secs(5)),
+            "[#######.......................]  25.0%  10.0 rows/s  ETA 15s"
+        );
+        assert_eq!(
+            format_progress(150, 200, Duration::from_secs(300)),
+            "[######################........]  75.0%  0.5 rows/s  ETA 1m40s"
+        );
+        assert_eq!(
+            format_progress(200, 200, Duration::from_secs(400)),
+            "[##############################] 100.0%  0.5 rows/s  ETA 0s"
+        );
+    }
+
+    #[test]
+    fn progress_past_the_end_is_capped() {
+        assert_eq!(
+            format_progress(300, 200, Duration::from_secs(400)),
+            format_progress(200, 200, Duration::from_secs(400))
+        );
+        assert!(format_progress(0, 0, Duration::ZERO).contains("100.0%"));
+    }
+
+    #[test]
+    fn the_line_never_exceeds_the_terminal_width() {
+        let cases = [
+            (0, 1, Duration::ZERO),
+            (1, usize::MAX, Duration::from_nanos(1)),
+            (usize::MAX / 2, usize::MAX, Duration::from_nanos(1)),
+            (1, 1_000_000_000, Duration::from_secs(1)),
+            (999, 1000, Duration::from_secs(u64::MAX / 2)),
+        ];
+
+        for (done, total, elapsed) in cases {
+            let line = format_progress(done, total, elapsed);
+            assert!(line.len() <= MAX_LINE_WIDTH, "{:?} is too long", line);
+        }
+    }
+
+    #[test]
+    fn formatting_durations() {
+        assert_eq!(format_duration(0), "0s");
+        assert_eq!(format_duration(59), "59s");
+        assert_eq!(format_duration(125), "2m05s");
+        assert_eq!(format_duration(3723), "1h02m03s");
+    }
+}
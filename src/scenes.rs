@@ -0,0 +1,336 @@
+use crate::{
+    camera::Camera,
+    canvas::{plot_path, Canvas, PlotBounds},
+    color::Color,
+    intersections::{hit, Ray},
+    lights::{lighting, LightingContext, Material, PointLight},
+    matrix::{rotation_x, rotation_y, rotation_z, scaling, translation, view_transform},
+    shapes::Sphere,
+    spatial::{Point, Tuple, Vector},
+    tick::{Environment, Projectile},
+    world::World,
+};
+use anyhow::Result;
+use std::f64::consts::PI;
+
+/// A function that builds a scene: the world to render, and the
+/// camera to render it with
+pub type SceneFn = fn() -> Result<(World, Camera)>;
+
+/// Returns every known scene along with the name it is looked up by.
+/// Adding a scene here makes it available to the binary and to anything
+/// else that looks scenes up with [by_name].
+pub fn registry() -> Vec<(&'static str, SceneFn)> {
+    vec![
+        ("chapter7", chapter7),
+        ("default-world", default_world),
+        ("sphere-grid", sphere_grid),
+    ]
+}
+
+/// Finds the scene registered under `name`, ignoring case
+///
+/// ```
+/// use raytracer::scenes::by_name;
+///
+/// let scene = by_name("Chapter7").expect("the scene is registered");
+/// let (world, camera) = scene().unwrap();
+///
+/// assert_eq!(world.objects.len(), 6);
+/// assert_eq!(camera.get_hsize(), 500);
+/// ```
+pub fn by_name(name: &str) -> Option<SceneFn> {
+    registry()
+        .into_iter()
+        .find(|(scene_name, _)| scene_name.eq_ignore_ascii_case(name))
+        .map(|(_, scene)| scene)
+}
+
+/// The scene from the end of chapter 7: three spheres in a room made
+/// of flattened spheres
+pub fn chapter7() -> Result<(World, Camera)> {
+    let mut floor_material = Material::default();
+    floor_material.set_color(Color::new(1, 0.9, 0.9));
+    floor_material.set_specular(0.0);
+    let floor = Sphere::new(scaling(10, 0.01, 10), floor_material)?;
+
+    let mut left_wall_transform = (translation(0, 0, 5) * rotation_y(-PI / 4.0))?;
+    left_wall_transform = (left_wall_transform * rotation_x(PI / 2.0))?;
+    left_wall_transform = (left_wall_transform * scaling(10, 0.01, 10))?;
+    let left_wall = Sphere::new(left_wall_transform, floor_material)?;
+
+    let mut right_wall_transform = (translation(0, 0, 5) * rotation_y(PI / 4.0))?;
+    right_wall_transform = (right_wall_transform * rotation_x(PI / 2.0))?;
+    right_wall_transform = (right_wall_transform * scaling(10, 0.01, 10))?;
+    let right_wall = Sphere::new(right_wall_transform, floor_material)?;
+
+    let mut middle_material = Material::default();
+    middle_material.set_color(Color::new(0.1, 1, 0.5));
+    middle_material.set_diffuse(0.7);
+    middle_material.set_specular(0.3);
+    let middle = Sphere::new(translation(-0.5, 1, 0.5), middle_material)?;
+
+    let mut right_material = Material::default();
+    right_material.set_color(Color::new(0.5, 1, 0.1));
+    right_material.set_diffuse(0.7);
+    right_material.set_specular(0.3);
+    let right = Sphere::new(
+        (translation(1.5, 0.5, -0.5) * scaling(0.5, 0.5, 0.5))?,
+        right_material,
+    )?;
+
+    let mut left_material = Material::default();
+    left_material.set_color(Color::new(1, 0.8, 0.1));
+    left_material.set_diffuse(0.7);
+    left_material.set_specular(0.3);
+    let left = Sphere::new(
+        (translation(-1.5, 0.33, -0.75) * scaling(0.33, 0.33, 0.33))?,
+        left_material,
+    )?;
+
+    let light_source = PointLight::new(Point::new(-10, 10, -10), Color::new(1, 1, 1));
+
+    let mut world = World::empty();
+    world.set_light(Some(light_source));
+    world.add_object(floor);
+    world.add_object(left_wall);
+    world.add_object(right_wall);
+    world.add_object(middle);
+    world.add_object(left);
+    world.add_object(right);
+
+    let mut camera = Camera::new(500, 500, PI / 3.0)?;
+    camera.set_transform(view_transform(
+        &Point::new(0, 1.5, -5),
+        &Point::new(0, 1, 0),
+        &Vector::new(0, 1, 0),
+    ));
+
+    Ok((world, camera))
+}
+
+/// The default world used throughout the book's tests, seen from
+/// straight in front
+pub fn default_world() -> Result<(World, Camera)> {
+    let mut camera = Camera::new(500, 500, PI / 2.0)?;
+    camera.set_transform(view_transform(
+        &Point::new(0, 0, -5),
+        &Point::origin(),
+        &Vector::new(0, 1, 0),
+    ));
+
+    Ok((World::default(), camera))
+}
+
+/// A 10x10 grid of small spheres in rainbow colors, seen at an angle.
+/// With a hundred objects it is much more expensive to render than
+/// the other scenes.
+pub fn sphere_grid() -> Result<(World, Camera)> {
+    let mut world = World::empty();
+    world.set_light(Some(PointLight::new(
+        Point::new(-10, 10, -10),
+        Color::new(1, 1, 1),
+    )));
+
+    for row in 0..10 {
+        for column in 0..10 {
+            let mut material = Material::default();
+            material.set_color(Color::new(column as f64 / 9.0, 0.5, row as f64 / 9.0));
+            material.set_specular(0.3);
+
+            let transform =
+                (translation(column as f64 - 4.5, 0, row as f64 - 4.5) * scaling(0.4, 0.4, 0.4))?;
+            world.add_object(Sphere::new(transform, material)?);
+        }
+    }
+
+    let mut camera = Camera::new(500, 500, PI / 3.0)?;
+    camera.set_transform(view_transform(
+        &Point::new(0, 7, -10),
+        &Point::origin(),
+        &Vector::new(0, 1, 0),
+    ));
+
+    Ok((world, camera))
+}
+
+/// The chapter 2 example: the trajectory of a projectile launched
+/// across a 900x550 unit field, plotted onto a `width` by `height`
+/// canvas
+pub fn projectile(width: usize, height: usize) -> Result<Canvas> {
+    let p = Tuple::point(0, 1, 0);
+    let v = Tuple::vector(1, 1.8, 0).normalize() * 11.25;
+    let projectile = Projectile::new(p, v)?;
+    let environment = Environment::default_earth();
+
+    let mut canvas = Canvas::new(width, height);
+    let bounds = PlotBounds::new(0.0, 900.0, 0.0, 550.0)?;
+
+    let path = projectile
+        .trajectory(&environment)
+        .map(|state| (state.position.get_x(), state.position.get_y()));
+
+    plot_path(&mut canvas, path, &bounds, Color::red())?;
+
+    Ok(canvas)
+}
+
+/// The chapter 4 example: the twelve hour marks of an analog clock,
+/// placed by rotating a point around the center of a `size` by `size`
+/// canvas
+pub fn analog_clock(size: usize) -> Result<Canvas> {
+    let mut canvas = Canvas::new(size, size);
+    let center = size as f64 / 2.0;
+    let twelve = Tuple::point(0, size as f64 * 0.4, 0);
+
+    for hour in 0..12 {
+        let transform = (translation(center, center, 0) * rotation_z(hour as f64 * PI / 6.0))?;
+        let mark = transform * twelve;
+
+        canvas.write_pixel_clipped(
+            mark.get_x().round() as isize,
+            (size as f64 - mark.get_y()).round() as isize,
+            Color::new(1, 1, 1),
+        );
+    }
+
+    Ok(canvas)
+}
+
+/// The chapter 5 example: the silhouette of a squashed and rotated
+/// sphere, found by casting a ray through each pixel of a wall behind
+/// it
+pub fn sphere_silhouette(canvas_pixels: usize) -> Result<Canvas> {
+    let mut s = Sphere::default();
+    s.set_transform((rotation_z(PI / 4.0) * scaling(0.5, 1, 1))?)?;
+
+    cast_at_wall(canvas_pixels, |ray| {
+        Ok(hit(&s.intersect(ray)?).map(|_| Color::red()))
+    })
+}
+
+/// The chapter 6 example: a white sphere shaded with the Phong
+/// reflection model under a red light
+pub fn phong_sphere(canvas_pixels: usize) -> Result<Canvas> {
+    let mut s = Sphere::default();
+    s.material.set_color(Color::new(1, 1, 1));
+
+    let light = PointLight::new(Point::new(-10, 10, -10), Color::new(1, 0, 0));
+
+    cast_at_wall(canvas_pixels, |ray| {
+        let xs = s.intersect(ray)?;
+        let Some(h) = hit(&xs) else {
+            return Ok(None);
+        };
+
+        let point = ray.position(h.t);
+        Ok(Some(lighting(&LightingContext {
+            material: &s.material,
+            light: &light,
+            point,
+            eyev: -ray.direction,
+            normalv: s.normal_at(point)?,
+            light_intensity: 1.0,
+        })))
+    })
+}
+
+/// Casts a ray from in front of the origin through every pixel of a
+/// 7x7 wall behind it, as the chapter 5 and 6 examples do before there
+/// is a camera. `shade` picks the color of each pixel, leaving it black
+/// for `None`.
+fn cast_at_wall(
+    canvas_pixels: usize,
+    mut shade: impl FnMut(&Ray) -> Result<Option<Color>>,
+) -> Result<Canvas> {
+    let ray_origin = Point::new(0, 0, -5);
+    let wall_z = 10.0;
+    let wall_size = 7.0;
+    let half = wall_size / 2.0;
+    let pixel_size = wall_size / canvas_pixels as f64;
+
+    let mut canvas = Canvas::new(canvas_pixels, canvas_pixels);
+
+    for y in 0..canvas_pixels {
+        let world_y = half - (y as f64 * pixel_size);
+
+        for x in 0..canvas_pixels {
+            let world_x = -half + (x as f64 * pixel_size);
+
+            let position_on_wall = Point::new(world_x, world_y, wall_z);
+            let ray = Ray::new(ray_origin, (position_on_wall - ray_origin).normalize());
+
+            if let Some(color) = shade(&ray)? {
+                canvas.write_pixel(x, y, color)?;
+            }
+        }
+    }
+
+    Ok(canvas)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{analog_clock, by_name, phong_sphere, projectile, registry, sphere_silhouette};
+    use crate::{camera::Camera, canvas::Canvas, color::Color};
+    use anyhow::Result;
+
+    fn lit_pixels(canvas: &Canvas) -> usize {
+        canvas
+            .enumerate_pixels()
+            .filter(|(_, _, color)| **color != Color::black())
+            .count()
+    }
+
+    #[test]
+    fn every_scene_constructs_and_renders() -> Result<()> {
+        for (name, scene) in registry() {
+            let (world, camera) = scene()?;
+            assert!(!world.objects.is_empty(), "{} has no objects", name);
+
+            let mut small = Camera::new(5, 5, camera.get_field_of_view())?;
+            small.set_transform(*camera.get_transform());
+
+            let image = small.render(&world)?;
+            assert_eq!((image.width, image.height), (5, 5));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn scene_names_are_unique() {
+        let names = registry();
+        for (i, (name, _)) in names.iter().enumerate() {
+            assert!(names[i + 1..]
+                .iter()
+                .all(|(other, _)| !other.eq_ignore_ascii_case(name)));
+        }
+    }
+
+    #[test]
+    fn lookups_ignore_case() {
+        assert!(by_name("chapter7").is_some());
+        assert!(by_name("CHAPTER7").is_some());
+        assert!(by_name("Default-World").is_some());
+        assert!(by_name("chapter 7").is_none());
+        assert!(by_name("").is_none());
+    }
+
+    #[test]
+    fn chapter_examples_draw_at_a_small_size() -> Result<()> {
+        let trajectory = projectile(45, 28)?;
+        assert_eq!((trajectory.width, trajectory.height), (45, 28));
+        assert!(lit_pixels(&trajectory) > 10);
+
+        assert_eq!(lit_pixels(&analog_clock(25)?), 12);
+
+        // the sphere is in the middle of the wall, and misses its corners
+        for canvas in [sphere_silhouette(10)?, phong_sphere(10)?] {
+            assert_ne!(*canvas.pixel_at(5, 5)?, Color::black());
+            assert_eq!(*canvas.pixel_at(0, 0)?, Color::black());
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,183 @@
+use crate::{
+    color::Color,
+    intersections::{Intersections, Ray},
+    lights::Material,
+    spatial::Tuple,
+};
+use anyhow::Result;
+use std::fmt;
+use uuid::Uuid;
+
+mod sphere;
+
+pub use sphere::Sphere;
+#[cfg(test)]
+pub(crate) use sphere::SPHERE_INTERSECT_CALLS;
+
+/// Generates the id of a new shape.
+///
+/// Random ids need the operating system's random number generator,
+/// which isn't there on `wasm32-unknown-unknown`. With the `wasm`
+/// feature, ids come from a counter instead, which is unique within
+/// the running program.
+pub(crate) fn new_id() -> Uuid {
+    #[cfg(feature = "wasm")]
+    {
+        use std::sync::atomic::{AtomicU64, Ordering};
+
+        static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+        Uuid::from_u128(NEXT_ID.fetch_add(1, Ordering::Relaxed) as u128)
+    }
+
+    #[cfg(not(feature = "wasm"))]
+    Uuid::new_v4()
+}
+
+/// Trait that can be used to implement a way to get
+/// surface normals for any objects that might implement
+/// this trait
+pub trait SurfaceNormal {
+    /// Returns a normalized surface normal vector for
+    /// any object that implements this method
+    fn normal_at(&self, point: Tuple) -> Result<Tuple>;
+}
+
+/// Trait that can be used to implement an intersection
+/// calculation for any objects that implement it
+pub trait Intersect {
+    /// Calculates the points of intersection for given [Ray] with
+    /// the object implementing this trait.
+    ///
+    /// If there are no points of intersection, an empty collection will
+    /// be returned. If there is a tangential intersection, the same
+    /// point will be returned twice.
+    fn intersect(&self, ray: &Ray) -> Result<Intersections> {
+        let mut xs = Intersections::new();
+        self.intersect_into(ray, &mut xs)?;
+        Ok(xs)
+    }
+
+    /// Same as [Intersect::intersect], but adds the intersections to
+    /// an existing collection. Shapes don't allocate anything on their
+    /// own, so reusing a collection with enough room avoids heap
+    /// allocations entirely.
+    fn intersect_into(&self, ray: &Ray, xs: &mut Intersections) -> Result<()>;
+}
+
+#[derive(Clone, Copy, PartialEq, PartialOrd)]
+/// Stores all the variants of the Object type
+pub enum Object {
+    Sphere(Sphere),
+}
+
+impl From<Sphere> for Object {
+    fn from(sphere: Sphere) -> Self {
+        Object::Sphere(sphere)
+    }
+}
+
+impl Object {
+    /// A default unit sphere wrapped as an Object
+    ///
+    /// ```
+    /// use raytracer::shapes::{Object, Sphere};
+    ///
+    /// assert_eq!(Object::sphere().kind(), "Sphere");
+    /// ```
+    pub fn sphere() -> Self {
+        Sphere::default().into()
+    }
+
+    /// Get the material of the Object
+    pub fn get_material(&self) -> &Material {
+        match self {
+            Object::Sphere(ref sphere) => &sphere.material,
+        }
+    }
+
+    /// Get the material of the Object for changing it in place
+    pub fn material_mut(&mut self) -> &mut Material {
+        match self {
+            Object::Sphere(ref mut sphere) => &mut sphere.material,
+        }
+    }
+
+    /// A sphere in world space that contains the whole object, as its
+    /// center and radius. Unbounded objects have none.
+    pub fn bounding_sphere(&self) -> Option<(Tuple, f64)> {
+        match self {
+            Object::Sphere(ref sphere) => Some(sphere.bounding_sphere()),
+        }
+    }
+
+    /// Get the unique id of the Object
+    pub fn id(&self) -> Uuid {
+        match self {
+            Object::Sphere(ref sphere) => sphere.id(),
+        }
+    }
+
+    /// The name of the kind of shape this Object is, e.g. `Sphere`
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Object::Sphere(_) => "Sphere",
+        }
+    }
+
+    /// Set the ambeint value for the material of this Object
+    pub fn set_ambient(&mut self, ambient: f64) {
+        self.material_mut().set_ambient(ambient);
+    }
+}
+
+/// Summarizes an [Object] as its kind followed by the start of its
+/// id, e.g. `Sphere(67e55044)`
+impl fmt::Display for Object {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let id = self.id().simple().to_string();
+        write!(f, "{}({})", self.kind(), &id[..8])
+    }
+}
+
+/// Describes an [Object] on a single line: its kind and short id, where
+/// it is, and the highlights of its material, e.g.
+/// `Sphere(67e55044) at P(0.00000, 1.00000, 0.00000) color (1, 0, 0)`.
+/// Full matrices make for hundreds of lines in a printed world.
+impl fmt::Debug for Object {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self)?;
+
+        if let Some((center, _)) = self.bounding_sphere() {
+            write!(f, " at {}", center)?;
+        }
+
+        let material = self.get_material();
+        let rgb = |c: Color| format!("({}, {}, {})", c.red, c.green, c.blue);
+        write!(f, " color {}", rgb(material.get_color()))?;
+
+        if material.get_emissive() != Color::black() {
+            write!(f, " emissive {}", rgb(material.get_emissive()))?;
+        }
+        if let Some(volume) = material.get_volume() {
+            write!(f, " volume density {}", volume.get_density())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl SurfaceNormal for Object {
+    fn normal_at(&self, point: Tuple) -> Result<Tuple> {
+        match self {
+            Object::Sphere(ref sphere) => sphere.normal_at(point),
+        }
+    }
+}
+
+impl Intersect for Object {
+    fn intersect_into(&self, ray: &Ray, xs: &mut Intersections) -> Result<()> {
+        match self {
+            Object::Sphere(ref sphere) => sphere.intersect_into(ray, xs),
+        }
+    }
+}
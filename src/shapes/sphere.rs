@@ -0,0 +1,513 @@
+use super::Object;
+use crate::{
+    error::RayTracerError,
+    intersections::{Intersection, Intersections, Ray},
+    lights::Material,
+    matrix::{inverse_3x3, Matrix},
+    spatial::Tuple,
+};
+use anyhow::Result;
+use uuid::Uuid;
+
+#[cfg(test)]
+thread_local! {
+    /// Counts calls to [Sphere::intersect_into] on the current thread, so
+    /// tests can check how many ray-sphere tests a render performs
+    pub(crate) static SPHERE_INTERSECT_CALLS: std::cell::Cell<usize> =
+        const { std::cell::Cell::new(0) };
+}
+
+#[derive(Debug, Clone, Copy, PartialOrd)]
+/// Representation of a unit sphere centred at (0,0,0)
+pub struct Sphere {
+    /// Added this field so that no two invocations of the
+    /// default / new will return the same Sphere. We want
+    /// to maintain uniqueness with each creation.
+    _id: Uuid,
+    transform_matrix: Matrix<4, 4>,
+    /// Cached inverse-transpose of the upper-left 3x3 of the transform,
+    /// used to bring normals into world space. This is `None` when the
+    /// transform is not invertible.
+    normal_matrix: Option<Matrix<3, 3>>,
+    /// Cached inverse of the transform, used to bring rays and points
+    /// into object space. This is `None` when the transform is not
+    /// invertible.
+    inverse_transform: Option<Matrix<4, 4>>,
+    pub material: Material,
+}
+
+impl Sphere {
+    /// Create a new [Sphere]. Fails with
+    /// [RayTracerError::NonInvertibleTransform] if `transform` has no
+    /// inverse, since such a sphere could never be rendered.
+    pub fn new(transform: Matrix<4, 4>, material: Material) -> Result<Self> {
+        let mut sphere = Self {
+            material,
+            ..Self::default()
+        };
+        sphere.set_transform(transform)?;
+        Ok(sphere)
+    }
+
+    /// Create a new [Sphere] without checking that its transform can be
+    /// inverted, to test how rendering copes with a broken object
+    #[cfg(test)]
+    pub(crate) fn with_unchecked_transform(transform: Matrix<4, 4>, material: Material) -> Self {
+        Self {
+            _id: super::new_id(),
+            transform_matrix: transform,
+            normal_matrix: Self::normal_matrix_for(&transform),
+            inverse_transform: transform.try_inverse(),
+            material,
+        }
+    }
+
+    /// Get the unique id of the sphere
+    pub fn id(&self) -> Uuid {
+        self._id
+    }
+
+    /// Computes the normal matrix for a transform: the inverse-transpose
+    /// of its upper-left 3x3. Since it has no translation row or column,
+    /// transforming a normal with it can never produce a non-zero w.
+    fn normal_matrix_for(transform: &Matrix<4, 4>) -> Option<Matrix<3, 3>> {
+        let linear_part = transform.submatrix::<3, 3>(3, 3).ok()?;
+        Some(inverse_3x3(&linear_part).ok()?.transpose())
+    }
+
+    /// Calculates the points of intersection for given [Ray] with
+    /// the Sphere.
+    ///
+    /// If there are no points of intersection, an empty collection will
+    /// be returned. If there is a tangential intersection, the same
+    /// point will be returned twice.
+    pub fn intersect(&self, ray: &Ray) -> Result<Intersections> {
+        let mut xs = Intersections::new();
+        self.intersect_into(ray, &mut xs)?;
+        Ok(xs)
+    }
+
+    /// Same as [Sphere::intersect], but adds the intersections to an
+    /// existing collection, which avoids allocating a new one
+    pub fn intersect_into(&self, ray: &Ray, xs: &mut Intersections) -> Result<()> {
+        #[cfg(test)]
+        SPHERE_INTERSECT_CALLS.with(|calls| calls.set(calls.get() + 1));
+
+        // First we transform the ray with the inverse of the object's transformation matrix
+        // so we can move/deform the ray instead of moving/deforming the object.
+        //
+        // This enables us to keep the calculation simple since we can assume our unit object
+        // centered at the origin (0, 0, 0), and the ray is transformed in relation to it.
+        let inverse = self
+            .inverse_transform
+            .ok_or(RayTracerError::NonInvertibleMatrix)?;
+        let transformed_ray = ray.transform(&inverse);
+
+        let sphere_to_ray = transformed_ray.origin - Tuple::origin();
+        let a = transformed_ray.direction.dot(&transformed_ray.direction);
+        let b = 2.0 * transformed_ray.direction.dot(&sphere_to_ray);
+        let c = sphere_to_ray.dot(&sphere_to_ray) - 1.0;
+        let discriminant = b * b - (4.0 * a * c);
+
+        if discriminant >= 0.0 {
+            let t1 = (-b - discriminant.sqrt()) / (2.0 * a);
+            let t2 = (-b + discriminant.sqrt()) / (2.0 * a);
+
+            xs.push(Intersection::new(t1, Object::Sphere(*self)));
+            xs.push(Intersection::new(t2, Object::Sphere(*self)));
+        }
+
+        Ok(())
+    }
+
+    pub fn normal_at(&self, point: Tuple) -> Result<Tuple> {
+        let normal_matrix = self
+            .normal_matrix
+            .ok_or(RayTracerError::NonInvertibleMatrix)?;
+
+        let inverse = self
+            .inverse_transform
+            .ok_or(RayTracerError::NonInvertibleMatrix)?;
+
+        let object_point = inverse * point;
+        let object_normal = object_point - Tuple::origin();
+        let world_normal = normal_matrix * object_normal;
+        Ok(world_normal.normalize())
+    }
+
+    /// A sphere in world space that contains this sphere, as its
+    /// center and radius. The radius is an upper bound on how much
+    /// the transform stretches the unit sphere, which is exact for
+    /// uniform scaling.
+    pub fn bounding_sphere(&self) -> (Tuple, f64) {
+        let m = &self.transform_matrix;
+        let center = Tuple::point(m[0][3], m[1][3], m[2][3]);
+
+        // the largest singular value of the linear part is at most the
+        // geometric mean of its largest row and column sums
+        let row_sum = (0..3)
+            .map(|row| (0..3).map(|col| m[row][col].abs()).sum::<f64>())
+            .fold(0.0, f64::max);
+        let col_sum = (0..3)
+            .map(|col| (0..3).map(|row| m[row][col].abs()).sum::<f64>())
+            .fold(0.0, f64::max);
+
+        (center, (row_sum * col_sum).sqrt())
+    }
+
+    /// Modify the transform of the sphere. Fails with
+    /// [RayTracerError::NonInvertibleTransform], leaving the sphere
+    /// unchanged, if `t` has no inverse.
+    pub fn set_transform(&mut self, t: Matrix<4, 4>) -> Result<()> {
+        let normal_matrix = Self::normal_matrix_for(&t);
+        let inverse_transform = t.try_inverse();
+        if normal_matrix.is_none() || inverse_transform.is_none() {
+            return Err(RayTracerError::NonInvertibleTransform {
+                object: Object::Sphere(*self).to_string(),
+            }
+            .into());
+        }
+
+        self.transform_matrix = t;
+        self.normal_matrix = normal_matrix;
+        self.inverse_transform = inverse_transform;
+        Ok(())
+    }
+
+    /// Set the material for the sphere
+    pub fn set_material(&mut self, m: Material) {
+        self.material = m;
+    }
+}
+
+impl Default for Sphere {
+    fn default() -> Self {
+        Self {
+            _id: super::new_id(),
+            transform_matrix: Matrix::<4, 4>::identity(),
+            normal_matrix: Some(Matrix::<3, 3>::identity()),
+            inverse_transform: Some(Matrix::<4, 4>::identity()),
+            material: Material::default(),
+        }
+    }
+}
+
+impl PartialEq for Sphere {
+    fn eq(&self, other: &Self) -> bool {
+        self._id == other._id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::{FRAC_1_SQRT_2, PI, SQRT_2};
+
+    use super::Sphere;
+    use crate::error::RayTracerError;
+    use crate::{
+        color::Color,
+        intersections::{Intersections, Ray},
+        lights::Material,
+        matrix::{inverse_4x4, rotation_z, scaling, shearing, translation, Matrix},
+        shapes::Object,
+        spatial::{Point, Tuple, Vector},
+        utils::alloc_counter::count_allocations,
+    };
+    use anyhow::Result;
+
+    #[test]
+    fn create_a_default_sphere() {
+        let s = Sphere::default();
+        assert_eq!(s.transform_matrix, Matrix::<4, 4>::identity());
+    }
+
+    #[test]
+    fn changing_a_spheres_transformation() -> Result<()> {
+        let mut s = Sphere::default();
+        assert_eq!(s.transform_matrix, Matrix::<4, 4>::identity());
+
+        let t = translation(2, 3, 4);
+        s.set_transform(t)?;
+        assert_eq!(s.transform_matrix, t);
+
+        Ok(())
+    }
+
+    #[test]
+    fn ray_intersects_sphere_at_two_points() -> Result<()> {
+        let ray = Ray::new(Point::new(0, 0, -5), Vector::new(0, 0, 1));
+        let s = Sphere::default();
+
+        let xs = s.intersect(&ray)?;
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].t, 4.0);
+        assert_eq!(xs[0].object, Object::Sphere(s));
+        assert_eq!(xs[1].t, 6.0);
+        assert_eq!(xs[1].object, Object::Sphere(s));
+        Ok(())
+    }
+
+    #[test]
+    fn intersecting_into_a_collection_with_room_does_not_allocate() -> Result<()> {
+        let ray = Ray::new(Point::new(0, 0, -5), Vector::new(0, 0, 1));
+        let s = Sphere::default();
+        let mut xs = Intersections::with_capacity(4);
+
+        let (result, allocations) = count_allocations(|| {
+            s.intersect_into(&ray, &mut xs)?;
+            s.intersect_into(&ray, &mut xs)
+        });
+        result?;
+
+        assert_eq!(allocations, 0);
+        let ts: Vec<f64> = xs.iter().map(|i| i.t).collect();
+        assert_eq!(ts, vec![4.0, 4.0, 6.0, 6.0]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn bounding_sphere_contains_the_transformed_sphere() -> Result<()> {
+        let s = Sphere::new(
+            (translation(1, 2, 3) * scaling(2, 2, 2))?,
+            Material::default(),
+        )?;
+        assert_eq!(s.bounding_sphere(), (Tuple::point(1, 2, 3), 2.0));
+
+        let transform = ((translation(-4, 0, 1) * rotation_z(PI / 5.0))? * scaling(1, 3, 0.5))?;
+        let s = Sphere::new(transform, Material::default())?;
+        let (center, radius) = s.bounding_sphere();
+
+        for i in 0..100 {
+            let (theta, phi) = (i as f64 * 0.7, i as f64 * 0.31);
+            let surface = Tuple::point(
+                theta.sin() * phi.cos(),
+                theta.sin() * phi.sin(),
+                theta.cos(),
+            );
+            assert!((transform * surface - center).magnitude() <= radius);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn ray_intersects_sphere_at_tangent() -> Result<()> {
+        let ray = Ray::new(Point::new(0, 1, -5), Vector::new(0, 0, 1));
+        let s = Sphere::default();
+
+        let xs = s.intersect(&ray)?;
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].t, 5.0);
+        assert_eq!(xs[0].object, Object::Sphere(s));
+        assert_eq!(xs[1].t, 5.0);
+        assert_eq!(xs[1].object, Object::Sphere(s));
+        Ok(())
+    }
+
+    #[test]
+    fn ray_misses_a_sphere() -> Result<()> {
+        let ray = Ray::new(Point::new(0, 2, -5), Vector::new(0, 0, 1));
+        let s = Sphere::default();
+
+        let xs = s.intersect(&ray)?;
+        assert_eq!(xs.len(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn ray_originates_inside_sphere() -> Result<()> {
+        let ray = Ray::new(Point::new(0, 0, 0), Vector::new(0, 0, 1));
+        let s = Sphere::default();
+
+        let xs = s.intersect(&ray)?;
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].t, -1.0);
+        assert_eq!(xs[0].object, Object::Sphere(s));
+        assert_eq!(xs[1].t, 1.0);
+        assert_eq!(xs[1].object, Object::Sphere(s));
+        Ok(())
+    }
+
+    #[test]
+    fn sphere_is_behind_a_ray() -> Result<()> {
+        let ray = Ray::new(Point::new(0, 0, 5), Vector::new(0, 0, 1));
+        let s = Sphere::default();
+
+        let xs = s.intersect(&ray)?;
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].t, -6.0);
+        assert_eq!(xs[0].object, Object::Sphere(s));
+        assert_eq!(xs[1].t, -4.0);
+        assert_eq!(xs[1].object, Object::Sphere(s));
+        Ok(())
+    }
+
+    #[test]
+    fn intersecting_a_scaled_sphere_with_a_ray() -> Result<()> {
+        let r = Ray::new(Point::new(0, 0, -5), Vector::new(0, 0, 1));
+        let mut s = Sphere::default();
+
+        s.set_transform(scaling(2, 2, 2))?;
+        let xs = s.intersect(&r)?;
+
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].t, 3.0);
+        assert_eq!(xs[1].t, 7.0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn intersecting_a_translated_sphere_with_a_ray() -> Result<()> {
+        let r = Ray::new(Point::new(0, 0, -5), Vector::new(0, 0, 1));
+        let mut s = Sphere::default();
+
+        s.set_transform(translation(5, 0, 0))?;
+        let xs = s.intersect(&r)?;
+
+        assert_eq!(xs.len(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn surface_normal_for_sphere() -> Result<()> {
+        let mut s = Sphere::default();
+
+        // test some basic surface normals out for a unit sphere
+        assert_eq!(s.normal_at(Tuple::point(1, 0, 0))?, Tuple::vector(1, 0, 0));
+        assert_eq!(s.normal_at(Tuple::point(0, 1, 0))?, Tuple::vector(0, 1, 0));
+        assert_eq!(s.normal_at(Tuple::point(0, 0, 1))?, Tuple::vector(0, 0, 1));
+
+        // now we check that the normal vector returned is also normalized
+        let n = Tuple::vector(
+            3.0_f64.sqrt() / 3.0,
+            3.0_f64.sqrt() / 3.0,
+            3.0_f64.sqrt() / 3.0,
+        );
+
+        assert_eq!(
+            s.normal_at(Tuple::point(
+                3.0_f64.sqrt() / 3.0,
+                3.0_f64.sqrt() / 3.0,
+                3.0_f64.sqrt() / 3.0
+            ))?,
+            n
+        );
+
+        assert_eq!(n, n.normalize());
+
+        // the normal_at function should be able to handle transforms
+        s.set_transform(translation(0, 1, 0))?;
+        let n = s.normal_at(Tuple::point(0, 1.70711, -FRAC_1_SQRT_2))?;
+        assert_eq!(n, Tuple::vector(0, FRAC_1_SQRT_2, -FRAC_1_SQRT_2));
+        assert_eq!(n.get_w(), 0.0);
+
+        let transform = (scaling(1, 0.5, 1) * rotation_z(PI / 5.0))?;
+        s.set_transform(transform)?;
+        assert_eq!(
+            s.normal_at(Tuple::point(0, SQRT_2 / 2.0, -SQRT_2 / 2.0))?,
+            Tuple::vector(0, 0.97014, -0.24254)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn normals_of_scaled_and_sheared_spheres() -> Result<()> {
+        let mut s = Sphere::default();
+
+        // non-uniform scaling
+        s.set_transform(scaling(1, 0.5, 1))?;
+        let n = s.normal_at(Tuple::point(0, SQRT_2 / 4.0, -SQRT_2 / 2.0))?;
+        assert_eq!(n, Tuple::vector(0, 0.89443, -0.44721));
+        assert_eq!(n.get_w(), 0.0);
+
+        // shearing combined with a translation, checked against the full
+        // 4x4 inverse-transpose computation for points on the surface
+        let transform = (translation(1, 2, 3) * shearing(1, 0, 0.5, 0, 0, 1))?;
+        s.set_transform(transform)?;
+
+        for object_point in [
+            Tuple::point(0, 1, 0),
+            Tuple::point(1, 0, 0),
+            Tuple::point(0, -FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+        ] {
+            let world_point = transform * object_point;
+            let object_normal = object_point - Tuple::origin();
+            let expected = (inverse_4x4(&transform)?.transpose() * object_normal)
+                .as_vector()
+                .normalize();
+
+            let n = s.normal_at(world_point)?;
+            assert_eq!(n, expected);
+            assert_eq!(n.get_w(), 0.0);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn normal_at_errors_for_non_invertible_transform() {
+        let s = Sphere::with_unchecked_transform(scaling(1, 0, 1), Material::default());
+        assert!(s.normal_at(Tuple::point(1, 0, 0)).is_err());
+    }
+
+    #[test]
+    fn non_invertible_transforms_are_rejected_up_front() {
+        let mut s = Sphere::default();
+        let err = s.set_transform(scaling(1, 0, 1)).unwrap_err();
+
+        assert_eq!(
+            err.downcast_ref::<RayTracerError>(),
+            Some(&RayTracerError::NonInvertibleTransform {
+                object: Object::Sphere(s).to_string()
+            })
+        );
+        assert_eq!(s.transform_matrix, Matrix::<4, 4>::identity());
+        assert!(Sphere::new(scaling(0, 0, 0), Material::default()).is_err());
+    }
+
+    #[test]
+    fn every_sphere_gets_its_own_id() {
+        let spheres: Vec<_> = (0..100).map(|_| Sphere::default().id()).collect();
+
+        for (i, id) in spheres.iter().enumerate() {
+            assert!(!spheres[i + 1..].contains(id));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "wasm")]
+    fn wasm_ids_come_from_a_counter() -> Result<()> {
+        let first = Sphere::default().id().as_u128();
+        let second = Sphere::new(Matrix::<4, 4>::identity(), Material::default())?
+            .id()
+            .as_u128();
+
+        // other tests may create spheres in between
+        assert!(second > first);
+
+        Ok(())
+    }
+
+    #[test]
+    fn sphere_starts_with_default_material() {
+        let s = Sphere::default();
+        assert_eq!(s.material, Material::default());
+    }
+
+    #[test]
+    fn sphere_material_can_be_set() {
+        let mut s = Sphere::default();
+        let mut m = Material::default();
+
+        m.set_color(Color::green());
+        m.set_ambient(0.5);
+
+        s.set_material(m);
+        assert_eq!(s.material.get_color(), Color::green());
+        assert_eq!(s.material.get_ambient(), 0.5);
+    }
+}
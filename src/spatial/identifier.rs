@@ -105,6 +105,21 @@ impl ops::Div<f64> for &Identifier {
     }
 }
 
+/// An [Identifier] is serialized as its `w` value
+#[cfg(feature = "serde")]
+impl serde::Serialize for Identifier {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i64(self.value() as i64)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Identifier {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        f64::deserialize(deserializer).map(Identifier::from)
+    }
+}
+
 impl<T> From<T> for Identifier
 where
     T: Into<f64>,
@@ -161,4 +176,21 @@ mod tests {
         assert_eq!(&vector * one, Identifier::Vector);
         assert_eq!(&vector * zero, Identifier::Vector);
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn identifiers_serialize_as_their_w_value() -> anyhow::Result<()> {
+        assert_eq!(serde_json::to_string(&Identifier::Point)?, "1");
+        assert_eq!(serde_json::to_string(&Identifier::Vector)?, "0");
+        assert_eq!(serde_json::from_str::<Identifier>("1")?, Identifier::Point);
+        assert_eq!(
+            serde_json::from_str::<Identifier>("0.0")?,
+            Identifier::Vector
+        );
+        assert_eq!(
+            serde_json::from_str::<Identifier>("7")?,
+            Identifier::Invalid
+        );
+        Ok(())
+    }
 }
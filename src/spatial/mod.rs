@@ -1,5 +1,7 @@
 mod identifier;
 mod tuple;
+mod typed;
 
 pub use identifier::Identifier;
-pub use tuple::Tuple;
+pub use tuple::{bounds_of, Tuple};
+pub use typed::{Point, Vector};
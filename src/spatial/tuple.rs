@@ -1,9 +1,19 @@
 use super::identifier::Identifier;
 use crate::utils::float_equals;
-use std::ops;
-
-#[derive(Clone, Copy, Debug)]
+use anyhow::Error;
+use std::{fmt, ops};
+
+#[derive(Clone, Copy)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "TupleData", into = "TupleData")
+)]
 /// Representation of a spatial property like a Vector, or Point
+///
+/// With the `serde` feature enabled, a [Tuple] is written as an object
+/// with `x`, `y`, `z`, and `w` fields, and only points (`w = 1`) and
+/// vectors (`w = 0`) can be read back.
 pub struct Tuple {
     x: f64,
     y: f64,
@@ -56,6 +66,81 @@ impl Tuple {
         Self::new(x.into(), y.into(), z.into(), Identifier::Vector)
     }
 
+    /// Create a new point from an array of its x, y, and z coordinates
+    ///
+    /// ```
+    /// use raytracer::spatial::Tuple;
+    ///
+    /// let p = Tuple::point_from([1.0, 2.0, 3.0]);
+    /// assert_eq!(p, Tuple::point(1, 2, 3));
+    /// ```
+    pub fn point_from(xyz: impl Into<[f64; 3]>) -> Self {
+        let [x, y, z] = xyz.into();
+        Self::point(x, y, z)
+    }
+
+    /// Create a new vector from an array of its x, y, and z components
+    ///
+    /// ```
+    /// use raytracer::spatial::Tuple;
+    ///
+    /// let v = Tuple::vector_from([1.0, 2.0, 3.0]);
+    /// assert_eq!(v, Tuple::vector(1, 2, 3));
+    /// ```
+    pub fn vector_from(xyz: impl Into<[f64; 3]>) -> Self {
+        let [x, y, z] = xyz.into();
+        Self::vector(x, y, z)
+    }
+
+    /// The point (0, 0, 0)
+    ///
+    /// ```
+    /// use raytracer::spatial::Tuple;
+    ///
+    /// const ORIGIN: Tuple = Tuple::origin();
+    /// assert_eq!(ORIGIN, Tuple::point(0, 0, 0));
+    /// ```
+    pub const fn origin() -> Self {
+        Self::const_tuple(0.0, 0.0, 0.0, Identifier::Point)
+    }
+
+    /// The vector (0, 0, 0)
+    pub const fn zero_vector() -> Self {
+        Self::const_tuple(0.0, 0.0, 0.0, Identifier::Vector)
+    }
+
+    /// The unit vector (1, 0, 0) along the x axis
+    pub const fn unit_x() -> Self {
+        Self::const_tuple(1.0, 0.0, 0.0, Identifier::Vector)
+    }
+
+    /// The unit vector (0, 1, 0) along the y axis, which is up
+    pub const fn unit_y() -> Self {
+        Self::const_tuple(0.0, 1.0, 0.0, Identifier::Vector)
+    }
+
+    /// The unit vector (0, 0, 1) along the z axis
+    pub const fn unit_z() -> Self {
+        Self::const_tuple(0.0, 0.0, 1.0, Identifier::Vector)
+    }
+
+    /// Same as [Tuple::new], but usable in const contexts since it
+    /// takes plain f64 components
+    const fn const_tuple(x: f64, y: f64, z: f64, w: Identifier) -> Self {
+        Self { x, y, z, w }
+    }
+
+    /// Returns the x, y, z, and w values of the [Tuple] as an array
+    ///
+    /// ```
+    /// use raytracer::spatial::Tuple;
+    ///
+    /// assert_eq!(Tuple::point(1, 2, 3).to_array(), [1.0, 2.0, 3.0, 1.0]);
+    /// ```
+    pub fn to_array(&self) -> [f64; 4] {
+        [self.x, self.y, self.z, self.get_w()]
+    }
+
     /// Returns true if the [Tuple] represents a Point in 3D space
     ///
     /// ```
@@ -115,7 +200,15 @@ impl Tuple {
     ///
     /// _Remember: the order of [Tuple] inputs matters in the case of a cross product,
     /// aka `A x B` is not necessarily  equal to `B x A`_
+    ///
+    /// The cross product is only defined for vectors, so debug builds
+    /// panic when either [Tuple] is a point.
     pub fn cross(&self, other: &Self) -> Self {
+        debug_assert!(
+            self.is_a_vector() && other.is_a_vector(),
+            "the cross product is only defined for vectors"
+        );
+
         let new_x = (self.y * other.z) - (self.z * other.y);
         let new_y = (self.z * other.x) - (self.x * other.z);
         let new_z = (self.x * other.y) - (self.y * other.x);
@@ -123,6 +216,84 @@ impl Tuple {
         Self::vector(new_x, new_y, new_z)
     }
 
+    /// Returns the angle between this vector and another one, in radians
+    ///
+    /// ```
+    /// use raytracer::spatial::Tuple;
+    /// use std::f64::consts::FRAC_PI_2;
+    ///
+    /// let x = Tuple::vector(1, 0, 0);
+    /// let y = Tuple::vector(0, 1, 0);
+    /// assert_eq!(x.angle_between(&y), FRAC_PI_2);
+    /// ```
+    pub fn angle_between(&self, other: &Tuple) -> f64 {
+        debug_assert!(
+            self.is_a_vector() && other.is_a_vector(),
+            "angles can only be measured between vectors"
+        );
+        debug_assert!(
+            self.magnitude() > 0.0 && other.magnitude() > 0.0,
+            "a zero vector has no direction to measure an angle from"
+        );
+
+        let cosine = self.dot(other) / (self.magnitude() * other.magnitude());
+
+        // rounding errors can push the cosine just outside [-1, 1]
+        cosine.clamp(-1.0, 1.0).acos()
+    }
+
+    /// Returns the projection of this vector onto another one, which
+    /// is the part of this vector that points along `other`
+    ///
+    /// ```
+    /// use raytracer::spatial::Tuple;
+    ///
+    /// let v = Tuple::vector(3, 4, 0);
+    /// assert_eq!(v.project_onto(&Tuple::vector(1, 0, 0)), Tuple::vector(3, 0, 0));
+    /// ```
+    pub fn project_onto(&self, other: &Tuple) -> Tuple {
+        debug_assert!(
+            self.is_a_vector() && other.is_a_vector(),
+            "only vectors can be projected onto vectors"
+        );
+        debug_assert!(
+            other.magnitude() > 0.0,
+            "a vector can't be projected onto a zero vector"
+        );
+
+        other * (self.dot(other) / other.dot(other))
+    }
+
+    /// Returns the rejection of this vector from another one, which is
+    /// the part of this vector that is perpendicular to `other`. Adding
+    /// the projection and the rejection gives back the original vector.
+    pub fn reject_from(&self, other: &Tuple) -> Tuple {
+        self - &self.project_onto(other)
+    }
+
+    /// Returns the reflection of this vector around the given surface
+    /// normal, using the formula: reflect(v, n) = v - n × 2 × dot(v, n)
+    ///
+    /// The normal is expected to be normalized. For a non-unit normal
+    /// the result is not a true reflection, since the component along
+    /// the normal gets scaled by the squared magnitude of the normal.
+    ///
+    /// ```
+    /// use raytracer::spatial::Tuple;
+    ///
+    /// let v = Tuple::vector(1, -1, 0);
+    /// let n = Tuple::vector(0, 1, 0);
+    /// assert_eq!(v.reflect(&n), Tuple::vector(1, 1, 0));
+    /// ```
+    pub fn reflect(&self, normal: &Tuple) -> Tuple {
+        debug_assert!(
+            self.is_a_vector() && normal.is_a_vector(),
+            "only vectors can be reflected around a vector"
+        );
+
+        self - &(normal * (2.0 * normal.dot(self)))
+    }
+
     /// Returns the x coordinate of the [Tuple]
     pub fn get_x(&self) -> f64 {
         self.x
@@ -143,11 +314,146 @@ impl Tuple {
         self.w.value() as f64
     }
 
+    /// Returns an iterator over the x, y, and z components of the [Tuple]
+    ///
+    /// ```
+    /// use raytracer::spatial::Tuple;
+    ///
+    /// let v = Tuple::vector(1, 2, 3);
+    /// assert_eq!(v.iter_xyz().sum::<f64>(), 6.0);
+    /// ```
+    pub fn iter_xyz(&self) -> impl Iterator<Item = f64> {
+        [self.x, self.y, self.z].into_iter()
+    }
+
+    /// Returns the largest of the x, y, and z components
+    pub fn max_component(&self) -> f64 {
+        self.x.max(self.y).max(self.z)
+    }
+
+    /// Returns the smallest of the x, y, and z components
+    pub fn min_component(&self) -> f64 {
+        self.x.min(self.y).min(self.z)
+    }
+
     /// Returns a vector with the x,y,z values
     /// of the current [Tuple]
     pub fn convert_to_vector(&self) -> Tuple {
+        self.as_vector()
+    }
+
+    /// Returns a tuple with the same x, y and z, and w forced to 0.
+    ///
+    /// Multiplying a normal by a matrix with a translation can leave
+    /// a stray w behind, which throws off the magnitude and any dot
+    /// products. This drops it again.
+    ///
+    /// ```
+    /// use raytracer::spatial::Tuple;
+    ///
+    /// let t = Tuple::point(1, 2, 3) + Tuple::vector(0, 0, 1);
+    /// assert_eq!(t.as_vector(), Tuple::vector(1, 2, 4));
+    /// assert_eq!(t.as_vector().get_w(), 0.0);
+    /// ```
+    pub fn as_vector(&self) -> Tuple {
         Tuple::vector(self.x, self.y, self.z)
     }
+
+    /// Returns the smallest of each of the x, y, and z components of
+    /// the two tuples. The result keeps the identifier when both agree
+    /// on it, and is [Identifier::Invalid] otherwise.
+    ///
+    /// ```
+    /// use raytracer::spatial::Tuple;
+    ///
+    /// let a = Tuple::point(1, -2, 3);
+    /// let b = Tuple::point(-1, 2, 0);
+    /// assert_eq!(a.min(&b), Tuple::point(-1, -2, 0));
+    /// ```
+    pub fn min(&self, other: &Tuple) -> Tuple {
+        Tuple {
+            x: self.x.min(other.x),
+            y: self.y.min(other.y),
+            z: self.z.min(other.z),
+            w: Self::common_identifier(self, other),
+        }
+    }
+
+    /// Returns the largest of each of the x, y, and z components of
+    /// the two tuples. Identifiers are handled as in [Tuple::min].
+    ///
+    /// ```
+    /// use raytracer::spatial::Tuple;
+    ///
+    /// let a = Tuple::point(1, -2, 3);
+    /// let b = Tuple::point(-1, 2, 0);
+    /// assert_eq!(a.max(&b), Tuple::point(1, 2, 3));
+    /// ```
+    pub fn max(&self, other: &Tuple) -> Tuple {
+        Tuple {
+            x: self.x.max(other.x),
+            y: self.y.max(other.y),
+            z: self.z.max(other.z),
+            w: Self::common_identifier(self, other),
+        }
+    }
+
+    /// Restricts each of the x, y, and z components to lie between the
+    /// matching components of `min` and `max`, keeping the identifier
+    /// of `self`
+    ///
+    /// ```
+    /// use raytracer::spatial::Tuple;
+    ///
+    /// let p = Tuple::point(5, -5, 0.5);
+    /// let clamped = p.clamp_components(&Tuple::point(0, 0, 0), &Tuple::point(1, 1, 1));
+    /// assert_eq!(clamped, Tuple::point(1, 0, 0.5));
+    /// ```
+    pub fn clamp_components(&self, min: &Tuple, max: &Tuple) -> Tuple {
+        Tuple {
+            x: self.x.max(min.x).min(max.x),
+            y: self.y.max(min.y).min(max.y),
+            z: self.z.max(min.z).min(max.z),
+            w: self.w,
+        }
+    }
+
+    fn common_identifier(a: &Tuple, b: &Tuple) -> Identifier {
+        if a.w == b.w {
+            a.w
+        } else {
+            Identifier::Invalid
+        }
+    }
+}
+
+/// Finds the smallest axis-aligned box that contains all of `points`,
+/// returned as its minimum and maximum corners.
+///
+/// With no points at all the box is empty: the minimum corner is at
+/// positive infinity and the maximum at negative infinity, so it
+/// grows to fit anything it is combined with.
+///
+/// ```
+/// use raytracer::spatial::{bounds_of, Tuple};
+///
+/// let triangle = [
+///     Tuple::point(0, 1, 0),
+///     Tuple::point(-1, 0, 0),
+///     Tuple::point(1, 0, 2),
+/// ];
+///
+/// let (min, max) = bounds_of(triangle.into_iter());
+/// assert_eq!(min, Tuple::point(-1, 0, 0));
+/// assert_eq!(max, Tuple::point(1, 1, 2));
+/// ```
+pub fn bounds_of(points: impl Iterator<Item = Tuple>) -> (Tuple, Tuple) {
+    let empty = (
+        Tuple::point(f64::INFINITY, f64::INFINITY, f64::INFINITY),
+        Tuple::point(f64::NEG_INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY),
+    );
+
+    points.fold(empty, |(min, max), p| (min.min(&p), max.max(&p)))
 }
 
 impl ops::Add<&Tuple> for &Tuple {
@@ -189,6 +495,14 @@ impl ops::Sub<&Tuple> for &Tuple {
     }
 }
 
+impl ops::Sub<Tuple> for Tuple {
+    type Output = Tuple;
+
+    fn sub(self, rhs: Tuple) -> Self::Output {
+        ops::Sub::sub(&self, &rhs)
+    }
+}
+
 impl ops::Neg for &Tuple {
     type Output = Tuple;
 
@@ -228,6 +542,74 @@ impl ops::Div<f64> for &Tuple {
     }
 }
 
+impl ops::Mul<f64> for Tuple {
+    type Output = Tuple;
+
+    fn mul(self, rhs: f64) -> Self::Output {
+        ops::Mul::mul(&self, rhs)
+    }
+}
+
+impl ops::Div<f64> for Tuple {
+    type Output = Tuple;
+
+    fn div(self, rhs: f64) -> Self::Output {
+        ops::Div::div(&self, rhs)
+    }
+}
+
+impl ops::Mul<&Tuple> for f64 {
+    type Output = Tuple;
+
+    fn mul(self, rhs: &Tuple) -> Self::Output {
+        ops::Mul::mul(rhs, self)
+    }
+}
+
+impl ops::Mul<Tuple> for f64 {
+    type Output = Tuple;
+
+    fn mul(self, rhs: Tuple) -> Self::Output {
+        ops::Mul::mul(&rhs, self)
+    }
+}
+
+impl ops::AddAssign<&Tuple> for Tuple {
+    fn add_assign(&mut self, rhs: &Tuple) {
+        *self = ops::Add::add(&*self, rhs);
+    }
+}
+
+impl ops::AddAssign<Tuple> for Tuple {
+    fn add_assign(&mut self, rhs: Tuple) {
+        *self += &rhs;
+    }
+}
+
+impl ops::SubAssign<&Tuple> for Tuple {
+    fn sub_assign(&mut self, rhs: &Tuple) {
+        *self = ops::Sub::sub(&*self, rhs);
+    }
+}
+
+impl ops::SubAssign<Tuple> for Tuple {
+    fn sub_assign(&mut self, rhs: Tuple) {
+        *self -= &rhs;
+    }
+}
+
+impl ops::MulAssign<f64> for Tuple {
+    fn mul_assign(&mut self, rhs: f64) {
+        *self = ops::Mul::mul(&*self, rhs);
+    }
+}
+
+impl ops::DivAssign<f64> for Tuple {
+    fn div_assign(&mut self, rhs: f64) {
+        *self = ops::Div::div(&*self, rhs);
+    }
+}
+
 impl ops::Neg for Tuple {
     type Output = Tuple;
 
@@ -236,6 +618,87 @@ impl ops::Neg for Tuple {
     }
 }
 
+/// Formats a single component with 5 decimals, without ever showing
+/// a negative zero
+fn fmt_component(value: f64) -> String {
+    let formatted = format!("{:.5}", value);
+    match formatted.strip_prefix('-') {
+        Some(unsigned) if unsigned.bytes().all(|b| b == b'0' || b == b'.') => unsigned.to_string(),
+        _ => formatted,
+    }
+}
+
+/// Formats points as `P(x, y, z)` and vectors as `V(x, y, z)`. Tuples
+/// that are neither are shown with their `w` value as `T(x, y, z, w)`.
+///
+/// ```
+/// use raytracer::spatial::Tuple;
+///
+/// assert_eq!(Tuple::point(1, -2.5, 0).to_string(), "P(1.00000, -2.50000, 0.00000)");
+/// assert_eq!(Tuple::vector(0, 1, 0).to_string(), "V(0.00000, 1.00000, 0.00000)");
+/// ```
+impl fmt::Display for Tuple {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (x, y, z) = (
+            fmt_component(self.x),
+            fmt_component(self.y),
+            fmt_component(self.z),
+        );
+
+        match self.w {
+            Identifier::Point => write!(f, "P({}, {}, {})", x, y, z),
+            Identifier::Vector => write!(f, "V({}, {}, {})", x, y, z),
+            Identifier::Invalid => write!(f, "T({}, {}, {}, {})", x, y, z, self.get_w()),
+        }
+    }
+}
+
+/// Uses the same compact form as [fmt::Display], which keeps test
+/// failure output short and readable
+impl fmt::Debug for Tuple {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// Indexes the components of a [Tuple] in the order x, y, z, w.
+///
+/// Panics if the index is greater than 3.
+impl ops::Index<usize> for Tuple {
+    type Output = f64;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        match index {
+            0 => &self.x,
+            1 => &self.y,
+            2 => &self.z,
+            3 => match self.w {
+                Identifier::Vector => &0.0,
+                Identifier::Point => &1.0,
+                Identifier::Invalid => &2.0,
+            },
+            _ => panic!("Tuple index out of bounds: {} is greater than 3", index),
+        }
+    }
+}
+
+/// Mutably indexes the x, y, and z components of a [Tuple].
+///
+/// The w component decides whether the [Tuple] is a point or a vector,
+/// so it can't be changed through an index. Panics if the index is
+/// greater than 2.
+impl ops::IndexMut<usize> for Tuple {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        match index {
+            0 => &mut self.x,
+            1 => &mut self.y,
+            2 => &mut self.z,
+            3 => panic!("The w component of a Tuple can't be mutated through an index"),
+            _ => panic!("Tuple index out of bounds: {} is greater than 3", index),
+        }
+    }
+}
+
 impl<T, U, G, N> From<(T, U, G, N)> for Tuple
 where
     T: Into<f64>,
@@ -253,6 +716,55 @@ where
     }
 }
 
+/// Creates a [Tuple] from a slice of exactly four values, where the
+/// last value is the `w` component that identifies the [Tuple].
+impl TryFrom<&[f64]> for Tuple {
+    type Error = Error;
+
+    fn try_from(value: &[f64]) -> Result<Self, Self::Error> {
+        match *value {
+            [x, y, z, w] => Ok(Tuple::from((x, y, z, w))),
+            _ => Err(Error::msg(format!(
+                "A tuple needs exactly 4 values, but {} were given",
+                value.len()
+            ))),
+        }
+    }
+}
+
+/// The serialized form of a [Tuple]
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct TupleData {
+    x: f64,
+    y: f64,
+    z: f64,
+    w: f64,
+}
+
+#[cfg(feature = "serde")]
+impl From<Tuple> for TupleData {
+    fn from(value: Tuple) -> Self {
+        let [x, y, z, w] = value.to_array();
+        Self { x, y, z, w }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<TupleData> for Tuple {
+    type Error = Error;
+
+    fn try_from(value: TupleData) -> Result<Self, Self::Error> {
+        match Identifier::from(value.w) {
+            Identifier::Invalid => Err(Error::msg(format!(
+                "w must be 0 for a vector or 1 for a point, but was {}",
+                value.w
+            ))),
+            w => Ok(Tuple::new(value.x, value.y, value.z, w)),
+        }
+    }
+}
+
 impl PartialEq for Tuple {
     fn eq(&self, other: &Self) -> bool {
         float_equals(&self.x, &other.x)
@@ -263,9 +775,13 @@ impl PartialEq for Tuple {
 }
 
 #[cfg(test)]
+// the tests spell out the borrowed forms of the operators on purpose
+#[allow(clippy::op_ref)]
 mod tests {
     use super::Identifier;
-    use super::Tuple;
+    use super::{bounds_of, Tuple};
+    use crate::utils::float_equals_eps;
+    use std::f64::consts::{FRAC_PI_2, FRAC_PI_4, PI, SQRT_2};
 
     #[test]
     fn tuple_new() {
@@ -376,6 +892,51 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn owned_tuple_operations_match_reference_forms() {
+        let point_a = Tuple::point(3.0, -2.0, 5.0);
+        let vector_a = Tuple::vector(-2.0, 3.0, 1.0);
+        let vector_b = Tuple::vector(5.0, -3.0, -5.0);
+
+        assert_eq!(point_a - vector_a, &point_a - &vector_a);
+        assert!((point_a - vector_a).is_a_point());
+        assert_eq!(vector_a - vector_b, &vector_a - &vector_b);
+        assert!((vector_a - vector_b).is_a_vector());
+
+        assert_eq!(vector_a * 2.0, &vector_a * 2.0);
+        assert_eq!(vector_a / 2.0, &vector_a / 2.0);
+    }
+
+    #[test]
+    fn compound_assignment_operators() {
+        let mut p = Tuple::point(1, 2, 3);
+        p += Tuple::vector(1, 1, 1);
+        assert_eq!(p, Tuple::point(2, 3, 4));
+        assert!(p.is_a_point());
+
+        p -= &Tuple::vector(2, 3, 4);
+        assert_eq!(p, Tuple::point(0, 0, 0));
+
+        // subtracting a point from a point follows the binary op rules
+        let mut d = Tuple::point(3, 2, 1);
+        d -= Tuple::point(1, 1, 1);
+        assert_eq!(d, Tuple::vector(2, 1, 0));
+
+        let mut v = Tuple::vector(1, -2, 3);
+        v *= 2.0;
+        assert_eq!(v, Tuple::vector(2, -4, 6));
+
+        v /= 4.0;
+        assert_eq!(v, Tuple::vector(0.5, -1, 1.5));
+    }
+
+    #[test]
+    fn scalar_on_the_left_multiplies_tuples() {
+        let v = Tuple::vector(1, -2, 3);
+        assert_eq!(2.0 * v, v * 2.0);
+        assert_eq!(2.0 * &v, Tuple::vector(2, -4, 6));
+    }
+
     #[test]
     fn negate_tuples() {
         let point_a = Tuple::point(3.0, -2.0, 5.0);
@@ -465,6 +1026,154 @@ mod tests {
         assert_eq!(b.cross(&a), Tuple::vector(1, -2, 1));
     }
 
+    #[test]
+    fn reflect() {
+        // Reflecting a vector approaching at 45 degree angle
+        let v = Tuple::vector(1, -1, 0);
+        let n = Tuple::vector(0, 1, 0);
+        assert_eq!(v.reflect(&n), Tuple::vector(1, 1, 0));
+
+        // Reflecting off a slanted surface
+        let v = Tuple::vector(0, -1, 0);
+        let n = Tuple::vector(SQRT_2 / 2.0, SQRT_2 / 2.0, 0);
+        assert_eq!(v.reflect(&n), Tuple::vector(1, 0, 0));
+
+        // A non-unit normal scales the component along the normal
+        // by its squared magnitude
+        let v = Tuple::vector(1, -1, 0);
+        let n = Tuple::vector(0, 2, 0);
+        assert_eq!(v.reflect(&n), Tuple::vector(1, 7, 0));
+    }
+
+    #[test]
+    fn indexing_matches_the_getters() {
+        let p = Tuple::point(1.5, -2, 3);
+        assert_eq!(p[0], p.get_x());
+        assert_eq!(p[1], p.get_y());
+        assert_eq!(p[2], p.get_z());
+        assert_eq!(p[3], p.get_w());
+        assert_eq!(Tuple::vector(1, 2, 3)[3], 0.0);
+    }
+
+    #[test]
+    fn mutating_through_an_index() {
+        let mut v = Tuple::vector(1, 2, 3);
+        v[0] = 4.0;
+        v[2] += 1.0;
+
+        assert_eq!(v.get_x(), 4.0);
+        assert_eq!(v.get_z(), 4.0);
+        assert!(v.is_a_vector());
+    }
+
+    #[test]
+    #[should_panic]
+    fn indexing_past_w_panics() {
+        let _ = Tuple::point(1, 2, 3)[4];
+    }
+
+    #[test]
+    #[should_panic]
+    fn mutating_w_through_an_index_panics() {
+        let mut p = Tuple::point(1, 2, 3);
+        p[3] = 0.0;
+    }
+
+    #[test]
+    fn iterating_and_component_extremes() {
+        let v = Tuple::vector(-5, -1, -3);
+        assert_eq!(v.iter_xyz().collect::<Vec<_>>(), vec![-5.0, -1.0, -3.0]);
+        assert_eq!(v.max_component(), -1.0);
+        assert_eq!(v.min_component(), -5.0);
+
+        // the w component is never considered
+        let p = Tuple::point(-1, -2, -3);
+        assert_eq!(p.max_component(), -1.0);
+    }
+
+    #[test]
+    fn constructing_from_arrays() {
+        assert_eq!(
+            Tuple::point_from([1.0, -2.0, 3.5]),
+            Tuple::point(1, -2, 3.5)
+        );
+        assert_eq!(Tuple::vector_from([0.0, 1.0, 0.0]), Tuple::vector(0, 1, 0));
+    }
+
+    #[test]
+    fn constructing_from_slices() -> anyhow::Result<()> {
+        let values = [1.0, 2.0, 3.0, 1.0, 5.0];
+
+        let p = Tuple::try_from(&values[..4])?;
+        assert_eq!(p, Tuple::point(1, 2, 3));
+
+        let v = Tuple::try_from(&[1.0, 2.0, 3.0, 0.0][..])?;
+        assert!(v.is_a_vector());
+
+        assert!(Tuple::try_from(&values[..3]).is_err());
+        assert!(Tuple::try_from(&values[..]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip_through_to_array() -> anyhow::Result<()> {
+        for t in [Tuple::point(4, -1, 0.5), Tuple::vector(-3, 2, 7)] {
+            assert_eq!(Tuple::try_from(&t.to_array()[..])?, t);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn angles_between_vectors() {
+        let x = Tuple::vector(1, 0, 0);
+        assert_eq!(x.angle_between(&Tuple::vector(0, 0, 3)), FRAC_PI_2);
+        assert_eq!(x.angle_between(&Tuple::vector(5, 0, 0)), 0.0);
+        assert_eq!(x.angle_between(&Tuple::vector(-2, 0, 0)), PI);
+        assert!(float_equals_eps(
+            &x.angle_between(&Tuple::vector(1, 1, 0)),
+            &FRAC_PI_4,
+            1e-12
+        ));
+    }
+
+    #[test]
+    fn projection_and_rejection() {
+        let v = Tuple::vector(3, 4, 0);
+        let x = Tuple::vector(2, 0, 0);
+
+        assert_eq!(v.project_onto(&x), Tuple::vector(3, 0, 0));
+        assert_eq!(v.reject_from(&x), Tuple::vector(0, 4, 0));
+
+        let w = Tuple::vector(1, -2, 5);
+        let axis = Tuple::vector(1, 1, 1);
+        let projection = w.project_onto(&axis);
+        let rejection = w.reject_from(&axis);
+
+        assert_eq!(projection + rejection, w);
+        assert!(float_equals_eps(&rejection.dot(&axis), &0.0, 1e-12));
+    }
+
+    #[test]
+    #[should_panic(expected = "only defined for vectors")]
+    fn cross_product_rejects_points() {
+        let _ = Tuple::vector(1, 0, 0).cross(&Tuple::point(0, 1, 0));
+    }
+
+    #[test]
+    fn display_and_debug_are_compact() {
+        let p = Tuple::point(1, -2.5, 1.0 / 3.0);
+        assert_eq!(p.to_string(), "P(1.00000, -2.50000, 0.33333)");
+        assert_eq!(format!("{:?}", p), p.to_string());
+
+        let v = Tuple::vector(-0.0, -0.000001, 4);
+        assert_eq!(v.to_string(), "V(0.00000, 0.00000, 4.00000)");
+
+        let invalid = Tuple::point(1, 2, 3) + Tuple::point(1, 2, 3);
+        assert_eq!(invalid.to_string(), "T(2.00000, 4.00000, 6.00000, 2)");
+    }
+
     #[test]
     fn convert_to_vector_works() {
         let p = Tuple::point(2, 3, 4);
@@ -476,4 +1185,97 @@ mod tests {
         // case 2: vector to vector
         assert_eq!(v.convert_to_vector(), Tuple::vector(2, 4, 5));
     }
+
+    #[test]
+    fn axis_constants_have_the_expected_components() {
+        const AXES: [Tuple; 3] = [Tuple::unit_x(), Tuple::unit_y(), Tuple::unit_z()];
+
+        assert_eq!(AXES[0], Tuple::vector(1, 0, 0));
+        assert_eq!(AXES[1], Tuple::vector(0, 1, 0));
+        assert_eq!(AXES[2], Tuple::vector(0, 0, 1));
+        assert!(AXES.iter().all(|axis| axis.w == Identifier::Vector));
+        assert!(AXES.iter().all(|axis| axis.magnitude() == 1.0));
+
+        const ORIGIN: Tuple = Tuple::origin();
+        const ZERO: Tuple = Tuple::zero_vector();
+        assert_eq!(ORIGIN.to_array(), [0.0, 0.0, 0.0, 1.0]);
+        assert_eq!(ORIGIN.w, Identifier::Point);
+        assert_eq!(ZERO.to_array(), [0.0, 0.0, 0.0, 0.0]);
+        assert_eq!(ZERO.w, Identifier::Vector);
+        assert_eq!(Tuple::unit_x().cross(&Tuple::unit_y()), Tuple::unit_z());
+    }
+
+    #[test]
+    fn as_vector_drops_any_w() {
+        let stray = Tuple::point(1, 2, 3) + Tuple::point(0, 0, 0);
+        assert_eq!(stray.get_w(), 2.0);
+
+        let v = stray.as_vector();
+        assert!(v.is_a_vector());
+        assert_eq!(v.get_w(), 0.0);
+        assert_eq!((v.get_x(), v.get_y(), v.get_z()), (1.0, 2.0, 3.0));
+    }
+
+    #[test]
+    fn componentwise_min_and_max() {
+        let a = Tuple::point(-3, 2, 0.5);
+        let b = Tuple::point(1, -4, -0.5);
+
+        assert_eq!(a.min(&b), Tuple::point(-3, -4, -0.5));
+        assert_eq!(a.max(&b), Tuple::point(1, 2, 0.5));
+        assert_eq!(a.min(&b), b.min(&a));
+    }
+
+    #[test]
+    fn min_and_max_keep_only_a_shared_identifier() {
+        let p = Tuple::point(1, 2, 3);
+        let v = Tuple::vector(3, 2, 1);
+
+        assert!(p.min(&p).is_a_point());
+        assert!(v.max(&v).is_a_vector());
+        assert_eq!(p.min(&v).get_w(), Identifier::Invalid.value() as f64);
+        assert_eq!(v.max(&p).get_w(), Identifier::Invalid.value() as f64);
+
+        let clamped = v.clamp_components(&Tuple::origin(), &Tuple::point(2, 2, 2));
+        assert_eq!(clamped, Tuple::vector(2, 2, 1));
+    }
+
+    #[test]
+    fn bounds_of_a_triangle() {
+        let vertices = [
+            Tuple::point(-1, 0, 2),
+            Tuple::point(3, -2, 0),
+            Tuple::point(0, 4, 1),
+        ];
+
+        let (min, max) = bounds_of(vertices.into_iter());
+        assert_eq!(min, Tuple::point(-1, -2, 0));
+        assert_eq!(max, Tuple::point(3, 4, 2));
+
+        let (min, max) = bounds_of(std::iter::empty());
+        assert!(min.min_component() > max.max_component());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn json_round_trip_of_points_and_vectors() -> anyhow::Result<()> {
+        let p = Tuple::point(1, -2.5, 3);
+        let json = serde_json::to_string(&p)?;
+        assert_eq!(json, r#"{"x":1.0,"y":-2.5,"z":3.0,"w":1.0}"#);
+        assert_eq!(serde_json::from_str::<Tuple>(&json)?, p);
+
+        let v = Tuple::vector(0, 1, 0);
+        let back: Tuple = serde_json::from_str(&serde_json::to_string(&v)?)?;
+        assert!(back.is_a_vector());
+        assert_eq!(back, v);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserializing_an_invalid_w_fails() {
+        let json = r#"{"x":1.0,"y":2.0,"z":3.0,"w":2.0}"#;
+        assert!(serde_json::from_str::<Tuple>(json).is_err());
+    }
 }
@@ -0,0 +1,281 @@
+use super::Tuple;
+use crate::error::RayTracerError;
+use anyhow::{Error, Result};
+use std::ops;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// A point in 3D space whose kind is checked at compile time.
+///
+/// Unlike a [Tuple], only the operations that make sense for points
+/// are available, so mistakes like adding two points don't compile:
+///
+/// ```compile_fail
+/// use raytracer::spatial::Point;
+///
+/// let p = Point::new(1, 2, 3) + Point::new(4, 5, 6);
+/// ```
+///
+/// Converting to and from a [Tuple] keeps the existing [Tuple] based
+/// APIs working:
+///
+/// ```
+/// use raytracer::spatial::{Point, Tuple, Vector};
+///
+/// let p = Point::new(1, 2, 3) + Vector::new(1, 0, 0);
+/// assert_eq!(Tuple::from(p), Tuple::point(2, 2, 3));
+///
+/// assert!(Point::try_from(Tuple::vector(0, 1, 0)).is_err());
+/// ```
+pub struct Point(Tuple);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// A vector in 3D space whose kind is checked at compile time.
+///
+/// Vector-only operations like the dot product aren't available on
+/// points:
+///
+/// ```compile_fail
+/// use raytracer::spatial::Point;
+///
+/// let d = Point::new(1, 2, 3).dot(&Point::new(4, 5, 6));
+/// ```
+pub struct Vector(Tuple);
+
+impl Point {
+    /// Create a new [Point] at (x, y, z)
+    pub fn new(x: impl Into<f64>, y: impl Into<f64>, z: impl Into<f64>) -> Self {
+        Self(Tuple::point(x, y, z))
+    }
+
+    /// The point at (0, 0, 0)
+    pub fn origin() -> Self {
+        Self::new(0, 0, 0)
+    }
+
+    /// Returns the x coordinate of the [Point]
+    pub fn get_x(&self) -> f64 {
+        self.0.get_x()
+    }
+
+    /// Returns the y coordinate of the [Point]
+    pub fn get_y(&self) -> f64 {
+        self.0.get_y()
+    }
+
+    /// Returns the z coordinate of the [Point]
+    pub fn get_z(&self) -> f64 {
+        self.0.get_z()
+    }
+
+    /// Returns a reference to the underlying [Tuple]
+    pub fn as_tuple(&self) -> &Tuple {
+        &self.0
+    }
+}
+
+impl Vector {
+    /// Create a new [Vector] with components (x, y, z)
+    pub fn new(x: impl Into<f64>, y: impl Into<f64>, z: impl Into<f64>) -> Self {
+        Self(Tuple::vector(x, y, z))
+    }
+
+    /// Returns the x component of the [Vector]
+    pub fn get_x(&self) -> f64 {
+        self.0.get_x()
+    }
+
+    /// Returns the y component of the [Vector]
+    pub fn get_y(&self) -> f64 {
+        self.0.get_y()
+    }
+
+    /// Returns the z component of the [Vector]
+    pub fn get_z(&self) -> f64 {
+        self.0.get_z()
+    }
+
+    /// Returns the length of the [Vector]
+    pub fn magnitude(&self) -> f64 {
+        self.0.magnitude()
+    }
+
+    /// Returns a unit [Vector] pointing in the same direction
+    pub fn normalize(&self) -> Self {
+        Self(self.0.normalize())
+    }
+
+    /// Returns the dot product of two vectors
+    pub fn dot(&self, other: &Self) -> f64 {
+        self.0.dot(&other.0)
+    }
+
+    /// Returns the cross product of two vectors
+    pub fn cross(&self, other: &Self) -> Self {
+        Self(self.0.cross(&other.0))
+    }
+
+    /// Returns the reflection of this vector around the given normal
+    pub fn reflect(&self, normal: &Self) -> Self {
+        Self(self.0.reflect(&normal.0))
+    }
+
+    /// Returns a reference to the underlying [Tuple]
+    pub fn as_tuple(&self) -> &Tuple {
+        &self.0
+    }
+}
+
+impl From<Point> for Tuple {
+    fn from(value: Point) -> Self {
+        value.0
+    }
+}
+
+impl From<Vector> for Tuple {
+    fn from(value: Vector) -> Self {
+        value.0
+    }
+}
+
+impl TryFrom<Tuple> for Point {
+    type Error = Error;
+
+    fn try_from(value: Tuple) -> Result<Self> {
+        if value.is_a_point() {
+            Ok(Self(value))
+        } else {
+            Err(RayTracerError::invalid_tuple("point", &value).into())
+        }
+    }
+}
+
+impl TryFrom<Tuple> for Vector {
+    type Error = Error;
+
+    fn try_from(value: Tuple) -> Result<Self> {
+        if value.is_a_vector() {
+            Ok(Self(value))
+        } else {
+            Err(RayTracerError::invalid_tuple("vector", &value).into())
+        }
+    }
+}
+
+impl ops::Sub<Point> for Point {
+    type Output = Vector;
+
+    fn sub(self, rhs: Point) -> Self::Output {
+        Vector(self.0 - rhs.0)
+    }
+}
+
+impl ops::Add<Vector> for Point {
+    type Output = Point;
+
+    fn add(self, rhs: Vector) -> Self::Output {
+        Point(self.0 + rhs.0)
+    }
+}
+
+impl ops::Sub<Vector> for Point {
+    type Output = Point;
+
+    fn sub(self, rhs: Vector) -> Self::Output {
+        Point(self.0 - rhs.0)
+    }
+}
+
+impl ops::Add<Vector> for Vector {
+    type Output = Vector;
+
+    fn add(self, rhs: Vector) -> Self::Output {
+        Vector(self.0 + rhs.0)
+    }
+}
+
+impl ops::Sub<Vector> for Vector {
+    type Output = Vector;
+
+    fn sub(self, rhs: Vector) -> Self::Output {
+        Vector(self.0 - rhs.0)
+    }
+}
+
+impl ops::Mul<f64> for Vector {
+    type Output = Vector;
+
+    fn mul(self, rhs: f64) -> Self::Output {
+        Vector(self.0 * rhs)
+    }
+}
+
+impl ops::Div<f64> for Vector {
+    type Output = Vector;
+
+    fn div(self, rhs: f64) -> Self::Output {
+        Vector(self.0 / rhs)
+    }
+}
+
+impl ops::Neg for Vector {
+    type Output = Vector;
+
+    fn neg(self) -> Self::Output {
+        Vector(-self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Point, Vector};
+    use crate::spatial::Tuple;
+    use anyhow::Result;
+
+    #[test]
+    fn converting_to_and_from_tuples() -> Result<()> {
+        let p = Point::new(1, 2, 3);
+        let v = Vector::new(4, 5, 6);
+
+        assert_eq!(Tuple::from(p), Tuple::point(1, 2, 3));
+        assert_eq!(Tuple::from(v), Tuple::vector(4, 5, 6));
+
+        assert_eq!(Point::try_from(Tuple::point(1, 2, 3))?, p);
+        assert_eq!(Vector::try_from(Tuple::vector(4, 5, 6))?, v);
+
+        assert!(Point::try_from(Tuple::vector(1, 2, 3)).is_err());
+        assert!(Vector::try_from(Tuple::point(1, 2, 3)).is_err());
+        assert!(Point::try_from(Tuple::point(1, 2, 3) + Tuple::point(1, 1, 1)).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn operations_produce_the_right_kinds() {
+        let p = Point::new(3, 2, 1);
+        let q = Point::new(5, 6, 7);
+        let v = Vector::new(1, 1, 1);
+
+        assert_eq!(p - q, Vector::new(-2, -4, -6));
+        assert_eq!(p + v, Point::new(4, 3, 2));
+        assert_eq!(p - v, Point::new(2, 1, 0));
+        assert_eq!(v + v, Vector::new(2, 2, 2));
+        assert_eq!(v - v, Vector::new(0, 0, 0));
+        assert_eq!(-v * 2.0, Vector::new(-2, -2, -2));
+        assert_eq!(v / 2.0, Vector::new(0.5, 0.5, 0.5));
+    }
+
+    #[test]
+    fn vector_only_operations() {
+        let a = Vector::new(1, 2, 3);
+        let b = Vector::new(2, 3, 4);
+
+        assert_eq!(a.dot(&b), 20.0);
+        assert_eq!(a.cross(&b), Vector::new(-1, 2, -1));
+        assert_eq!(Vector::new(4, 0, 0).normalize(), Vector::new(1, 0, 0));
+        assert_eq!(Vector::new(0, 3, 4).magnitude(), 5.0);
+        assert_eq!(
+            Vector::new(1, -1, 0).reflect(&Vector::new(0, 1, 0)),
+            Vector::new(1, 1, 0)
+        );
+    }
+}
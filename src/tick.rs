@@ -1,30 +1,336 @@
 use crate::spatial::Tuple;
+use anyhow::{Error, Result};
+
+/// The environment a [Projectile] travels through, made up
+/// of a gravity vector, a wind vector, and a drag coefficient
 pub struct Environment {
     gravity: Tuple,
     wind: Tuple,
+    drag_coefficient: f64,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
+/// A projectile with a position (point) and a velocity (vector)
 pub struct Projectile {
     pub position: Tuple,
     pub velocity: Tuple,
 }
 
 impl Environment {
-    pub fn new(gravity: Tuple, wind: Tuple) -> Self {
-        Self { gravity, wind }
+    /// Create a new [Environment] with the given gravity and wind vectors
+    ///
+    /// Note: This returns a result because both gravity and wind
+    /// must be vectors.
+    pub fn new(gravity: Tuple, wind: Tuple) -> Result<Self> {
+        if !gravity.is_a_vector() || !wind.is_a_vector() {
+            return Err(Error::msg("gravity and wind must both be Vectors"));
+        }
+
+        Ok(Self {
+            gravity,
+            wind,
+            drag_coefficient: 0.0,
+        })
+    }
+
+    /// The environment used in the book: a gravity of -0.1 units
+    /// per tick along y, and a light wind of -0.01 units along x
+    pub fn default_earth() -> Self {
+        Self {
+            gravity: Tuple::vector(0, -0.1, 0),
+            wind: Tuple::vector(-0.01, 0, 0),
+            drag_coefficient: 0.0,
+        }
+    }
+
+    /// Get the drag coefficient of the environment
+    pub fn get_drag_coefficient(&self) -> f64 {
+        self.drag_coefficient
+    }
+
+    /// Set the drag coefficient of the environment. Drag slows a
+    /// projectile down in proportion to its velocity, and is only
+    /// taken into account by [tick_dt].
+    ///
+    /// The drag coefficient must be a finite, non-negative number.
+    pub fn set_drag_coefficient(&mut self, drag: f64) -> Result<()> {
+        if !drag.is_finite() || drag < 0.0 {
+            return Err(Error::msg(
+                "The drag coefficient must be a finite, non-negative number",
+            ));
+        }
+
+        self.drag_coefficient = drag;
+        Ok(())
     }
 }
 
 impl Projectile {
-    pub fn new(position: Tuple, velocity: Tuple) -> Self {
-        Self { position, velocity }
+    /// Create a new [Projectile] at `position` moving with `velocity`
+    ///
+    /// Note: This returns a result because the position must be a
+    /// point and the velocity must be a vector.
+    pub fn new(position: Tuple, velocity: Tuple) -> Result<Self> {
+        if !position.is_a_point() || !velocity.is_a_vector() {
+            return Err(Error::msg(
+                "position must be a Point and velocity must be a Vector",
+            ));
+        }
+
+        Ok(Self { position, velocity })
+    }
+
+    /// Returns an iterator over the states of this projectile as it
+    /// travels through `env`, one [tick] at a time. The iterator starts
+    /// with the current state and ends once the projectile falls below
+    /// the ground (y < 0).
+    ///
+    /// The iterator never ends if the projectile never comes back
+    /// down, e.g. in an environment without gravity.
+    ///
+    /// ```
+    /// use raytracer::{spatial::Tuple, tick::{Environment, Projectile}};
+    ///
+    /// let env = Environment::default_earth();
+    /// let p = Projectile::new(Tuple::point(0, 1, 0), Tuple::vector(1, 1, 0)).unwrap();
+    ///
+    /// for state in p.trajectory(&env) {
+    ///     assert!(state.position.get_y() >= 0.0);
+    /// }
+    /// ```
+    pub fn trajectory(self, env: &Environment) -> Trajectory<'_> {
+        Trajectory {
+            env,
+            current: Some(self),
+        }
+    }
+}
+
+/// Iterator over the successive states of a [Projectile], created
+/// with [Projectile::trajectory]
+pub struct Trajectory<'a> {
+    env: &'a Environment,
+    current: Option<Projectile>,
+}
+
+impl Trajectory<'_> {
+    /// The highest y position reached along the trajectory
+    pub fn apex(self) -> f64 {
+        self.map(|p| p.position.get_y()).fold(f64::MIN, f64::max)
+    }
+
+    /// The horizontal (x) distance between the first state of the
+    /// trajectory and the last state before hitting the ground
+    pub fn range(mut self) -> f64 {
+        let start = match self.next() {
+            Some(p) => p.position.get_x(),
+            None => return 0.0,
+        };
+
+        let end = self.last().map_or(start, |p| p.position.get_x());
+        end - start
+    }
+}
+
+impl Iterator for Trajectory<'_> {
+    type Item = Projectile;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.current.take()?;
+
+        if current.position.get_y() < 0.0 {
+            return None;
+        }
+
+        self.current = Some(tick(self.env, current));
+        Some(current)
     }
 }
 
+/// Advances the projectile by `dt` units of time in the given
+/// environment, returning the projectile's new state.
+///
+/// This uses semi-implicit Euler integration: the velocity is updated
+/// first (including drag), and the new velocity moves the projectile.
+/// Smaller time steps give smoother and more accurate trajectories.
+pub fn tick_dt(env: &Environment, proj: Projectile, dt: f64) -> Projectile {
+    let acceleration = env.gravity + env.wind - proj.velocity * env.drag_coefficient;
+    let velocity = proj.velocity + acceleration * dt;
+
+    Projectile {
+        position: proj.position + velocity * dt,
+        velocity,
+    }
+}
+
+/// Advances the projectile by a single unit of time in the given
+/// environment, returning the projectile's new state.
+///
+/// This is the simple update from the book, which ignores drag and
+/// moves the projectile with its velocity from before the update.
 pub fn tick(env: &Environment, proj: Projectile) -> Projectile {
-    Projectile::new(
-        proj.position + proj.velocity,
-        proj.velocity + env.gravity + env.wind,
-    )
+    Projectile {
+        position: proj.position + proj.velocity,
+        velocity: proj.velocity + env.gravity + env.wind,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{tick, tick_dt, Environment, Projectile};
+    use crate::spatial::Tuple;
+    use anyhow::Result;
+    use std::f64::consts::FRAC_1_SQRT_2;
+
+    fn book_projectile() -> Result<Projectile> {
+        Projectile::new(Tuple::point(0, 1, 0), Tuple::vector(1, 1, 0).normalize())
+    }
+
+    #[test]
+    fn constructors_validate_their_tuples() {
+        assert!(Environment::new(Tuple::point(0, -0.1, 0), Tuple::vector(0, 0, 0)).is_err());
+        assert!(Environment::new(Tuple::vector(0, -0.1, 0), Tuple::point(0, 0, 0)).is_err());
+        assert!(Environment::new(Tuple::vector(0, -0.1, 0), Tuple::vector(0, 0, 0)).is_ok());
+
+        assert!(Projectile::new(Tuple::vector(0, 1, 0), Tuple::vector(1, 1, 0)).is_err());
+        assert!(Projectile::new(Tuple::point(0, 1, 0), Tuple::point(1, 1, 0)).is_err());
+    }
+
+    /// Runs the book projectile until it falls back below its starting
+    /// height, returning the highest point it reached and its range
+    fn simulate(env: &Environment, dt: f64) -> Result<(f64, f64)> {
+        let mut p = Projectile::new(Tuple::point(0, 0, 0), Tuple::vector(1, 1.8, 0).normalize())?;
+        let mut apex = 0.0_f64;
+
+        loop {
+            p = tick_dt(env, p, dt);
+            apex = apex.max(p.position.get_y());
+
+            if p.position.get_y() < 0.0 {
+                return Ok((apex, p.position.get_x()));
+            }
+        }
+    }
+
+    #[test]
+    fn trajectory_matches_the_tick_loop() -> Result<()> {
+        let env = Environment::default_earth();
+        let start = Projectile::new(
+            Tuple::point(0, 1, 0),
+            Tuple::vector(1, 1.8, 0).normalize() * 11.25,
+        )?;
+
+        let mut loop_count = 0;
+        let mut p = start;
+        while p.position.get_y() >= 0.0 {
+            loop_count += 1;
+            p = tick(&env, p);
+        }
+
+        assert_eq!(start.trajectory(&env).count(), loop_count);
+
+        Ok(())
+    }
+
+    #[test]
+    fn trajectory_apex_and_range() -> Result<()> {
+        let env = Environment::default_earth();
+        let start = Projectile::new(Tuple::point(0, 1, 0), Tuple::vector(1, 1, 0).normalize())?;
+
+        let apex = start.trajectory(&env).apex();
+        let range = start.trajectory(&env).range();
+
+        // starting at y = 1 and moving up at ~0.707 per tick under
+        // a gravity of 0.1, the apex is a bit above 3
+        assert!(apex > 3.0 && apex < 4.0);
+        assert!(range > 0.0);
+
+        // a projectile that starts below ground has no trajectory
+        let underground = Projectile::new(Tuple::point(0, -1, 0), Tuple::vector(1, 1, 0))?;
+        assert_eq!(underground.trajectory(&env).count(), 0);
+        assert_eq!(underground.trajectory(&env).range(), 0.0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn drag_coefficient_is_validated() {
+        let mut env = Environment::default_earth();
+        assert_eq!(env.get_drag_coefficient(), 0.0);
+
+        assert!(env.set_drag_coefficient(-0.1).is_err());
+        assert!(env.set_drag_coefficient(f64::INFINITY).is_err());
+        assert!(env.set_drag_coefficient(0.05).is_ok());
+        assert_eq!(env.get_drag_coefficient(), 0.05);
+    }
+
+    #[test]
+    fn smaller_time_steps_converge_on_the_apex() -> Result<()> {
+        let env = Environment::default_earth();
+
+        let (coarse, _) = simulate(&env, 0.5)?;
+        let (fine, _) = simulate(&env, 0.25)?;
+        let (finer, _) = simulate(&env, 0.125)?;
+
+        // the exact apex for this launch is vy² / 2g ≈ 3.82 units
+        assert!((fine - finer).abs() < (coarse - fine).abs());
+        assert!((finer - 3.82).abs() < 0.1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn drag_reduces_the_range() -> Result<()> {
+        let mut env = Environment::default_earth();
+        let (_, without_drag) = simulate(&env, 0.1)?;
+
+        env.set_drag_coefficient(0.05)?;
+        let (_, with_drag) = simulate(&env, 0.1)?;
+
+        assert!(with_drag < without_drag);
+
+        Ok(())
+    }
+
+    #[test]
+    fn position_after_one_tick() -> Result<()> {
+        let env = Environment::default_earth();
+        let p = tick(&env, book_projectile()?);
+
+        assert_eq!(
+            p.position,
+            Tuple::point(FRAC_1_SQRT_2, 1.0 + FRAC_1_SQRT_2, 0)
+        );
+        assert_eq!(
+            p.velocity,
+            Tuple::vector(FRAC_1_SQRT_2 - 0.01, FRAC_1_SQRT_2 - 0.1, 0)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn position_after_n_ticks() -> Result<()> {
+        let env = Environment::default_earth();
+        let mut p = book_projectile()?;
+
+        let n = 5;
+        for _ in 0..n {
+            p = tick(&env, p);
+        }
+
+        // p(n) = p(0) + n * v(0) + a * n * (n - 1) / 2
+        let n = n as f64;
+        let steps = n * (n - 1.0) / 2.0;
+        assert_eq!(
+            p.position,
+            Tuple::point(
+                n * FRAC_1_SQRT_2 - 0.01 * steps,
+                1.0 + n * FRAC_1_SQRT_2 - 0.1 * steps,
+                0
+            )
+        );
+
+        Ok(())
+    }
 }
@@ -1,10 +1,46 @@
-pub const EPSILON: f64 = 0.00001;
+/// Tolerance used when comparing floating point values for equality,
+/// e.g. in the [PartialEq] implementations of tuples, colors, and
+/// matrices. It is loose enough to match the truncated values used
+/// in the book's test cases.
+const TEST_EPSILON: f64 = 0.00001;
+
+/// Distance used to offset geometry to avoid self-intersection, like
+/// pushing the over point of a hit off the surface before casting
+/// shadow rays from it.
+pub const RENDER_EPSILON: f64 = 0.00001;
 
 /// Helper function to properly compare the equality
 /// of two 64-bit precision floating point numbers.
 ///
 /// This accounts for there being an error of at most
-/// [EPSILON] in difference between `a` and `b`
+/// [TEST_EPSILON] in difference between `a` and `b`
 pub fn float_equals(a: &f64, b: &f64) -> bool {
-    (a - b).abs() < EPSILON
+    float_equals_eps(a, b, TEST_EPSILON)
+}
+
+/// Same as [float_equals], but with the allowed error given as `eps`.
+/// This is useful for internal math that deserves a much tighter check
+/// than the book's test values allow.
+pub fn float_equals_eps(a: &f64, b: &f64, eps: f64) -> bool {
+    (a - b).abs() < eps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{float_equals, float_equals_eps};
+
+    #[test]
+    fn tight_epsilon_detects_small_differences() {
+        let a = 1.0;
+        let b = 1.0 + 1e-5;
+
+        assert!(float_equals_eps(&a, &b, 2e-4));
+        assert!(!float_equals_eps(&a, &b, 1e-9));
+    }
+
+    #[test]
+    fn default_comparison_allows_book_rounding() {
+        assert!(float_equals(&0.38066, &0.380661193));
+        assert!(!float_equals(&0.38066, &0.3807));
+    }
 }
@@ -1,3 +1,10 @@
+#[cfg(test)]
+pub(crate) mod alloc_counter;
 mod float_equals;
+pub mod rng;
 
-pub use float_equals::{float_equals, EPSILON};
+// only tests need a custom epsilon so far, but it is there for any
+// internal math that needs a tighter check than float_equals
+#[allow(unused_imports)]
+pub(crate) use float_equals::float_equals_eps;
+pub(crate) use float_equals::{float_equals, RENDER_EPSILON};
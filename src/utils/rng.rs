@@ -0,0 +1,434 @@
+use crate::spatial::Tuple;
+
+/// A source of sample values in \[0, 1) for the stochastic parts of
+/// rendering.
+///
+/// Purely random values are simple, but they clump together and leave
+/// gaps, which shows up as noise. Other samplers spread their values
+/// out more evenly, so fewer samples are needed for the same quality.
+pub trait Sampler {
+    /// Returns the next value in the range \[0, 1)
+    fn next_1d(&mut self) -> f64;
+
+    /// Returns the next point in the unit square \[0, 1) x \[0, 1)
+    fn next_2d(&mut self) -> (f64, f64) {
+        (self.next_1d(), self.next_1d())
+    }
+
+    /// Returns a new sampler of the same kind whose values are
+    /// unrelated to this one's, for example to give each pixel its own
+    /// sampler. Forks with the same offset produce the same values.
+    fn fork(&self, seed_offset: u64) -> Self
+    where
+        Self: Sized;
+
+    /// Returns a point (x, y) inside the unit disk, useful for
+    /// sampling a lens or an area light
+    fn in_unit_disk(&mut self) -> (f64, f64) {
+        loop {
+            let (x, y) = self.next_2d();
+            let (x, y) = (2.0 * x - 1.0, 2.0 * y - 1.0);
+
+            if x * x + y * y < 1.0 {
+                return (x, y);
+            }
+        }
+    }
+
+    /// Returns a unit vector on the hemisphere around `normal`
+    fn on_hemisphere(&mut self, normal: &Tuple) -> Tuple {
+        let direction = loop {
+            let v = Tuple::vector(
+                2.0 * self.next_1d() - 1.0,
+                2.0 * self.next_1d() - 1.0,
+                2.0 * self.next_1d() - 1.0,
+            );
+
+            let length_squared = v.dot(&v);
+            if length_squared > 1e-12 && length_squared <= 1.0 {
+                break v.normalize();
+            }
+        };
+
+        if direction.dot(normal) < 0.0 {
+            -direction
+        } else {
+            direction
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A small, seedable random number generator (PCG32) for the
+/// stochastic parts of rendering.
+///
+/// Two samplers created with the same seed always produce the same
+/// sequence of values, so renders that use randomness are still
+/// reproducible.
+///
+/// ```
+/// use raytracer::utils::rng::RandomSampler;
+///
+/// let mut a = RandomSampler::new(42);
+/// let mut b = RandomSampler::new(42);
+///
+/// let x = a.next_f64();
+/// assert!((0.0..1.0).contains(&x));
+/// assert_eq!(x, b.next_f64());
+/// ```
+pub struct RandomSampler {
+    seed: u64,
+    stream: u64,
+    state: u64,
+    increment: u64,
+}
+
+impl RandomSampler {
+    const MULTIPLIER: u64 = 6364136223846793005;
+
+    /// Create a new sampler from a seed
+    pub fn new(seed: u64) -> Self {
+        Self::with_stream(seed, 0)
+    }
+
+    /// Create a new sampler from a seed and a stream number. Samplers
+    /// with the same seed but different streams produce unrelated
+    /// sequences, which is useful to give each pixel its own sampler.
+    pub fn with_stream(seed: u64, stream: u64) -> Self {
+        let mut sampler = Self {
+            seed,
+            stream,
+            state: 0,
+            increment: (stream << 1) | 1,
+        };
+        sampler.next_u32();
+        sampler.state = sampler.state.wrapping_add(seed);
+        sampler.next_u32();
+        sampler
+    }
+
+    /// Returns the next random 32-bit value
+    pub fn next_u32(&mut self) -> u32 {
+        let old = self.state;
+        self.state = old
+            .wrapping_mul(Self::MULTIPLIER)
+            .wrapping_add(self.increment);
+
+        let xorshifted = (((old >> 18) ^ old) >> 27) as u32;
+        let rotation = (old >> 59) as u32;
+        xorshifted.rotate_right(rotation)
+    }
+
+    /// Returns a random value in the range \[0, 1)
+    pub fn next_f64(&mut self) -> f64 {
+        let bits = ((self.next_u32() as u64) << 32) | self.next_u32() as u64;
+        (bits >> 11) as f64 / (1_u64 << 53) as f64
+    }
+}
+
+impl Sampler for RandomSampler {
+    fn next_1d(&mut self) -> f64 {
+        self.next_f64()
+    }
+
+    fn fork(&self, seed_offset: u64) -> Self {
+        Self::with_stream(self.seed, self.stream.wrapping_add(seed_offset))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A sampler that splits the unit square into a grid and places one 2D
+/// sample at a random spot in each cell, so the samples of a pixel
+/// can't all bunch up in one corner.
+///
+/// `samples` values from [Sampler::next_2d] cover the grid once, with
+/// the largest square number of them that fits in `samples` stratified,
+/// and any left over spread over the whole square, which keeps their
+/// average unbiased. 1D values are plain random numbers.
+///
+/// ```
+/// use raytracer::utils::rng::{Sampler, StratifiedSampler};
+///
+/// let mut s = StratifiedSampler::new(4, 0);
+/// let mut quadrants: Vec<_> = (0..4)
+///     .map(|_| {
+///         let (x, y) = s.next_2d();
+///         (x < 0.5, y < 0.5)
+///     })
+///     .collect();
+/// quadrants.sort();
+/// quadrants.dedup();
+///
+/// assert_eq!(quadrants.len(), 4);
+/// ```
+pub struct StratifiedSampler {
+    samples: usize,
+    side: usize,
+    index: usize,
+    random: RandomSampler,
+}
+
+impl StratifiedSampler {
+    /// Create a new sampler for `samples` 2D samples at a time, which
+    /// is raised to at least 1, with its random jitter seeded by `seed`
+    pub fn new(samples: usize, seed: u64) -> Self {
+        let samples = samples.max(1);
+        Self {
+            samples,
+            side: samples.isqrt(),
+            index: 0,
+            random: RandomSampler::new(seed),
+        }
+    }
+}
+
+impl Sampler for StratifiedSampler {
+    fn next_1d(&mut self) -> f64 {
+        self.random.next_f64()
+    }
+
+    fn next_2d(&mut self) -> (f64, f64) {
+        let i = self.index % self.samples;
+        self.index += 1;
+
+        let (jitter_x, jitter_y) = (self.random.next_f64(), self.random.next_f64());
+        if i >= self.side * self.side {
+            return (jitter_x, jitter_y);
+        }
+
+        let side = self.side as f64;
+        (
+            ((i % self.side) as f64 + jitter_x) / side,
+            ((i / self.side) as f64 + jitter_y) / side,
+        )
+    }
+
+    fn fork(&self, seed_offset: u64) -> Self {
+        Self {
+            index: 0,
+            random: self.random.fork(seed_offset),
+            ..*self
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A sampler whose 2D samples follow the Halton sequence in bases 2
+/// and 3, which fills the unit square evenly at any number of samples.
+/// 1D values are plain random numbers.
+///
+/// Forks shift the whole sequence by a random amount (wrapping around
+/// the square), so every pixel sees an evenly spread set of samples
+/// without repeating the same pattern.
+///
+/// ```
+/// use raytracer::utils::rng::{HaltonSampler, Sampler};
+///
+/// let mut s = HaltonSampler::new(0);
+///
+/// assert_eq!(s.next_2d(), (1.0 / 2.0, 1.0 / 3.0));
+/// assert_eq!(s.next_2d(), (1.0 / 4.0, 2.0 / 3.0));
+/// ```
+pub struct HaltonSampler {
+    index: u64,
+    shift: (f64, f64),
+    random: RandomSampler,
+}
+
+impl HaltonSampler {
+    /// Create a new sampler, starting at the beginning of the Halton
+    /// sequence, with its 1D values and forks seeded by `seed`
+    pub fn new(seed: u64) -> Self {
+        Self {
+            index: 1,
+            shift: (0.0, 0.0),
+            random: RandomSampler::new(seed),
+        }
+    }
+}
+
+/// The `index`-th value of the van der Corput sequence in `base`,
+/// which mirrors the digits of `index` around the radix point
+fn radical_inverse(mut index: u64, base: u64) -> f64 {
+    let mut result = 0.0;
+    let mut scale = 1.0 / base as f64;
+
+    while index > 0 {
+        result += (index % base) as f64 * scale;
+        index /= base;
+        scale /= base as f64;
+    }
+
+    result
+}
+
+impl Sampler for HaltonSampler {
+    fn next_1d(&mut self) -> f64 {
+        self.random.next_f64()
+    }
+
+    fn next_2d(&mut self) -> (f64, f64) {
+        let x = radical_inverse(self.index, 2) + self.shift.0;
+        let y = radical_inverse(self.index, 3) + self.shift.1;
+        self.index += 1;
+
+        (x.fract(), y.fract())
+    }
+
+    fn fork(&self, seed_offset: u64) -> Self {
+        let mut random = self.random.fork(seed_offset);
+        Self {
+            index: 1,
+            shift: (random.next_f64(), random.next_f64()),
+            random,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// The kind of [Sampler] a camera uses for the stochastic parts of a
+/// render
+pub enum SamplerKind {
+    /// A [RandomSampler]
+    #[default]
+    Random,
+    /// A [StratifiedSampler] over the samples of each pixel
+    Stratified,
+    /// A [HaltonSampler]
+    Halton,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{radical_inverse, HaltonSampler, RandomSampler, Sampler, StratifiedSampler};
+    use crate::{spatial::Tuple, utils::float_equals};
+
+    #[test]
+    fn same_seed_gives_identical_sequences() {
+        let mut a = RandomSampler::new(7);
+        let mut b = RandomSampler::new(7);
+
+        for _ in 0..100 {
+            assert_eq!(a.next_u32(), b.next_u32());
+            assert_eq!(a.next_f64(), b.next_f64());
+        }
+    }
+
+    #[test]
+    fn different_seeds_and_streams_differ() {
+        let sequence = |mut s: RandomSampler| (0..10).map(|_| s.next_u32()).collect::<Vec<_>>();
+
+        assert_ne!(
+            sequence(RandomSampler::new(1)),
+            sequence(RandomSampler::new(2))
+        );
+        assert_ne!(
+            sequence(RandomSampler::with_stream(1, 0)),
+            sequence(RandomSampler::with_stream(1, 1))
+        );
+    }
+
+    #[test]
+    fn values_fall_in_their_ranges() {
+        let mut s = RandomSampler::new(3);
+        let normal = Tuple::vector(0, 1, 0);
+
+        for _ in 0..1000 {
+            assert!((0.0..1.0).contains(&s.next_f64()));
+
+            let (x, y) = s.in_unit_disk();
+            assert!(x * x + y * y < 1.0);
+
+            let v = s.on_hemisphere(&normal);
+            assert!(v.is_a_vector());
+            assert!(float_equals(&v.magnitude(), &1.0));
+            assert!(v.dot(&normal) >= 0.0);
+        }
+    }
+
+    #[test]
+    fn values_are_spread_out() {
+        let mut s = RandomSampler::new(11);
+        let mean = (0..10_000).map(|_| s.next_f64()).sum::<f64>() / 10_000.0;
+
+        assert!((mean - 0.5).abs() < 0.02);
+    }
+
+    #[test]
+    fn forks_are_reproducible_and_independent() {
+        let base = RandomSampler::new(5);
+        let sequence = |mut s: RandomSampler| (0..10).map(|_| s.next_1d()).collect::<Vec<_>>();
+
+        assert_eq!(
+            sequence(base.fork(3)),
+            sequence(RandomSampler::with_stream(5, 3))
+        );
+        assert_ne!(sequence(base.fork(3)), sequence(base.fork(4)));
+    }
+
+    #[test]
+    fn stratified_samples_land_one_per_cell() {
+        let mut s = StratifiedSampler::new(9, 1).fork(12);
+
+        for _round in 0..3 {
+            let mut cells: Vec<_> = (0..9)
+                .map(|_| {
+                    let (x, y) = s.next_2d();
+                    ((x * 3.0) as usize, (y * 3.0) as usize)
+                })
+                .collect();
+            cells.sort();
+            cells.dedup();
+
+            assert_eq!(cells.len(), 9);
+        }
+    }
+
+    #[test]
+    fn leftover_stratified_samples_cover_the_whole_square() {
+        let mut s = StratifiedSampler::new(5, 2);
+        let mut leftover = vec![];
+
+        for _ in 0..200 {
+            for i in 0..5 {
+                let sample = s.next_2d();
+                if i == 4 {
+                    leftover.push(sample);
+                }
+            }
+        }
+
+        assert!(leftover.iter().any(|&(x, y)| x < 0.5 && y < 0.5));
+        assert!(leftover.iter().any(|&(x, y)| x >= 0.5 && y >= 0.5));
+    }
+
+    #[test]
+    fn halton_follows_the_known_sequence() {
+        let mut s = HaltonSampler::new(0);
+        let expected = [
+            (1.0 / 2.0, 1.0 / 3.0),
+            (1.0 / 4.0, 2.0 / 3.0),
+            (3.0 / 4.0, 1.0 / 9.0),
+            (1.0 / 8.0, 4.0 / 9.0),
+            (5.0 / 8.0, 7.0 / 9.0),
+        ];
+
+        for (x, y) in expected {
+            let (sx, sy) = s.next_2d();
+            assert!(float_equals(&sx, &x) && float_equals(&sy, &y));
+        }
+
+        assert!(float_equals(&radical_inverse(6, 2), &0.375));
+    }
+
+    #[test]
+    fn forked_halton_samples_stay_in_the_unit_square() {
+        let mut s = HaltonSampler::new(0).fork(7);
+        assert_ne!(s.next_2d(), (0.5, 1.0 / 3.0));
+
+        for _ in 0..100 {
+            let (x, y) = s.next_2d();
+            assert!((0.0..1.0).contains(&x) && (0.0..1.0).contains(&y));
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use crate::{canvas::Canvas, color::Color, spatial::Tuple};
+use std::f64::consts::PI;
+
+#[derive(Debug, Clone, PartialEq)]
+/// What a ray that misses every object in the world sees
+pub enum Background {
+    /// The same color in every direction
+    Solid(Color),
+    /// A vertical gradient from `horizon` to `zenith` (straight up).
+    /// Below the horizon it fades to `ground` (straight down) when a
+    /// ground color is given, and stays at `horizon` otherwise.
+    SkyGradient {
+        horizon: Color,
+        zenith: Color,
+        ground: Option<Color>,
+    },
+    /// An equirectangular panorama. The center of the image looks
+    /// toward +x, the left and right edges meet behind the -x axis, and
+    /// the top and bottom rows are straight up and down.
+    Environment(Canvas),
+}
+
+impl Background {
+    /// Finds the color seen in the given direction
+    pub fn color_toward(&self, direction: &Tuple) -> Color {
+        let direction = direction.normalize();
+
+        match self {
+            Background::Solid(color) => *color,
+            Background::SkyGradient {
+                horizon,
+                zenith,
+                ground,
+            } => {
+                let y = direction.get_y().clamp(-1.0, 1.0);
+
+                match ground {
+                    Some(ground) if y < 0.0 => lerp(horizon, ground, -y),
+                    _ => lerp(horizon, zenith, y.max(0.0)),
+                }
+            }
+            Background::Environment(environment) => {
+                let longitude = direction.get_z().atan2(direction.get_x());
+                let latitude = direction.get_y().clamp(-1.0, 1.0).asin();
+
+                let u = 0.5 + longitude / (2.0 * PI);
+                let v = 0.5 - latitude / PI;
+
+                environment.sample_bilinear(u, v)
+            }
+        }
+    }
+}
+
+impl Default for Background {
+    fn default() -> Self {
+        Background::Solid(Color::black())
+    }
+}
+
+/// Blends from `from` (t = 0) to `to` (t = 1)
+fn lerp(from: &Color, to: &Color, t: f64) -> Color {
+    *from * (1.0 - t) + *to * t
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Background;
+    use crate::{color::Color, spatial::Tuple};
+
+    fn sky(ground: Option<Color>) -> Background {
+        Background::SkyGradient {
+            horizon: Color::new(1, 1, 1),
+            zenith: Color::new(0.2, 0.4, 1),
+            ground,
+        }
+    }
+
+    #[test]
+    fn the_default_background_is_black() {
+        let background = Background::default();
+
+        for direction in [Tuple::vector(0, 1, 0), Tuple::vector(1, -1, 3)] {
+            assert_eq!(background.color_toward(&direction), Color::black());
+        }
+    }
+
+    #[test]
+    fn sky_gradient_blends_from_the_horizon_to_the_zenith() {
+        let background = sky(Some(Color::new(0.3, 0.2, 0.1)));
+
+        assert_eq!(
+            background.color_toward(&Tuple::vector(0, 10, 0)),
+            Color::new(0.2, 0.4, 1)
+        );
+        assert_eq!(
+            background.color_toward(&Tuple::vector(1, 0, 1)),
+            Color::new(1, 1, 1)
+        );
+        assert_eq!(
+            background.color_toward(&Tuple::vector(0, -1, 0)),
+            Color::new(0.3, 0.2, 0.1)
+        );
+
+        // 30 degrees above the horizon is halfway there
+        let up_30 = Tuple::vector(3_f64.sqrt(), 1, 0);
+        assert_eq!(background.color_toward(&up_30), Color::new(0.6, 0.7, 1));
+    }
+
+    #[test]
+    fn sky_gradient_without_ground_keeps_the_horizon_below() {
+        let background = sky(None);
+
+        assert_eq!(
+            background.color_toward(&Tuple::vector(0, -1, 0)),
+            Color::new(1, 1, 1)
+        );
+    }
+}
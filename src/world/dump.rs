@@ -0,0 +1,210 @@
+use super::{RayTraceNode, World};
+use crate::camera::Camera;
+use anyhow::Result;
+use std::{borrow::Cow, io};
+
+/// The columns written by the CSV dumps, in order
+const CSV_HEADER: [&str; 15] = [
+    "x",
+    "y",
+    "depth",
+    "object_id",
+    "t",
+    "point_x",
+    "point_y",
+    "point_z",
+    "normal_x",
+    "normal_y",
+    "normal_z",
+    "shadowed",
+    "red",
+    "green",
+    "blue",
+];
+
+impl World {
+    /// Traces the ray through the pixel (x, y) of the camera, like
+    /// [World::trace_pixel], and writes what it found as CSV, for
+    /// analysis in other tools.
+    ///
+    /// After a header row, there is a row for every ray in the trace,
+    /// with its depth in the tree of rays (0 for the camera ray). The
+    /// hit columns are empty for rays that miss everything.
+    pub fn dump_pixel_csv(
+        &self,
+        camera: &Camera,
+        x: usize,
+        y: usize,
+        mut writer: impl io::Write,
+    ) -> Result<()> {
+        write_csv_row(&mut writer, CSV_HEADER)?;
+        let trace = self.trace_pixel(camera, x, y)?;
+        self.write_trace(&mut writer, (x, y), &trace, 0, usize::MAX)
+    }
+
+    /// Same as [World::dump_pixel_csv], for every pixel of the camera
+    /// row by row, but only with the camera rays' first hits
+    pub fn dump_first_hits_csv(&self, camera: &Camera, mut writer: impl io::Write) -> Result<()> {
+        write_csv_row(&mut writer, CSV_HEADER)?;
+        for y in 0..camera.get_vsize() {
+            for x in 0..camera.get_hsize() {
+                let trace = self.trace_pixel(camera, x, y)?;
+                self.write_trace(&mut writer, (x, y), &trace, 0, 0)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes a row for the node, and for its children up to `max_depth`
+    fn write_trace(
+        &self,
+        writer: &mut impl io::Write,
+        (x, y): (usize, usize),
+        node: &RayTraceNode,
+        depth: usize,
+        max_depth: usize,
+    ) -> Result<()> {
+        let mut row = vec![x.to_string(), y.to_string(), depth.to_string()];
+
+        match &node.hit {
+            Some(hit) => row.extend([
+                hit.object_id.to_string(),
+                hit.t.to_string(),
+                hit.point.get_x().to_string(),
+                hit.point.get_y().to_string(),
+                hit.point.get_z().to_string(),
+                hit.normalv.get_x().to_string(),
+                hit.normalv.get_y().to_string(),
+                hit.normalv.get_z().to_string(),
+                hit.in_shadow.to_string(),
+            ]),
+            None => row.extend(std::iter::repeat_n(String::new(), 9)),
+        }
+
+        row.extend([
+            node.color.red.to_string(),
+            node.color.green.to_string(),
+            node.color.blue.to_string(),
+        ]);
+        write_csv_row(writer, &row)?;
+
+        if depth < max_depth {
+            for child in &node.children {
+                self.write_trace(writer, (x, y), child, depth + 1, max_depth)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes the fields as a single line of CSV
+fn write_csv_row<S: AsRef<str>>(
+    writer: &mut impl io::Write,
+    fields: impl IntoIterator<Item = S>,
+) -> Result<()> {
+    let fields: Vec<String> = fields
+        .into_iter()
+        .map(|f| escape_csv(f.as_ref()).into_owned())
+        .collect();
+    writeln!(writer, "{}", fields.join(","))?;
+    Ok(())
+}
+
+/// Quotes a field if it contains a comma, a quote or a line break, and
+/// doubles any quotes inside it
+fn escape_csv(field: &str) -> Cow<'_, str> {
+    if field.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(field)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{escape_csv, CSV_HEADER};
+    use crate::{
+        camera::Camera,
+        matrix::view_transform,
+        spatial::{Point, Vector},
+        world::World,
+    };
+    use anyhow::Result;
+    use std::f64::consts::PI;
+
+    fn camera() -> Result<Camera> {
+        let mut c = Camera::new(3, 3, PI / 2.0)?;
+        c.set_transform(view_transform(
+            &Point::new(0, 0, -5),
+            &Point::new(0, 0, 0),
+            &Vector::new(0, 1, 0),
+        ));
+        Ok(c)
+    }
+
+    fn parse(csv: &[u8]) -> Vec<Vec<String>> {
+        String::from_utf8_lossy(csv)
+            .lines()
+            .map(|line| line.split(',').map(String::from).collect())
+            .collect()
+    }
+
+    #[test]
+    fn first_hits_have_a_row_per_pixel() -> Result<()> {
+        let w = World::default();
+        let mut csv = vec![];
+        w.dump_first_hits_csv(&camera()?, &mut csv)?;
+
+        let rows = parse(&csv);
+        assert_eq!(rows[0], CSV_HEADER);
+        assert_eq!(rows.len(), 1 + 9);
+        assert!(rows.iter().all(|row| row.len() == CSV_HEADER.len()));
+
+        // the center pixel hits the outer sphere straight on
+        let center = &rows[1 + 4];
+        assert_eq!(center[..3], ["1", "1", "0"]);
+        assert_eq!(center[3], w.objects[0].id().to_string());
+        assert_eq!(center[4].parse::<f64>()?, 4.0);
+        assert_eq!(center[11], "false");
+
+        Ok(())
+    }
+
+    #[test]
+    fn a_pixel_dump_matches_its_row_in_the_full_dump() -> Result<()> {
+        let w = World::default();
+        let (mut pixel, mut full) = (vec![], vec![]);
+        w.dump_pixel_csv(&camera()?, 2, 0, &mut pixel)?;
+        w.dump_first_hits_csv(&camera()?, &mut full)?;
+
+        let pixel = parse(&pixel);
+        assert_eq!(pixel.len(), 2);
+        assert_eq!(pixel[1], parse(&full)[1 + 2]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn misses_leave_the_hit_columns_empty() -> Result<()> {
+        let mut w = World::default();
+        w.objects.clear();
+        let mut csv = vec![];
+        w.dump_pixel_csv(&camera()?, 0, 0, &mut csv)?;
+
+        assert_eq!(
+            String::from_utf8(csv)?.lines().nth(1),
+            Some("0,0,0,,,,,,,,,,0,0,0")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn fields_with_separators_are_quoted() {
+        assert_eq!(escape_csv("1.5"), "1.5");
+        assert_eq!(escape_csv("a,b"), "\"a,b\"");
+        assert_eq!(escape_csv("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(escape_csv("two\nlines"), "\"two\nlines\"");
+    }
+}
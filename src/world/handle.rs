@@ -0,0 +1,233 @@
+use super::World;
+use crate::shapes::Object;
+use std::ops::{Index, IndexMut};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Refers to an object in a [World], as returned by
+/// [World::add_object].
+///
+/// Every handle is issued by the world, so adding the same shape twice
+/// gives two handles, one for each copy. Unlike a position in
+/// [World::objects], a handle keeps referring to the same object when
+/// other objects are removed. Once its own object is removed the handle
+/// is stale: [World::get_object] returns `None` for it and indexing the
+/// world with it panics.
+pub struct ObjectHandle(u64);
+
+impl World {
+    /// Adds the object to the end of [World::objects] and issues a
+    /// handle for it
+    pub(super) fn push_object(&mut self, obj: Object) -> ObjectHandle {
+        self.sync_handles();
+        self.objects.push(obj);
+
+        let handle = self.new_handle();
+        self.handles.push((handle, obj.id()));
+        handle
+    }
+
+    /// A handle that no other object of this world ever had
+    fn new_handle(&mut self) -> ObjectHandle {
+        self.next_handle += 1;
+        ObjectHandle(self.next_handle - 1)
+    }
+
+    /// Matches the handles up with the objects again after
+    /// [World::objects] was changed directly. Objects that are still
+    /// there keep their handles, and objects added without
+    /// [World::add_object] get new ones.
+    fn sync_handles(&mut self) {
+        let in_line = self.handles.len() == self.objects.len()
+            && self
+                .handles
+                .iter()
+                .zip(&self.objects)
+                .all(|((_, id), o)| *id == o.id());
+        if in_line {
+            return;
+        }
+
+        let mut old = std::mem::take(&mut self.handles);
+        for i in 0..self.objects.len() {
+            let id = self.objects[i].id();
+            let handle = match old.iter().position(|(_, old_id)| *old_id == id) {
+                Some(j) => old.remove(j).0,
+                None => self.new_handle(),
+            };
+            self.handles.push((handle, id));
+        }
+    }
+
+    /// Finds the position of the object with this handle
+    fn position_of(&self, handle: ObjectHandle) -> Option<usize> {
+        let (i, (_, id)) = self
+            .handles
+            .iter()
+            .enumerate()
+            .find(|(_, (h, _))| *h == handle)?;
+
+        // objects changed directly may have moved out from under it
+        self.objects.get(i).filter(|o| o.id() == *id).map(|_| i)
+    }
+
+    /// Get the object that this handle refers to, if it is still in
+    /// the world
+    pub fn get_object(&self, handle: ObjectHandle) -> Option<&Object> {
+        self.position_of(handle).map(|i| &self.objects[i])
+    }
+
+    /// Get the object that this handle refers to for changing it in
+    /// place, if it is still in the world
+    pub fn get_object_mut(&mut self, handle: ObjectHandle) -> Option<&mut Object> {
+        self.sync_handles();
+        self.position_of(handle).map(|i| &mut self.objects[i])
+    }
+
+    /// Removes the object that this handle refers to from the world and
+    /// returns it. The handles of every other object stay valid.
+    pub fn remove_object(&mut self, handle: ObjectHandle) -> Option<Object> {
+        self.sync_handles();
+
+        let i = self.position_of(handle)?;
+        self.handles.remove(i);
+        Some(self.objects.remove(i))
+    }
+}
+
+impl Index<usize> for World {
+    type Output = Object;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.objects[index]
+    }
+}
+
+impl IndexMut<usize> for World {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.objects[index]
+    }
+}
+
+impl Index<ObjectHandle> for World {
+    type Output = Object;
+
+    fn index(&self, handle: ObjectHandle) -> &Self::Output {
+        self.get_object(handle)
+            .unwrap_or_else(|| panic!("{:?} does not refer to an object in this world", handle))
+    }
+}
+
+impl IndexMut<ObjectHandle> for World {
+    fn index_mut(&mut self, handle: ObjectHandle) -> &mut Self::Output {
+        self.get_object_mut(handle)
+            .unwrap_or_else(|| panic!("{:?} does not refer to an object in this world", handle))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        color::Color,
+        lights::Material,
+        matrix::translation,
+        shapes::{Object, Sphere},
+        world::World,
+    };
+
+    fn sphere(x: f64) -> Object {
+        Object::Sphere(Sphere::new(translation(x, 0, 0), Material::default()).unwrap())
+    }
+
+    #[test]
+    fn worlds_can_be_indexed_by_position() {
+        let mut w = World::default();
+        assert_eq!(w[1], w.objects[1]);
+
+        w[0].material_mut().set_color(Color::red());
+        assert_eq!(w.objects[0].get_material().get_color(), Color::red());
+    }
+
+    #[test]
+    fn handles_refer_to_the_object_they_were_returned_for() {
+        let mut w = World::empty();
+        let a = sphere(1.0);
+        let handle = w.add_object(a);
+
+        assert_eq!(w[handle], a);
+
+        w[handle].material_mut().set_color(Color::red());
+        assert_eq!(w.objects[0].get_material().get_color(), Color::red());
+    }
+
+    #[test]
+    fn adding_the_same_shape_twice_gives_two_handles() {
+        let mut w = World::empty();
+        let s = Sphere::default();
+        let first = w.add_object(s);
+        let second = w.add_object(s);
+        assert_ne!(first, second);
+
+        w[second].material_mut().set_color(Color::red());
+        assert_ne!(w.objects[0].get_material().get_color(), Color::red());
+        assert_eq!(w.objects[1].get_material().get_color(), Color::red());
+
+        w.remove_object(first);
+        assert_eq!(w.object_count(), 1);
+        assert_eq!(w[second].get_material().get_color(), Color::red());
+        assert!(w.get_object(first).is_none());
+    }
+
+    #[test]
+    fn handles_follow_objects_changed_directly() {
+        let mut w = World::default();
+        let kept = w.objects[1];
+        w.objects.remove(0);
+
+        let s = sphere(1.0);
+        let added = w.add_object(s);
+        assert_eq!(w[added], s);
+
+        let copy = w.add_object(kept);
+        assert_eq!(w.object_count(), 3);
+        assert_eq!(w.remove_object(copy), Some(kept));
+        assert_eq!(w.objects, vec![kept, s]);
+        assert_eq!(w[added], s);
+    }
+
+    #[test]
+    fn handles_survive_removing_earlier_objects() {
+        let mut w = World::empty();
+        let first = w.add_object(sphere(1.0));
+        let second = w.add_object(sphere(2.0));
+        let third = w.add_object(sphere(3.0));
+
+        let removed = w.remove_object(first);
+        assert!(removed.is_some());
+        assert_eq!(w.object_count(), 2);
+
+        // the positions shifted, the handles didn't
+        assert_eq!(w[second], w[0]);
+        assert_eq!(w[third], w[1]);
+    }
+
+    #[test]
+    fn stale_handles_find_nothing() {
+        let mut w = World::empty();
+        let handle = w.add_object(sphere(1.0));
+        w.remove_object(handle);
+
+        assert!(w.get_object(handle).is_none());
+        assert!(w.get_object_mut(handle).is_none());
+        assert!(w.remove_object(handle).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "does not refer to an object")]
+    fn indexing_with_a_stale_handle_panics() {
+        let mut w = World::empty();
+        let handle = w.add_object(sphere(1.0));
+        w.remove_object(handle);
+
+        let _ = &w[handle];
+    }
+}
@@ -1,18 +1,43 @@
 use crate::{
+    canvas::Canvas,
     color::Color,
-    intersections::{hit, Computations, Intersect, Intersection, Object, Ray, Sphere},
+    intersections::{hit, hit_for_shadow, Computations, Intersections, Ray, ShadowResult},
     lights::{lighting, PointLight},
     matrix::scaling,
-    spatial::Tuple,
+    shapes::{Intersect, Object, Sphere},
+    spatial::{Point, Tuple},
+    utils::RENDER_EPSILON,
 };
-use anyhow::Result;
-
-#[derive(Debug, Clone, PartialEq)]
+use anyhow::{Error, Result};
+use uuid::Uuid;
+
+mod background;
+mod dump;
+mod handle;
+mod pathtrace;
+mod summary;
+mod trace;
+mod volume;
+
+pub use background::Background;
+pub use handle::ObjectHandle;
+pub use summary::WorldSummary;
+pub use trace::{RayTraceNode, TracedHit};
+
+#[derive(Clone, PartialEq)]
 /// Data structure representing the world that contains
 /// objects and a light source
 pub struct World {
     pub light: Option<PointLight>,
     pub objects: Vec<Object>,
+    bias: f64,
+    background: Background,
+    bounds_pretest: bool,
+    clamp_output: Option<(f64, f64)>,
+    /// The handle and the id of every object, in the same order as
+    /// `objects`
+    handles: Vec<(ObjectHandle, Uuid)>,
+    next_handle: u64,
 }
 
 impl World {
@@ -26,6 +51,12 @@ impl World {
         Self {
             light: None,
             objects: vec![],
+            bias: RENDER_EPSILON,
+            background: Background::default(),
+            bounds_pretest: true,
+            clamp_output: None,
+            handles: vec![],
+            next_handle: 0,
         }
     }
 
@@ -39,9 +70,116 @@ impl World {
         self.light = light;
     }
 
-    /// Add an object to the world
-    pub fn add_object(&mut self, obj: Object) {
-        self.objects.push(obj);
+    /// Get the distance that hit points are pushed off of surfaces
+    /// before casting shadow rays from them
+    pub fn get_bias(&self) -> f64 {
+        self.bias
+    }
+
+    /// Set the distance that hit points are pushed off of surfaces
+    /// before casting shadow rays from them. The bias is scaled up for
+    /// points far from the origin to avoid speckled self-shadowing
+    /// ("shadow acne") in huge scenes, while tiny scenes may need a
+    /// smaller one.
+    ///
+    /// The bias must be a finite, non-negative number.
+    pub fn set_bias(&mut self, bias: f64) -> Result<()> {
+        if !bias.is_finite() || bias < 0.0 {
+            return Err(Error::msg("The bias must be a finite, non-negative number"));
+        }
+
+        self.bias = bias;
+        Ok(())
+    }
+
+    /// Returns true if rays are checked against the bounding sphere of
+    /// each object before being intersected with the object itself
+    pub fn get_bounds_pretest(&self) -> bool {
+        self.bounds_pretest
+    }
+
+    /// Sets whether rays are checked against the bounding sphere of
+    /// each object before being intersected with the object itself.
+    /// The check is much cheaper than a full intersection, which needs
+    /// the inverse of the object's transform. It is on by default, and
+    /// never changes which intersections are found.
+    pub fn set_bounds_pretest(&mut self, enabled: bool) {
+        self.bounds_pretest = enabled;
+    }
+
+    /// Get the range that the colors returned by [World::color_at] are
+    /// clamped to, if any
+    pub fn get_clamp_output(&self) -> Option<(f64, f64)> {
+        self.clamp_output
+    }
+
+    /// Sets a range (min, max) to clamp every channel of the colors
+    /// returned by [World::color_at] to, or `None` to leave them as they
+    /// are, which is the default.
+    ///
+    /// Bright lights and emissive materials easily push colors past 1.
+    /// Image files clamp them anyway, but code that compares colors,
+    /// like a canvas diff, sees the raw values. Only the final color is
+    /// clamped, so light bouncing between surfaces keeps its energy.
+    /// Renders, traces and [World::color_at_pathtraced] are clamped too.
+    pub fn set_clamp_output(&mut self, range: Option<(f64, f64)>) -> Result<()> {
+        if let Some((min, max)) = range {
+            if !(min.is_finite() && max.is_finite() && min <= max) {
+                return Err(Error::msg(
+                    "The clamping range must be finite, with its minimum at most its maximum",
+                ));
+            }
+        }
+
+        self.clamp_output = range;
+        Ok(())
+    }
+
+    /// Clamps a final color to the output range of the world, if it has
+    /// one
+    pub(crate) fn clamp_output_color(&self, color: Color) -> Color {
+        match self.clamp_output {
+            Some((min, max)) => color.clamp(min, max),
+            None => color,
+        }
+    }
+
+    /// Get what rays that miss every object see
+    pub fn get_background(&self) -> &Background {
+        &self.background
+    }
+
+    /// Set what rays that miss every object see, which is solid
+    /// black by default
+    pub fn set_background(&mut self, background: Background) {
+        self.background = background;
+    }
+
+    /// Get the environment map seen by rays that miss every object,
+    /// if the background is one
+    pub fn get_environment(&self) -> Option<&Canvas> {
+        match &self.background {
+            Background::Environment(environment) => Some(environment),
+            _ => None,
+        }
+    }
+
+    /// Surround the world with an equirectangular panorama, which is
+    /// seen by rays that miss every object instead of black. This is
+    /// the same as setting a [Background::Environment].
+    pub fn set_environment(&mut self, environment: Canvas) {
+        self.background = Background::Environment(environment);
+    }
+
+    /// Finds the color seen by a ray that misses every object
+    pub(crate) fn background_color(&self, ray: &Ray) -> Color {
+        self.background.color_toward(&ray.direction)
+    }
+
+    /// Add an object to the world, returning a handle that refers to
+    /// it for as long as it stays in the world
+    pub fn add_object(&mut self, obj: impl Into<Object>) -> ObjectHandle {
+        self.push_object(obj.into())
     }
 
     /// Get a count of number of objects in the world
@@ -49,36 +187,92 @@ impl World {
         self.objects.len()
     }
 
-    /// Determines if a point in the world is shadowed or not
+    /// Determines if a point in the world is shadowed or not. A point
+    /// behind a medium is in shadow unless all of the light gets
+    /// through it, see [World::light_transmittance].
     pub fn is_shadowed(&self, point: &Tuple) -> Result<bool> {
-        if self.light.is_none() {
-            return Ok(false);
-        }
+        Ok(self.light_transmittance(point)? < 1.0)
+    }
+
+    /// Finds the fraction of the light that reaches a point in the
+    /// world: 0 when an opaque object is in the way, 1 when nothing is,
+    /// and in between when the light only passes through media, which
+    /// dim it by their transmittance along the way.
+    pub fn light_transmittance(&self, point: &Tuple) -> Result<f64> {
+        let Some(light) = self.light.as_ref() else {
+            return Ok(1.0);
+        };
 
-        let v = &self.light.unwrap().position - point;
+        let v = &light.position - point;
         let distance = v.magnitude();
         let direction = v.normalize();
 
-        let r = Ray::new(*point, direction)?;
-        let h = hit(self.intersect_world(&r)?);
+        // the whole line is needed, since the point or the light may be
+        // inside a medium whose other boundary is behind them
+        let r = Ray::new((*point).try_into()?, direction.try_into()?);
+        let xs = self.intersect_world(&r)?;
 
-        if let Some(h) = h {
-            Ok(h.t < distance)
-        } else {
-            Ok(false)
+        if hit_for_shadow(&xs, distance) != ShadowResult::Unshadowed {
+            return Ok(0.0);
         }
+
+        let mut transmittance = 1.0;
+        for (n, entry) in xs.iter().enumerate() {
+            let Some(medium) = entry.object.get_material().get_volume() else {
+                continue;
+            };
+
+            // the hits on a shape alternate between entries and exits,
+            // so only those after an even number of others are entries
+            if xs[..n].iter().filter(|i| i.object == entry.object).count() % 2 == 1 {
+                continue;
+            }
+            let Some(exit) = xs[n + 1..].iter().find(|i| i.object == entry.object) else {
+                continue;
+            };
+
+            let inside = exit.t.min(distance) - entry.t.max(0.0);
+            if inside > 0.0 {
+                transmittance *= medium.transmittance(inside);
+            }
+        }
+
+        Ok(transmittance)
     }
 
     /// Finds and returns all the intersections of the given ray
-    /// with the world
-    fn intersect_world(&self, ray: &Ray) -> Result<Vec<Intersection>> {
-        let mut xs: Vec<Intersection> = vec![];
+    /// with the world, limited to the range carried by the ray
+    pub(crate) fn intersect_world(&self, ray: &Ray) -> Result<Intersections> {
+        self.intersect_world_in_range(ray, ray.t_min(), ray.t_max())
+    }
+
+    /// Finds and returns the intersections of the given ray with
+    /// the world that satisfy `t_min < t < t_max`, sorted by `t`.
+    ///
+    /// Every hit is inserted at its place with a binary search as the
+    /// objects report it, so there is no sort over all of them at the
+    /// end.
+    pub fn intersect_world_in_range(
+        &self,
+        ray: &Ray,
+        t_min: f64,
+        t_max: f64,
+    ) -> Result<Intersections> {
+        // most shapes are hit at most twice, so this is usually the
+        // only allocation made for the ray
+        let mut xs = Intersections::with_capacity(2 * self.objects.len());
         for o in &self.objects {
-            let mut intersections = o.intersect(ray)?;
-            xs.append(&mut intersections);
+            if self.bounds_pretest
+                && o.bounding_sphere()
+                    .is_some_and(|(center, radius)| misses_sphere(ray, &center, radius))
+            {
+                continue;
+            }
+
+            o.intersect_into(ray, &mut xs)?;
         }
 
-        xs.sort_by(|a, b| a.t.partial_cmp(&b.t).unwrap());
+        xs.retain_range(t_min, t_max);
 
         Ok(xs)
     }
@@ -86,18 +280,13 @@ impl World {
     /// Given a set of pre-computed state values of the world,
     /// calculate the color of a hit in the world
     fn shade_hit(&self, comps: &Computations) -> Result<Color> {
-        if self.light.is_none() {
+        let Some(light) = self.light.as_ref() else {
             return Ok(Color::black());
-        }
+        };
 
-        Ok(lighting(
-            &comps.get_object().get_material(),
-            self.light.as_ref().unwrap(),
-            comps.get_point(),
-            comps.get_eyev(),
-            comps.get_normalv(),
-            self.is_shadowed(comps.get_over_point())?, // placeholder until shadows are accounted for
-        ))
+        let light_intensity = self.light_transmittance(comps.get_over_point())?;
+
+        Ok(lighting(&comps.lighting_context(light, light_intensity)))
     }
 
     /// This method calculates all the intersections of a given ray
@@ -105,21 +294,42 @@ impl World {
     /// to find the color at the hits from the input ray.
     pub fn color_at(&self, ray: &Ray) -> Result<Color> {
         let xs = self.intersect_world(ray)?;
-        let h = hit(xs);
+        Ok(self.clamp_output_color(self.color_of_hit(&xs, ray)?))
+    }
 
-        if h.is_none() {
-            return Ok(Color::black());
+    /// Finds the color for a ray whose intersections with the world
+    /// were already found. This is the second half of [World::color_at].
+    pub(crate) fn color_of_hit(&self, xs: &Intersections, ray: &Ray) -> Result<Color> {
+        match hit(xs) {
+            Some(h) => {
+                if let Some(medium) = h.object.get_material().get_volume() {
+                    return self.shade_volume(medium, h, xs, ray);
+                }
+
+                let comps = Computations::prepare_computations_with_bias(h, ray, self.bias)?;
+                self.shade_hit(&comps)
+            }
+            None => Ok(self.background_color(ray)),
         }
-
-        let comps = Computations::prepare_computations(h.as_ref().unwrap(), ray)?;
-        self.shade_hit(&comps)
     }
 }
 
+/// Returns true if the line along the ray passes further than `radius`
+/// from `center`. A small margin keeps rays grazing the sphere from
+/// being rejected by rounding errors.
+fn misses_sphere(ray: &Ray, center: &Tuple, radius: f64) -> bool {
+    let to_center = *center - ray.origin;
+    let along = to_center.dot(&ray.direction);
+    let distance_squared =
+        to_center.dot(&to_center) - along * along / ray.direction.dot(&ray.direction);
+
+    let radius = radius * (1.0 + 1e-6) + RENDER_EPSILON;
+    distance_squared > radius * radius
+}
+
 impl Default for World {
     fn default() -> Self {
-        let light_source =
-            PointLight::new(Tuple::point(-10, 10, -10), Color::new(1, 1, 1)).unwrap();
+        let light_source = PointLight::new(Point::new(-10, 10, -10), Color::new(1, 1, 1));
 
         let mut s1 = Sphere::default();
         s1.material.set_color(Color::new(0.8, 1.0, 0.6));
@@ -127,26 +337,37 @@ impl Default for World {
         s1.material.set_specular(0.2);
 
         let mut s2 = Sphere::default();
-        s2.set_transform(scaling(0.5, 0.5, 0.5));
+        s2.set_transform(scaling(0.5, 0.5, 0.5)).unwrap();
 
         Self {
             light: Some(light_source),
             objects: vec![Object::Sphere(s1), Object::Sphere(s2)],
+            bias: RENDER_EPSILON,
+            background: Background::default(),
+            bounds_pretest: true,
+            clamp_output: None,
+            handles: vec![],
+            next_handle: 0,
         }
     }
 }
 
 #[cfg(test)]
 mod test {
-    use super::World;
+    use super::{Background, World};
     use crate::{
+        camera::Camera,
+        canvas::Canvas,
         color::Color,
-        intersections::{Computations, Intersection, Object, Ray, Sphere},
-        lights::PointLight,
-        matrix::translation,
-        spatial::Tuple,
+        intersections::{Computations, Intersection, Ray},
+        lights::{Material, PointLight},
+        matrix::{scaling, translation, view_transform},
+        shapes::{Intersect, Object, Sphere, SurfaceNormal, SPHERE_INTERSECT_CALLS},
+        spatial::{Point, Tuple, Vector},
+        utils::{alloc_counter::count_allocations, rng::RandomSampler, RENDER_EPSILON},
     };
     use anyhow::Result;
+    use std::f64::consts::PI;
 
     #[test]
     fn new_world_is_empty() {
@@ -163,10 +384,23 @@ mod test {
         assert_eq!(w.object_count(), 2);
     }
 
+    #[test]
+    fn shapes_can_be_added_without_wrapping_them() {
+        let mut w = World::empty();
+        let s = Sphere::default();
+
+        w.add_object(s);
+        w.add_object(Object::sphere());
+
+        assert_eq!(w.objects[0], Object::Sphere(s));
+        assert_eq!(Object::from(s), Object::Sphere(s));
+        assert!(matches!(w.objects[1], Object::Sphere(_)));
+    }
+
     #[test]
     fn intersect_world_default() -> Result<()> {
         let w = World::default();
-        let ray = Ray::new(Tuple::point(0, 0, -5), Tuple::vector(0, 0, 1))?;
+        let ray = Ray::new(Point::new(0, 0, -5), Vector::new(0, 0, 1));
 
         let xs = w.intersect_world(&ray)?;
 
@@ -179,10 +413,137 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn intersecting_the_world_allocates_only_the_result() -> Result<()> {
+        let w = World::default();
+        let mut c = Camera::new(11, 11, PI / 2.0)?;
+        c.set_transform(view_transform(
+            &Point::new(0, 0, -5),
+            &Point::new(0, 0, 0),
+            &Vector::new(0, 1, 0),
+        ));
+
+        for y in 0..11 {
+            for x in 0..11 {
+                let ray = c.ray_for_pixel(x, y)?;
+                let (xs, allocations) = count_allocations(|| w.intersect_world(&ray));
+                xs?;
+
+                assert_eq!(allocations, 1, "pixel ({}, {})", x, y);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn intersections_match_a_full_sort_in_a_random_scene() -> Result<()> {
+        let mut sampler = RandomSampler::new(42);
+        let mut random = |range: f64| (sampler.next_f64() - 0.5) * range;
+
+        let mut w = World::empty();
+        for _ in 0..50 {
+            let transform =
+                (translation(random(10.0), random(10.0), random(10.0)) * scaling(1.5, 1.5, 1.5))?;
+            w.add_object(Sphere::new(transform, Material::default())?);
+        }
+
+        let mut hits = 0;
+        for _ in 0..100 {
+            let direction = Vector::new(random(2.0), random(2.0), 2).normalize();
+            let ray = Ray::new(Point::new(random(4.0), random(4.0), -20), direction);
+
+            let mut expected = vec![];
+            for o in &w.objects {
+                expected.extend(o.intersect(&ray)?.iter().copied());
+            }
+            expected.sort();
+
+            assert_eq!(w.intersect_world(&ray)?[..], expected[..]);
+            hits += expected.len();
+        }
+
+        assert!(hits > 100);
+
+        Ok(())
+    }
+
+    #[test]
+    fn bounds_pretest_skips_work_without_changing_the_image() -> Result<()> {
+        let mut w = World::default();
+        w.objects.clear();
+        for i in 0..50 {
+            let (x, y) = ((i % 10) as f64 * 3.0 - 13.5, (i / 10) as f64 * 3.0 - 6.0);
+            w.add_object(Sphere::new(translation(x, y, 0), Material::default())?);
+        }
+
+        let mut c = Camera::new(20, 10, PI / 2.0)?;
+        c.set_transform(view_transform(
+            &Point::new(0, 0, -15),
+            &Point::new(0, 0, 0),
+            &Vector::new(0, 1, 0),
+        ));
+
+        let mut render = |pretest| -> Result<(Canvas, usize)> {
+            w.set_bounds_pretest(pretest);
+            SPHERE_INTERSECT_CALLS.with(|calls| calls.set(0));
+            let image = c.render(&w)?;
+            Ok((image, SPHERE_INTERSECT_CALLS.with(|calls| calls.get())))
+        };
+
+        let (with, tests_with) = render(true)?;
+        let (without, tests_without) = render(false)?;
+
+        assert_eq!(with, without);
+        assert!(tests_with * 5 < tests_without);
+
+        Ok(())
+    }
+
+    #[test]
+    fn intersect_world_in_range_limits_the_hits() -> Result<()> {
+        let mut w = World::empty();
+        let mut s = Sphere::default();
+        s.set_transform(translation(0, 0, 10))?;
+        w.add_object(s);
+
+        let ray = Ray::new(Point::new(0, 0, 0), Vector::new(0, 0, 1));
+
+        // The sphere lies between t = 9 and t = 11
+        let beyond = w.intersect_world_in_range(&ray, 0.0, 5.0)?;
+        assert!(beyond.is_empty());
+        assert_eq!(beyond.hit(), None);
+
+        let inside = w.intersect_world_in_range(&ray, 0.0, 20.0)?;
+        assert_eq!(inside.hit().map(|i| i.t), Some(9.0));
+
+        // The range carried by a clamped ray is respected too
+        let clamped = w.intersect_world(&ray.clamped(0, 10))?;
+        assert_eq!(clamped.len(), 1);
+        assert_eq!(clamped.hit().map(|i| i.t), Some(9.0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn unbounded_queries_match_intersect_world() -> Result<()> {
+        let w = World::default();
+        let ray = Ray::new(Point::new(0, 0, 0), Vector::new(0, 0, 1));
+
+        let all = w.intersect_world(&ray)?;
+        let ranged = w.intersect_world_in_range(&ray, f64::NEG_INFINITY, f64::INFINITY)?;
+
+        assert_eq!(all.len(), 4);
+        assert_eq!(all, ranged);
+        assert_eq!(all[0].t, -1.0);
+
+        Ok(())
+    }
+
     #[test]
     fn shading_an_intersection() -> Result<()> {
         let w = World::default();
-        let r = Ray::new(Tuple::point(0, 0, -5), Tuple::vector(0, 0, 1))?;
+        let r = Ray::new(Point::new(0, 0, -5), Vector::new(0, 0, 1));
 
         // Ensure that we have two objects in our world
         assert_eq!(w.object_count(), 2);
@@ -200,11 +561,11 @@ mod test {
     fn shading_an_intersection_from_the_inside() -> Result<()> {
         let mut w = World::default();
         w.set_light(Some(PointLight::new(
-            Tuple::point(0, 0.25, 0),
+            Point::new(0, 0.25, 0),
             Color::new(1, 1, 1),
-        )?));
+        )));
 
-        let r = Ray::new(Tuple::point(0, 0, 0), Tuple::vector(0, 0, 1))?;
+        let r = Ray::new(Point::new(0, 0, 0), Vector::new(0, 0, 1));
 
         // Ensure that we have two objects in our world
         assert_eq!(w.object_count(), 2);
@@ -221,7 +582,7 @@ mod test {
     #[test]
     fn color_at_when_ray_misses() -> Result<()> {
         let w = World::default();
-        let r = Ray::new(Tuple::point(0, 0, -5), Tuple::vector(0, 1, 0))?;
+        let r = Ray::new(Point::new(0, 0, -5), Vector::new(0, 1, 0));
         let c = w.color_at(&r)?;
         assert_eq!(c, Color::black());
         Ok(())
@@ -230,19 +591,38 @@ mod test {
     #[test]
     fn color_at_when_a_ray_hits() -> Result<()> {
         let w = World::default();
-        let r = Ray::new(Tuple::point(0, 0, -5), Tuple::vector(0, 0, 1))?;
+        let r = Ray::new(Point::new(0, 0, -5), Vector::new(0, 0, 1));
         let c = w.color_at(&r)?;
         assert_eq!(c, Color::new(0.38066, 0.47583, 0.2855));
         Ok(())
     }
 
+    #[test]
+    fn materials_changed_in_place_are_used_for_shading() -> Result<()> {
+        let mut w = World::default();
+        let r = Ray::new(Point::new(0, 0, -5), Vector::new(0, 0, 1));
+        let before = w.color_at(&r)?;
+
+        w.objects[0]
+            .material_mut()
+            .set_color(Color::new(0.2, 0.2, 0.2));
+
+        assert_eq!(
+            w.objects[0].get_material().get_color(),
+            Color::new(0.2, 0.2, 0.2)
+        );
+        assert_ne!(w.color_at(&r)?, before);
+
+        Ok(())
+    }
+
     #[test]
     fn color_at_when_intersection_is_behind_ray() -> Result<()> {
         let mut w = World::default();
         w.objects[0].set_ambient(1.0);
         w.objects[1].set_ambient(1.0);
 
-        let r = Ray::new(Tuple::point(0, 0, 0.75), Tuple::vector(0, 0, -1))?;
+        let r = Ray::new(Point::new(0, 0, 0.75), Vector::new(0, 0, -1));
         let c = w.color_at(&r)?;
         assert_eq!(c, w.objects[1].get_material().get_color());
         Ok(())
@@ -273,18 +653,18 @@ mod test {
 
     #[test]
     fn shade_hit_is_given_an_intersection_in_shadow() -> Result<()> {
-        let light = PointLight::new(Tuple::point(0, 0, -10), Color::new(1, 1, 1))?;
+        let light = PointLight::new(Point::new(0, 0, -10), Color::new(1, 1, 1));
         let mut w = World::empty();
         w.set_light(Some(light));
 
         let s1 = Sphere::default();
-        w.add_object(Object::Sphere(s1));
+        w.add_object(s1);
 
         let mut s2 = Sphere::default();
-        s2.set_transform(translation(0, 0, 10));
-        w.add_object(Object::Sphere(s2));
+        s2.set_transform(translation(0, 0, 10))?;
+        w.add_object(s2);
 
-        let r = Ray::new(Tuple::point(0, 0, 5), Tuple::vector(0, 0, 1))?;
+        let r = Ray::new(Point::new(0, 0, 5), Vector::new(0, 0, 1));
         let i = Intersection::new(4, Object::Sphere(s2));
 
         let comps = Computations::prepare_computations(&i, &r)?;
@@ -294,4 +674,208 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn bias_defaults_to_epsilon_and_rejects_bad_values() {
+        let mut w = World::default();
+        assert_eq!(w.get_bias(), RENDER_EPSILON);
+        assert_eq!(World::empty().get_bias(), RENDER_EPSILON);
+
+        assert!(w.set_bias(-1.0).is_err());
+        assert!(w.set_bias(f64::NAN).is_err());
+        assert!(w.set_bias(0.01).is_ok());
+        assert_eq!(w.get_bias(), 0.01);
+    }
+
+    /// A 9x5 panorama where each pixel's red and green channels encode
+    /// its column and row
+    fn gradient_panorama() -> Result<Canvas> {
+        let mut canvas = Canvas::new(9, 5);
+        for y in 0..5 {
+            for x in 0..9 {
+                canvas.write_pixel(x, y, Color::new(x as f64 / 9.0, y as f64 / 5.0, 1))?;
+            }
+        }
+        Ok(canvas)
+    }
+
+    #[test]
+    fn rays_that_miss_sample_the_environment() -> Result<()> {
+        let mut w = World::empty();
+        assert_eq!(w.get_environment(), None);
+
+        let up = Ray::new(Point::new(0, 0, 0), Vector::new(0, 1, 0));
+        assert_eq!(w.color_at(&up)?, Color::black());
+
+        w.set_environment(gradient_panorama()?);
+
+        // straight up is the top row
+        assert_eq!(w.color_at(&up)?.green, 0.0);
+
+        // +x is the center of the image
+        let right = Ray::new(Point::new(0, 0, 0), Vector::new(5, 0, 0));
+        assert_eq!(w.color_at(&right)?, Color::new(4.0 / 9.0, 2.0 / 5.0, 1));
+
+        // -z is a quarter of the way around to the left of it
+        let back = Ray::new(Point::new(0, 0, 0), Vector::new(0, 0, -1));
+        assert_eq!(w.color_at(&back)?, Color::new(1.75 / 9.0, 2.0 / 5.0, 1));
+
+        Ok(())
+    }
+
+    #[test]
+    fn rays_that_miss_see_the_background() -> Result<()> {
+        let mut w = World::empty();
+        assert_eq!(w.get_background(), &Background::default());
+
+        w.set_background(Background::SkyGradient {
+            horizon: Color::new(1, 1, 1),
+            zenith: Color::new(0, 0, 1),
+            ground: None,
+        });
+
+        let up = Ray::new(Point::new(0, 0, 0), Vector::new(0, 1, 0));
+        assert_eq!(w.color_at(&up)?, Color::new(0, 0, 1));
+        assert_eq!(w.get_environment(), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn objects_still_hide_the_environment() -> Result<()> {
+        let mut w = World::default();
+        w.set_environment(gradient_panorama()?);
+
+        let r = Ray::new(Point::new(0, 0, -5), Vector::new(0, 0, 1));
+        assert_eq!(w.color_at(&r)?, Color::new(0.38066, 0.47583, 0.2855));
+
+        Ok(())
+    }
+
+    #[test]
+    fn large_scale_scene_with_increased_bias_has_no_shadow_acne() -> Result<()> {
+        let light_position = Tuple::point(-10000, 10000, -10000);
+        let mut w = World::empty();
+        w.set_light(Some(PointLight::new(
+            light_position.try_into()?,
+            Color::new(1, 1, 1),
+        )));
+
+        let mut s = Sphere::default();
+        s.set_transform(scaling(1000, 1000, 1000))?;
+        w.add_object(s);
+        w.set_bias(0.01)?;
+
+        let mut camera = Camera::new(21, 21, 0.5)?;
+        camera.set_transform(view_transform(
+            &Point::new(0, 0, -5000),
+            &Point::new(0, 0, 0),
+            &Vector::new(0, 1, 0),
+        ));
+        let image = camera.render(&w)?;
+
+        // The shadowed color is only the ambient contribution
+        let ambient = Color::new(0.1, 0.1, 0.1);
+        let mut lit_pixels = 0;
+
+        for y in 0..20 {
+            for x in 0..20 {
+                let ray = camera.ray_for_pixel(x, y)?;
+                let Some(h) = w.intersect_world(&ray)?.hit().copied() else {
+                    continue;
+                };
+
+                let point = ray.position(h.t);
+                let normal = h.object.normal_at(point)?;
+                let to_light = (light_position - point).normalize();
+
+                // Every pixel facing the light must be lit, not just ambient
+                if normal.dot(&to_light) > 0.1 {
+                    lit_pixels += 1;
+                    assert_ne!(*image.pixel_at(x, y)?, ambient);
+                }
+            }
+        }
+
+        assert!(lit_pixels > 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn huge_scene_has_no_shadow_acne_with_the_default_bias() -> Result<()> {
+        let scale = 1e10;
+        let mut w = World::empty();
+        let light = Tuple::point(-10.0 * scale, 10.0 * scale, -10.0 * scale);
+        w.set_light(Some(PointLight::new(
+            light.try_into()?,
+            Color::new(1, 1, 1),
+        )));
+
+        let mut s = Sphere::default();
+        s.set_transform(scaling(scale, scale, scale))?;
+        w.add_object(s);
+
+        // a 10x10 patch of the surface right below the light
+        let surface = scale / 3_f64.sqrt();
+        let mut camera = Camera::new(10, 10, 0.05)?;
+        camera.set_transform(view_transform(
+            &Point::new(-3.0 * scale, 3.0 * scale, -3.0 * scale),
+            &Point::new(-surface, surface, -surface),
+            &Vector::new(0, 1, 0),
+        ));
+        let image = camera.render(&w)?;
+
+        let ambient = Color::new(0.1, 0.1, 0.1);
+        for y in 0..10 {
+            for x in 0..10 {
+                assert_ne!(*image.pixel_at(x, y)?, ambient, "acne at ({}, {})", x, y);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn overexposed_colors_can_be_clamped() -> Result<()> {
+        let mut w = World::default();
+        w.set_light(Some(PointLight::new(
+            Point::new(-10, 10, -10),
+            Color::new(3, 3, 3),
+        )));
+        let ray = Ray::new(Point::new(0, 0, -5), Vector::new(0, 0, 1));
+
+        let raw = w.color_at(&ray)?;
+        assert!(raw.red > 1.0 && raw.green > 1.0);
+        assert_eq!(w.get_clamp_output(), None);
+
+        w.set_clamp_output(Some((0.0, 1.0)))?;
+        let clamped = w.color_at(&ray)?;
+        assert_eq!(clamped, raw.clamp(0.0, 1.0));
+        assert!([clamped.red, clamped.green, clamped.blue]
+            .iter()
+            .all(|&c| c <= 1.0));
+
+        // renders go through the same clamp
+        let mut c = Camera::new(11, 11, PI / 2.0)?;
+        c.set_transform(view_transform(
+            &Point::new(0, 0, -5),
+            &Point::origin(),
+            &Vector::new(0, 1, 0),
+        ));
+        assert_eq!(*c.render(&w)?.pixel_at(5, 5)?, clamped);
+
+        Ok(())
+    }
+
+    #[test]
+    fn clamping_ranges_must_be_ordered_and_finite() {
+        let mut w = World::default();
+
+        assert!(w.set_clamp_output(Some((1.0, 0.0))).is_err());
+        assert!(w.set_clamp_output(Some((0.0, f64::INFINITY))).is_err());
+        assert!(w.set_clamp_output(Some((0.0, 2.0))).is_ok());
+        assert!(w.set_clamp_output(None).is_ok());
+        assert_eq!(w.get_clamp_output(), None);
+    }
 }
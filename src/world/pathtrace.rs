@@ -0,0 +1,242 @@
+use super::World;
+use crate::{
+    color::Color,
+    intersections::{hit, Computations, Ray},
+    spatial::Tuple,
+    utils::rng::Sampler,
+};
+use anyhow::Result;
+use std::f64::consts::PI;
+
+/// Number of bounces a path always takes before Russian roulette may
+/// end it early
+const ROULETTE_START: usize = 3;
+
+impl World {
+    /// Finds the color for the ray by tracing a single random path of
+    /// up to `depth` bounces through the world.
+    ///
+    /// At every hit the path picks one cosine-weighted direction to
+    /// continue in, and the light gathered along the path is tinted by
+    /// the color of each surface it bounced off. Only emissive
+    /// materials and the background light the scene: the point
+    /// light and the Phong attributes of the materials are ignored.
+    ///
+    /// A single path is very noisy, so the color of a pixel should be
+    /// the average of many of them.
+    pub fn color_at_pathtraced(
+        &self,
+        ray: &Ray,
+        depth: usize,
+        sampler: &mut dyn Sampler,
+    ) -> Result<Color> {
+        Ok(self.clamp_output_color(self.trace_path(ray, 0, depth, sampler)?))
+    }
+
+    fn trace_path(
+        &self,
+        ray: &Ray,
+        bounce: usize,
+        depth: usize,
+        sampler: &mut dyn Sampler,
+    ) -> Result<Color> {
+        let xs = self.intersect_world(ray)?;
+        let Some(h) = hit(&xs) else {
+            return Ok(self.background_color(ray));
+        };
+
+        let comps = Computations::prepare_computations_with_bias(h, ray, self.bias)?;
+        let material = comps.get_object().get_material();
+        let emitted = material.get_emissive();
+
+        if bounce >= depth {
+            return Ok(emitted);
+        }
+
+        let mut albedo = material.get_color();
+
+        // past the first few bounces, end dark paths at random and
+        // brighten the ones that survive so the average is unchanged
+        if bounce >= ROULETTE_START {
+            let survival = albedo
+                .red
+                .max(albedo.green)
+                .max(albedo.blue)
+                .clamp(0.05, 0.95);
+
+            if sampler.next_1d() >= survival {
+                return Ok(emitted);
+            }
+
+            albedo *= 1.0 / survival;
+        }
+
+        let direction = cosine_weighted_direction(comps.get_normalv(), sampler);
+        let bounced = Ray::new((*comps.get_over_point()).try_into()?, direction.try_into()?);
+        let incoming = self.trace_path(&bounced, bounce + 1, depth, sampler)?;
+
+        Ok(emitted + albedo * incoming)
+    }
+}
+
+/// Picks a random direction on the hemisphere around `normal`, with
+/// directions close to the normal more likely than grazing ones
+fn cosine_weighted_direction(normal: &Tuple, sampler: &mut dyn Sampler) -> Tuple {
+    let helper = if normal.get_x().abs() > 0.9 {
+        Tuple::unit_y()
+    } else {
+        Tuple::unit_x()
+    };
+    let tangent = normal.cross(&helper).normalize();
+    let bitangent = normal.cross(&tangent);
+
+    let u = sampler.next_1d();
+    let angle = 2.0 * PI * sampler.next_1d();
+    let radius = u.sqrt();
+
+    (tangent * (radius * angle.cos())
+        + bitangent * (radius * angle.sin())
+        + *normal * (1.0 - u).sqrt())
+    .normalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::cosine_weighted_direction;
+    use crate::{
+        color::Color,
+        intersections::Ray,
+        lights::Material,
+        matrix::{scaling, translation},
+        shapes::Sphere,
+        spatial::{Point, Tuple, Vector},
+        utils::{float_equals, rng::RandomSampler},
+        world::World,
+    };
+    use anyhow::Result;
+
+    /// A closed white room with a glowing panel below its ceiling
+    fn lit_room() -> Result<World> {
+        let mut walls = Material::default();
+        walls.set_color(Color::new(0.7, 0.7, 0.7));
+
+        let mut panel = Material::default();
+        panel.set_color(Color::black());
+        panel.set_emissive(Color::new(4, 4, 4));
+
+        let ceiling_light = Sphere::new((translation(0, 7, 0) * scaling(5, 0.5, 5))?, panel)?;
+
+        let mut w = World::empty();
+        w.add_object(Sphere::new(scaling(10, 10, 10), walls)?);
+        w.add_object(ceiling_light);
+
+        Ok(w)
+    }
+
+    #[test]
+    fn a_closed_room_lights_its_floor_evenly() -> Result<()> {
+        let w = lit_room()?;
+        let mut sampler = RandomSampler::new(1);
+
+        let brightness: Vec<f64> = [(0.0, 0.0), (0.3, 0.0), (0.0, -0.3), (-0.3, 0.3)]
+            .into_iter()
+            .map(|(x, z)| {
+                let ray = Ray::new(Point::new(0, 0, 0), Vector::new(x, -1, z).normalize());
+                let mut total = Color::black();
+                for _ in 0..2000 {
+                    total = total + w.color_at_pathtraced(&ray, 8, &mut sampler)?;
+                }
+                Ok(total.red / 2000.0)
+            })
+            .collect::<Result<_>>()?;
+
+        let min = brightness.iter().cloned().fold(f64::INFINITY, f64::min);
+        let max = brightness.iter().cloned().fold(0.0, f64::max);
+
+        assert!(min > 0.0);
+        assert!(
+            max / min < 1.5,
+            "floor brightness {:?} is uneven",
+            brightness
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn paths_are_reproducible_with_a_seed() -> Result<()> {
+        let w = lit_room()?;
+        let ray = Ray::new(Point::new(0, 0, 0), Vector::new(0, -1, 0));
+
+        let trace = |seed| -> Result<Vec<Color>> {
+            let mut sampler = RandomSampler::new(seed);
+            (0..20)
+                .map(|_| w.color_at_pathtraced(&ray, 8, &mut sampler))
+                .collect()
+        };
+
+        assert_eq!(trace(5)?, trace(5)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn a_path_without_bounces_only_sees_emission() -> Result<()> {
+        let w = lit_room()?;
+        let mut sampler = RandomSampler::new(1);
+
+        let up = Ray::new(Point::new(0, 0, 0), Vector::new(0, 1, 0));
+        let down = Ray::new(Point::new(0, 0, 0), Vector::new(0, -1, 0));
+
+        assert_eq!(
+            w.color_at_pathtraced(&up, 0, &mut sampler)?,
+            Color::new(4, 4, 4)
+        );
+        assert_eq!(
+            w.color_at_pathtraced(&down, 0, &mut sampler)?,
+            Color::black()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn bounce_directions_stay_on_the_normal_side() {
+        let mut sampler = RandomSampler::new(9);
+        let normal = Tuple::vector(1, 0, 0);
+
+        for _ in 0..500 {
+            let d = cosine_weighted_direction(&normal, &mut sampler);
+            assert!(d.is_a_vector());
+            assert!(float_equals(&d.magnitude(), &1.0));
+            assert!(d.dot(&normal) >= 0.0);
+        }
+    }
+
+    #[test]
+    fn clamping_only_applies_to_the_final_color() -> Result<()> {
+        // the panel glows at 4, far above the clamp. Clamping it before
+        // it lit the walls would make them darker than clamping the
+        // final color.
+        let mut w = lit_room()?;
+        let ray = Ray::new(Point::origin(), Vector::new(0, -1, 0));
+
+        let trace = |w: &World| -> Result<Vec<Color>> {
+            let mut sampler = RandomSampler::new(3);
+            (0..50)
+                .map(|_| w.color_at_pathtraced(&ray, 8, &mut sampler))
+                .collect()
+        };
+
+        let raw = trace(&w)?;
+        w.set_clamp_output(Some((0.0, 1.0)))?;
+        let clamped = trace(&w)?;
+
+        for (raw, clamped) in raw.iter().zip(&clamped) {
+            assert_eq!(*clamped, raw.clamp(0.0, 1.0));
+        }
+        assert!(raw.iter().any(|c| c.red > 1.0));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,143 @@
+use super::World;
+use std::fmt::{self, Display};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Statistics about the contents of a [World]
+pub struct WorldSummary {
+    /// The number of objects of each kind, sorted by kind
+    pub objects_by_kind: Vec<(&'static str, usize)>,
+    /// The number of lights in the world
+    pub lights: usize,
+}
+
+impl WorldSummary {
+    /// The number of objects of all kinds
+    pub fn object_count(&self) -> usize {
+        self.objects_by_kind.iter().map(|(_, count)| count).sum()
+    }
+}
+
+impl World {
+    /// Counts the objects and lights in the world
+    ///
+    /// ```
+    /// use raytracer::world::World;
+    ///
+    /// let summary = World::default().summary();
+    /// assert_eq!(summary.to_string(), "objects: 2 (Sphere: 2)\nlights: 1");
+    /// ```
+    pub fn summary(&self) -> WorldSummary {
+        let mut objects_by_kind: Vec<(&'static str, usize)> = vec![];
+
+        for object in &self.objects {
+            match objects_by_kind
+                .iter_mut()
+                .find(|(k, _)| *k == object.kind())
+            {
+                Some((_, count)) => *count += 1,
+                None => objects_by_kind.push((object.kind(), 1)),
+            }
+        }
+
+        objects_by_kind.sort();
+
+        WorldSummary {
+            objects_by_kind,
+            lights: self.light.iter().count(),
+        }
+    }
+}
+
+impl Display for WorldSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "objects: {}", self.object_count())?;
+
+        if !self.objects_by_kind.is_empty() {
+            let kinds: Vec<String> = self
+                .objects_by_kind
+                .iter()
+                .map(|(kind, count)| format!("{}: {}", kind, count))
+                .collect();
+            write!(f, " ({})", kinds.join(", "))?;
+        }
+
+        write!(f, "\nlights: {}", self.lights)
+    }
+}
+
+/// Prints the light and then one line per object, see the [fmt::Debug]
+/// of [crate::shapes::Object]
+///
+/// ```
+/// use raytracer::world::World;
+///
+/// let printed = format!("{:?}", World::default());
+/// assert_eq!(printed.lines().count(), 5);
+/// ```
+impl fmt::Debug for World {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "World {{")?;
+
+        match &self.light {
+            Some(light) => writeln!(f, "    {}", light)?,
+            None => writeln!(f, "    no light")?,
+        }
+
+        for object in &self.objects {
+            writeln!(f, "    {:?}", object)?;
+        }
+
+        write!(f, "}}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::world::World;
+
+    #[test]
+    fn summarizing_the_default_world() {
+        let summary = World::default().summary();
+
+        assert_eq!(summary.objects_by_kind, vec![("Sphere", 2)]);
+        assert_eq!(summary.object_count(), 2);
+        assert_eq!(summary.lights, 1);
+
+        let printed = summary.to_string();
+        let lines: Vec<&str> = printed.lines().collect();
+        assert_eq!(lines, vec!["objects: 2 (Sphere: 2)", "lights: 1"]);
+    }
+
+    #[test]
+    fn summarizing_an_empty_world() {
+        let summary = World::empty().summary();
+
+        assert_eq!(summary.object_count(), 0);
+        assert_eq!(summary.lights, 0);
+        assert_eq!(summary.to_string(), "objects: 0\nlights: 0");
+    }
+
+    #[test]
+    fn debug_prints_one_line_per_object() {
+        let w = World::default();
+        let printed = format!("{:?}", w);
+
+        let expected = format!(
+            "World {{
+    PointLight at P(-10.00000, 10.00000, -10.00000) intensity (1, 1, 1)
+    {} at P(0.00000, 0.00000, 0.00000) color (0.8, 1, 0.6)
+    {} at P(0.00000, 0.00000, 0.00000) color (1, 1, 1)
+}}",
+            w.objects[0], w.objects[1]
+        );
+
+        assert_eq!(printed, expected);
+        assert_eq!(printed.matches("Sphere").count(), 2);
+        assert!(printed.contains("P(-10.00000, 10.00000, -10.00000)"));
+    }
+
+    #[test]
+    fn debug_shows_a_missing_light() {
+        assert_eq!(format!("{:?}", World::empty()), "World {\n    no light\n}");
+    }
+}
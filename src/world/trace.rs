@@ -0,0 +1,230 @@
+use super::World;
+use crate::{
+    camera::Camera,
+    color::Color,
+    intersections::{Computations, Ray},
+    spatial::Tuple,
+};
+use anyhow::Result;
+use std::fmt::{self, Display};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// Details about the hit found for a traced ray
+pub struct TracedHit {
+    /// Id of the object that was hit
+    pub object_id: Uuid,
+    /// The `t` value of the hit along the ray
+    pub t: f64,
+    /// The point on the surface that was hit
+    pub point: Tuple,
+    /// The surface normal at the hit, facing the eye
+    pub normalv: Tuple,
+    /// Whether the hit point is in the shadow of another object
+    pub in_shadow: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A record of everything that happened while finding the color
+/// for a single ray: the hit it found (if any), the color it
+/// produced, and a child node for every secondary ray it spawned.
+///
+/// The tracer doesn't spawn secondary rays for reflection or
+/// refraction yet, so for now `children` is always empty.
+pub struct RayTraceNode {
+    pub ray: Ray,
+    pub hit: Option<TracedHit>,
+    pub color: Color,
+    pub children: Vec<RayTraceNode>,
+}
+
+impl World {
+    /// Same as [World::color_at], but records the work done to find
+    /// the color in a [RayTraceNode] instead of only returning it.
+    pub fn color_at_traced(&self, ray: &Ray) -> Result<RayTraceNode> {
+        let xs = self.intersect_world(ray)?;
+
+        let Some(h) = xs.hit() else {
+            return Ok(RayTraceNode {
+                ray: *ray,
+                hit: None,
+                color: self.clamp_output_color(self.background_color(ray)),
+                children: vec![],
+            });
+        };
+
+        let comps = Computations::prepare_computations_with_bias(h, ray, self.bias)?;
+
+        let hit = TracedHit {
+            object_id: h.object.id(),
+            t: h.t,
+            point: *comps.get_point(),
+            normalv: *comps.get_normalv(),
+            in_shadow: self.is_shadowed(comps.get_over_point())?,
+        };
+
+        Ok(RayTraceNode {
+            ray: *ray,
+            hit: Some(hit),
+            color: self.clamp_output_color(self.color_of_hit(&xs, ray)?),
+            children: vec![],
+        })
+    }
+
+    /// Traces the ray through the pixel (x, y) of the camera. This is
+    /// useful to find out why a single pixel of a render looks wrong.
+    ///
+    /// ```
+    /// use raytracer::{camera::Camera, world::World};
+    /// use std::f64::consts::PI;
+    ///
+    /// let w = World::default();
+    /// let c = Camera::new(11, 11, PI / 2.0).unwrap();
+    ///
+    /// let trace = w.trace_pixel(&c, 5, 5).unwrap();
+    /// println!("{}", trace);
+    /// ```
+    pub fn trace_pixel(&self, camera: &Camera, x: usize, y: usize) -> Result<RayTraceNode> {
+        self.color_at_traced(&camera.ray_for_pixel(x, y)?)
+    }
+}
+
+impl RayTraceNode {
+    /// Writes this node and its children, indenting each level of the tree
+    fn fmt_indented(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        let indent = "  ".repeat(depth);
+
+        writeln!(f, "{}ray {}", indent, self.ray)?;
+
+        match &self.hit {
+            Some(h) => {
+                writeln!(
+                    f,
+                    "{}  hit object {} at t = {:.5}",
+                    indent, h.object_id, h.t
+                )?;
+                writeln!(f, "{}  point {}, normal {}", indent, h.point, h.normalv)?;
+                writeln!(f, "{}  in shadow: {}", indent, h.in_shadow)?;
+            }
+            None => writeln!(f, "{}  miss", indent)?,
+        }
+
+        writeln!(
+            f,
+            "{}  color ({:.5}, {:.5}, {:.5})",
+            indent, self.color.red, self.color.green, self.color.blue
+        )?;
+
+        for child in &self.children {
+            child.fmt_indented(f, depth + 1)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for RayTraceNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_indented(f, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        camera::Camera,
+        color::Color,
+        intersections::Ray,
+        matrix::view_transform,
+        spatial::{Point, Tuple, Vector},
+        world::World,
+    };
+    use anyhow::Result;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn tracing_a_hit_records_the_hit_and_color() -> Result<()> {
+        let w = World::default();
+        let r = Ray::new(Point::new(0, 0, -5), Vector::new(0, 0, 1));
+
+        let node = w.color_at_traced(&r)?;
+        let hit = node.hit.expect("the ray should hit the outer sphere");
+
+        assert_eq!(hit.object_id, w.objects[0].id());
+        assert_eq!(hit.t, 4.0);
+        assert_eq!(hit.point, Tuple::point(0, 0, -1));
+        assert_eq!(hit.normalv, Tuple::vector(0, 0, -1));
+        assert!(!hit.in_shadow);
+        assert_eq!(node.color, w.color_at(&r)?);
+        assert!(node.children.is_empty());
+
+        let printed = node.to_string();
+        assert!(printed.contains(&format!("hit object {} at t = 4.00000", w.objects[0].id())));
+        assert!(printed.contains("in shadow: false"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn tracing_records_the_id_of_the_object_that_was_hit() -> Result<()> {
+        let w = World::default();
+        let r = Ray::new(Point::new(0, 0, 0), Vector::new(0, 0, 1));
+
+        let hit = w
+            .color_at_traced(&r)?
+            .hit
+            .expect("the ray should hit the inner sphere");
+
+        assert_eq!(hit.object_id, w.objects[1].id());
+        assert_eq!(hit.t, 0.5);
+
+        Ok(())
+    }
+
+    #[test]
+    fn tracing_a_miss_yields_a_leaf_without_a_hit() -> Result<()> {
+        let w = World::default();
+        let r = Ray::new(Point::new(0, 0, -5), Vector::new(0, 1, 0));
+
+        let node = w.color_at_traced(&r)?;
+
+        assert_eq!(node.hit, None);
+        assert_eq!(node.color, Color::black());
+        assert!(node.children.is_empty());
+        assert!(node.to_string().contains("miss"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn tracing_a_pixel_matches_rendering_it() -> Result<()> {
+        let mut w = World::default();
+        let mut c = Camera::new(11, 11, PI / 2.0)?;
+        c.set_transform(view_transform(
+            &Point::new(0, 0, -5),
+            &Point::new(0, 0, 0),
+            &Vector::new(0, 1, 0),
+        ));
+
+        let node = w.trace_pixel(&c, 5, 5)?;
+        let image = c.render(&w)?;
+
+        assert_eq!(node.color, *image.pixel_at(5, 5)?);
+        assert_eq!(node.color, Color::new(0.38066, 0.47583, 0.2855));
+
+        // with the output clamped, hits and misses are clamped alike
+        w.set_clamp_output(Some((0.1, 0.3)))?;
+        let image = c.render(&w)?;
+
+        let hit = w.trace_pixel(&c, 5, 5)?;
+        assert_eq!(hit.color, *image.pixel_at(5, 5)?);
+        assert_eq!(hit.color, Color::new(0.3, 0.3, 0.2855));
+
+        let miss = w.trace_pixel(&c, 0, 0)?;
+        assert_eq!(miss.hit, None);
+        assert_eq!(miss.color, *image.pixel_at(0, 0)?);
+        assert_eq!(miss.color, Color::new(0.1, 0.1, 0.1));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,195 @@
+use super::World;
+use crate::{
+    color::Color,
+    intersections::{Intersection, Ray},
+    lights::Volume,
+};
+use anyhow::Result;
+
+impl World {
+    /// Finds the color for a ray whose hit `h` is on the boundary of
+    /// the given medium.
+    ///
+    /// The light coming from behind the medium is attenuated by the
+    /// distance the ray travels between the entry and the exit
+    /// intersections, and the medium adds its own color in proportion
+    /// to the light it blocked. A ray starting inside the shape enters
+    /// the medium at its origin.
+    pub(super) fn shade_volume(
+        &self,
+        medium: &Volume,
+        h: &Intersection,
+        xs: &[Intersection],
+        ray: &Ray,
+    ) -> Result<Color> {
+        let exit = xs
+            .iter()
+            .find(|i| i.t > h.t && i.object == h.object)
+            .map(|i| i.t);
+
+        let (entry, exit) = match exit {
+            Some(exit) => (h.t, exit),
+            None => (ray.t_min().max(0.0), h.t),
+        };
+
+        let speed = ray.direction.magnitude();
+        let transmittance = medium.transmittance((exit - entry) * speed);
+
+        // nudge past the exit so the boundary isn't hit again
+        let beyond = ray.clamped(exit + self.bias / speed, ray.t_max());
+        // the output clamp applies to the final color only, not to the
+        // light before the medium attenuates it
+        let behind = self.color_of_hit(&self.intersect_world(&beyond)?, &beyond)?;
+
+        Ok(behind * transmittance + medium.get_color() * (1.0 - transmittance))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        color::Color,
+        intersections::Ray,
+        lights::{Material, PointLight},
+        matrix::{translation, Matrix},
+        shapes::Sphere,
+        spatial::{Point, Tuple, Vector},
+        world::{Background, World},
+    };
+    use anyhow::Result;
+
+    fn smoke_ball(density: f64) -> World {
+        let mut w = World::empty();
+        w.add_object(
+            Sphere::new(
+                Matrix::identity(),
+                Material::volume(density, Color::new(0.1, 0.1, 0.1)),
+            )
+            .unwrap(),
+        );
+        w.set_background(Background::Solid(Color::new(1, 1, 1)));
+        w
+    }
+
+    fn color_through(w: &World, x: f64) -> Result<Color> {
+        w.color_at(&Ray::new(Point::new(x, 0, -5), Vector::new(0, 0, 1)))
+    }
+
+    #[test]
+    fn a_medium_without_density_is_invisible() -> Result<()> {
+        let w = smoke_ball(0.0);
+
+        for x in [0.0, 0.5, 0.9] {
+            assert_eq!(color_through(&w, x)?, Color::new(1, 1, 1));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn a_dense_medium_is_darker_in_the_middle_than_at_the_limb() -> Result<()> {
+        let w = smoke_ball(1.5);
+
+        let middle = color_through(&w, 0.0)?;
+        let limb = color_through(&w, 0.95)?;
+
+        assert!(middle.red < limb.red);
+        assert!(limb.red < 1.0);
+
+        // two units of medium in the middle
+        let transmittance = (-3.0_f64).exp();
+        assert_eq!(
+            middle,
+            Color::new(1, 1, 1) * transmittance + Color::new(0.1, 0.1, 0.1) * (1.0 - transmittance)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn rays_starting_inside_the_medium_are_attenuated_from_their_origin() -> Result<()> {
+        let w = smoke_ball(1.0);
+        let from_center = Ray::new(Point::new(0, 0, 0), Vector::new(0, 0, 1));
+
+        let transmittance = (-1.0_f64).exp();
+        assert_eq!(
+            w.color_at(&from_center)?,
+            Color::new(1, 1, 1) * transmittance + Color::new(0.1, 0.1, 0.1) * (1.0 - transmittance)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn the_output_clamp_applies_after_the_medium() -> Result<()> {
+        let mut w = smoke_ball(1.5);
+        w.set_clamp_output(Some((0.0, 0.5)))?;
+
+        // the white background is only clamped once it has been dimmed
+        let transmittance = (-3.0_f64).exp();
+        let dimmed =
+            Color::new(1, 1, 1) * transmittance + Color::new(0.1, 0.1, 0.1) * (1.0 - transmittance);
+        assert!(dimmed.red < 0.5);
+        assert_eq!(color_through(&w, 0.0)?, dimmed);
+
+        // around the medium, the background itself is clamped
+        assert_eq!(color_through(&w, 2.0)?, Color::new(0.5, 0.5, 0.5));
+
+        Ok(())
+    }
+
+    /// A lit sphere at the origin, with a ball of the given medium
+    /// between it and the light
+    fn shaded_through(medium: Option<Material>) -> Result<World> {
+        let mut w = World::empty();
+        w.set_light(Some(PointLight::new(
+            Point::new(0, 0, -10),
+            Color::new(1, 1, 1),
+        )));
+        w.add_object(Sphere::default());
+        if let Some(material) = medium {
+            w.add_object(Sphere::new(translation(0, 0, -5), material)?);
+        }
+        Ok(w)
+    }
+
+    #[test]
+    fn media_dim_the_light_instead_of_casting_a_full_shadow() -> Result<()> {
+        let lit = Tuple::point(0, 0, -1);
+        let eye = Ray::new(Point::new(3, 0, -3), Vector::new(-3, 0, 2).normalize());
+
+        let clear = shaded_through(None)?;
+        let invisible = shaded_through(Some(Material::volume(0.0, Color::black())))?;
+        let smoke = shaded_through(Some(Material::volume(1.0, Color::black())))?;
+        let wall = shaded_through(Some(Material::default()))?;
+
+        // a medium without density changes nothing, even with a light
+        assert!(!invisible.is_shadowed(&lit)?);
+        assert_eq!(invisible.light_transmittance(&lit)?, 1.0);
+        assert_eq!(invisible.color_at(&eye)?, clear.color_at(&eye)?);
+
+        // two units of smoke let some of the light through
+        assert!(smoke.is_shadowed(&lit)?);
+        assert_eq!(smoke.light_transmittance(&lit)?, (-2.0_f64).exp());
+        let (dim, bright, dark) = (
+            smoke.color_at(&eye)?,
+            clear.color_at(&eye)?,
+            wall.color_at(&eye)?,
+        );
+        assert!(dark.red < dim.red && dim.red < bright.red);
+
+        assert_eq!(wall.light_transmittance(&lit)?, 0.0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn traces_of_media_match_their_color() -> Result<()> {
+        let w = smoke_ball(1.5);
+        let r = Ray::new(Point::new(0, 0, -5), Vector::new(0, 0, 1));
+
+        assert_eq!(w.color_at_traced(&r)?.color, w.color_at(&r)?);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,166 @@
+//! Loading and comparing the reference images for the golden tests
+
+use anyhow::{Error, Result};
+use raytracer::canvas::Canvas;
+use std::{fmt::Display, fs, path::PathBuf};
+
+/// The largest difference allowed in any channel of any pixel, out of
+/// 255. This absorbs floating point noise between platforms, while
+/// still catching shadow acne or a shift in color.
+pub const TOLERANCE: u8 = 2;
+
+/// An 8-bit image loaded from a binary PPM file
+pub struct Reference {
+    pub width: usize,
+    pub height: usize,
+    /// The red, green and blue channels of each pixel, row by row
+    pub channels: Vec<u8>,
+}
+
+/// The pixel that differs the most between a render and its reference
+pub struct WorstPixel {
+    pub x: usize,
+    pub y: usize,
+    pub rendered: (u8, u8, u8),
+    pub expected: (u8, u8, u8),
+    pub difference: u8,
+}
+
+impl Display for WorstPixel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "pixel ({}, {}) is {:?} but should be {:?}, a difference of {}",
+            self.x, self.y, self.rendered, self.expected, self.difference
+        )
+    }
+}
+
+/// Where the reference image for the scene called `name` is stored
+fn reference_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden/references")
+        .join(format!("{}.ppm", name))
+}
+
+/// Parses a binary (P6) PPM image with a maximum color value of 255,
+/// as written by [Canvas::to_ppm_binary]
+pub fn load(bytes: &[u8]) -> Result<Reference> {
+    let mut fields = Vec::with_capacity(4);
+    let mut start = 0;
+
+    // the magic number, width, height and maximum value are separated by
+    // whitespace, and a single whitespace byte comes before the pixels
+    for (i, byte) in bytes.iter().enumerate() {
+        if byte.is_ascii_whitespace() {
+            fields.push(std::str::from_utf8(&bytes[start..i])?);
+            start = i + 1;
+            if fields.len() == 4 {
+                break;
+            }
+        }
+    }
+
+    if fields.len() != 4 || fields[0] != "P6" || fields[3] != "255" {
+        return Err(Error::msg(
+            "Expected a binary PPM image with 8-bit channels",
+        ));
+    }
+
+    let width: usize = fields[1].parse()?;
+    let height: usize = fields[2].parse()?;
+    let channels = bytes[start..].to_vec();
+
+    if channels.len() != width * height * 3 {
+        return Err(Error::msg(format!(
+            "Expected {} bytes of pixels for a {}x{} image, found {}",
+            width * height * 3,
+            width,
+            height,
+            channels.len()
+        )));
+    }
+
+    Ok(Reference {
+        width,
+        height,
+        channels,
+    })
+}
+
+/// Finds the pixel of `image` that differs most from `reference`.
+/// Both must have the same dimensions.
+pub fn compare(image: &Canvas, reference: &Reference) -> Result<WorstPixel> {
+    if (image.width, image.height) != (reference.width, reference.height) {
+        return Err(Error::msg(format!(
+            "The render is {}x{} but the reference is {}x{}",
+            image.width, image.height, reference.width, reference.height
+        )));
+    }
+
+    let mut worst: Option<WorstPixel> = None;
+
+    for (x, y, color) in image.enumerate_pixels() {
+        let (r, g, b) = color.get_255_scaled_tuple();
+        let rendered = (r as u8, g as u8, b as u8);
+
+        let i = (y * reference.width + x) * 3;
+        let expected = match reference.channels[i..i + 3] {
+            [r, g, b] => (r, g, b),
+            _ => unreachable!("every pixel has three channels"),
+        };
+
+        let difference = [
+            rendered.0.abs_diff(expected.0),
+            rendered.1.abs_diff(expected.1),
+            rendered.2.abs_diff(expected.2),
+        ]
+        .into_iter()
+        .max()
+        .unwrap_or(0);
+
+        if worst.as_ref().is_none_or(|w| difference > w.difference) {
+            worst = Some(WorstPixel {
+                x,
+                y,
+                rendered,
+                expected,
+                difference,
+            });
+        }
+    }
+
+    worst.ok_or_else(|| Error::msg("Cannot compare empty images"))
+}
+
+/// Checks `image` against the reference image for the scene called
+/// `name`, failing if any channel is off by more than [TOLERANCE].
+///
+/// When the `UPDATE_GOLDEN` environment variable is `1`, the reference
+/// is overwritten with `image` instead.
+pub fn check_golden(name: &str, image: &Canvas) -> Result<()> {
+    let path = reference_path(name);
+
+    if std::env::var("UPDATE_GOLDEN").is_ok_and(|value| value == "1") {
+        fs::write(&path, image.to_ppm_binary())?;
+        return Ok(());
+    }
+
+    let bytes = fs::read(&path).map_err(|e| {
+        Error::msg(format!(
+            "Could not read {}: {}. Run with UPDATE_GOLDEN=1 to create it",
+            path.display(),
+            e
+        ))
+    })?;
+
+    let worst = compare(image, &load(&bytes)?)?;
+    if worst.difference > TOLERANCE {
+        return Err(Error::msg(format!(
+            "The render of {} does not match its reference: {}",
+            name, worst
+        )));
+    }
+
+    Ok(())
+}
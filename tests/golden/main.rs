@@ -0,0 +1,40 @@
+//! Golden image tests: small renders of the gallery scenes, compared
+//! against the reference images in `references/`.
+//!
+//! After a change that is meant to alter the output, regenerate the
+//! references with `UPDATE_GOLDEN=1 cargo test --features examples-scenes
+//! --test golden` and check the new images before committing them.
+
+mod harness;
+
+use anyhow::{Error, Result};
+use raytracer::{canvas::Canvas, scenes};
+
+/// Width and height of every golden render
+const SIZE: usize = 32;
+
+/// Renders the gallery scene called `name` at [SIZE] x [SIZE] pixels
+fn render_small(name: &str) -> Result<Canvas> {
+    let scene = scenes::by_name(name)
+        .ok_or_else(|| Error::msg(format!("There is no scene called {}", name)))?;
+    let (world, mut camera) = scene()?;
+
+    camera.set_hsize(SIZE)?;
+    camera.set_vsize(SIZE)?;
+    camera.render(&world)
+}
+
+#[test]
+fn chapter7_matches_its_reference() -> Result<()> {
+    harness::check_golden("chapter7", &render_small("chapter7")?)
+}
+
+#[test]
+fn default_world_matches_its_reference() -> Result<()> {
+    harness::check_golden("default-world", &render_small("default-world")?)
+}
+
+#[test]
+fn sphere_grid_matches_its_reference() -> Result<()> {
+    harness::check_golden("sphere-grid", &render_small("sphere-grid")?)
+}